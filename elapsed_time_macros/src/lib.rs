@@ -0,0 +1,53 @@
+//! Attribute macros for the `elapsed_time` crate.
+//!
+//! Use them through `elapsed_time` with its `macros` feature rather than depending on this
+//! crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, ItemFn, LitStr};
+
+/// Times every call of the annotated function and reports its name and elapsed time to
+/// the global `MeasurementSink` when it returns.
+///
+/// The default sink prints `"<name>: <elapsed>"` to stderr; install another with
+/// `elapsed_time::sink::set_sink` to send the reports elsewhere.
+///
+/// The time is reported however the function exits, including early `return`s, `?` and
+/// panics. On an `async fn` the clock starts when the future is first polled and stops when
+/// it completes. Pass a string to report under a different label: `#[timed("load config")]`.
+///
+/// # Example
+///
+/// ```ignore
+/// use elapsed_time::timed;
+///
+/// #[timed]
+/// fn load_config(path: &str) -> std::io::Result<String> {
+///     std::fs::read_to_string(path)
+/// }
+/// // With the default sink, prints e.g. "load_config: 0.002s" after each call.
+/// ```
+#[proc_macro_attribute]
+pub fn timed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let label = if attr.is_empty() {
+        None
+    } else {
+        Some(parse_macro_input!(attr as LitStr))
+    };
+    let mut function = parse_macro_input!(item as ItemFn);
+
+    let label = match label {
+        Some(label) => label.value(),
+        None => function.sig.ident.to_string(),
+    };
+    let body = &function.block;
+    let block = quote! {
+        {
+            let __elapsed_time_guard = ::elapsed_time::__private::TimedGuard::new(#label);
+            #body
+        }
+    };
+    *function.block = syn::parse2(block).expect("generated block is valid");
+    quote!(#function).into()
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// What a [`CompletionAlert`] does when it fires.
+#[derive(Clone)]
+enum AlertAction {
+    /// Ring the terminal bell on stderr.
+    Bell,
+    /// Call a user-supplied hook with the elapsed time.
+    Hook(Arc<dyn Fn(Duration) + Send + Sync>),
+}
+
+/// An alert raised when a measured operation takes longer than a threshold to finish.
+///
+/// Use [`bell`](CompletionAlert::bell) to ring the terminal bell, which most terminals
+/// turn into a sound or a taskbar flash, or [`hook`](CompletionAlert::hook) to run your
+/// own code (play a sound, write a log line, ...).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::CompletionAlert;
+///
+/// let alert = CompletionAlert::bell(Duration::from_secs(30));
+/// let (output, elapsed) = alert.measure(|| "compiled");
+/// // Finished well under 30 seconds, so the bell stayed quiet.
+/// assert_eq!(output, "compiled");
+/// ```
+#[derive(Clone)]
+pub struct CompletionAlert {
+    threshold: Duration,
+    action: AlertAction,
+}
+
+impl CompletionAlert {
+    /// Rings the terminal bell for operations taking longer than `threshold`.
+    pub fn bell(threshold: Duration) -> Self {
+        CompletionAlert {
+            threshold,
+            action: AlertAction::Bell,
+        }
+    }
+
+    /// Calls `hook` with the elapsed time for operations taking longer than `threshold`.
+    pub fn hook<H>(threshold: Duration, hook: H) -> Self
+    where
+        H: Fn(Duration) + Send + Sync + 'static,
+    {
+        CompletionAlert {
+            threshold,
+            action: AlertAction::Hook(Arc::new(hook)),
+        }
+    }
+
+    /// Returns the elapsed time an operation must exceed to fire the alert.
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Fires the alert if `elapsed` exceeds the threshold.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the alert fired.
+    pub fn check(&self, elapsed: Duration) -> bool {
+        if elapsed <= self.threshold {
+            return false;
+        }
+        match &self.action {
+            AlertAction::Bell => {
+                let mut stderr = io::stderr();
+                let _ = stderr.write_all(b"\x07");
+                let _ = stderr.flush();
+            }
+            AlertAction::Hook(hook) => hook(elapsed),
+        }
+        true
+    }
+
+    /// Runs a closure, then fires the alert if it took longer than the threshold.
+    ///
+    /// # Returns
+    ///
+    /// The closure's return value and the formatted elapsed time.
+    pub fn measure<F, T>(&self, f: F) -> (T, String)
+    where
+        F: FnOnce() -> T,
+    {
+        let start = Instant::now();
+        let value = f();
+        let elapsed = start.elapsed();
+        self.check(elapsed);
+        (value, format_duration(elapsed))
+    }
+}
+
+impl fmt::Debug for CompletionAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.action {
+            AlertAction::Bell => "Bell",
+            AlertAction::Hook(_) => "Hook",
+        };
+        f.debug_struct("CompletionAlert")
+            .field("threshold", &self.threshold)
+            .field("action", &action)
+            .finish()
+    }
+}
+
+/// Runs a closure and rings the terminal bell if it took longer than `threshold`.
+///
+/// Shorthand for `CompletionAlert::bell(threshold).measure(f)`.
+///
+/// # Arguments
+///
+/// * `threshold` - The elapsed time a run must exceed to ring the bell.
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// The closure's return value and the formatted elapsed time.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use elapsed_time::measure_with_bell;
+///
+/// let (_, elapsed) = measure_with_bell(Duration::from_secs(10), || run_test_suite());
+/// println!("tests took {}", elapsed);
+/// # fn run_test_suite() {}
+/// ```
+pub fn measure_with_bell<F, T>(threshold: Duration, f: F) -> (T, String)
+where
+    F: FnOnce() -> T,
+{
+    CompletionAlert::bell(threshold).measure(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_hook_fires_only_over_threshold() {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&fired);
+        let alert = CompletionAlert::hook(Duration::from_secs(5), move |elapsed| {
+            sink.lock().unwrap().push(elapsed);
+        });
+
+        assert!(!alert.check(Duration::from_secs(4)));
+        assert!(!alert.check(Duration::from_secs(5)));
+        assert!(alert.check(Duration::from_secs(6)));
+        assert_eq!(*fired.lock().unwrap(), [Duration::from_secs(6)]);
+    }
+
+    #[test]
+    fn test_measure_returns_value() {
+        let alert = CompletionAlert::hook(Duration::ZERO, |_| {});
+        let (value, _) = alert.measure(|| 3 * 7);
+        assert_eq!(value, 21);
+        assert_eq!(format!("{:?}", alert), "CompletionAlert { threshold: 0ns, action: \"Hook\" }");
+    }
+}
@@ -0,0 +1,571 @@
+//! The `elapsed` command-line tool, built with the `cli` feature.
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::process::{Command, ExitCode, ExitStatus};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use elapsed_time::diff::Comparison;
+use elapsed_time::{
+    elapsed_between, format_duration, measure_command, sparkline, CompletionAlert,
+    DurationFormatter, LapTimer, TimestampFormat,
+};
+use elapsed_time::logtail::LogPairer;
+use regex::Regex;
+use serde_json::Value;
+
+const USAGE: &str = "\
+usage: elapsed <command> [args]
+
+commands:
+  run [--bell SECS] -- COMMAND [ARGS...]
+      Run COMMAND, passing its output and exit status through, and print
+      its wall and CPU time to stderr when it exits. With --bell, ring
+      the terminal bell if it took longer than SECS seconds.
+
+  stopwatch
+      Show a live stopwatch. Enter records a lap; Ctrl-C or end of input
+      stops it and prints a table of the laps.
+
+  diff [--threshold PCT] OLD.json NEW.json
+      Compare two report files by label and flag timings that got slower
+      than PCT percent (default 5). Exits with status 1 on regressions.
+
+  watch [-n SECS] [--count N] -- COMMAND [ARGS...]
+      Run COMMAND every SECS seconds (default 2), N times or until
+      interrupted, printing a rolling timing summary after each run.
+
+  logtail --start REGEX --end REGEX [FILE]
+      Read FILE (or stdin, e.g. from `tail -f`) and print the time between
+      each start line and its end line. Lines are paired by the capture
+      group named `id`, or the first group, if the patterns have one.
+
+  between [--format FORMAT] A B
+      Print the time from timestamp A to timestamp B. FORMAT is one of
+      auto (default), rfc3339, datetime, clf, syslog or clock.";
+
+/// Number of most recent runs drawn in the `watch` sparkline.
+const SPARKLINE_WIDTH: usize = 30;
+
+/// How often the live `stopwatch` display is redrawn.
+const STOPWATCH_REFRESH: Duration = Duration::from_millis(100);
+
+/// Exit status for usage and input errors, distinct from "regressions found".
+const EXIT_USAGE: u8 = 2;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("stopwatch") => stopwatch(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("watch") => watch(&args[1..]),
+        Some("logtail") => logtail(&args[1..]),
+        Some("between") => between(&args[1..]),
+        Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(other) => Err(format!("unknown command \"{}\"\n\n{}", other, USAGE)),
+        None => Err(USAGE.to_string()),
+    };
+    result.unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        ExitCode::from(EXIT_USAGE)
+    })
+}
+
+/// Parses a number of seconds given to `flag`, such as `-n 0.5`.
+fn parse_secs(flag: &str, value: Option<&String>) -> Result<Duration, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("invalid {} value \"{}\"", flag, value))
+}
+
+/// Returns the exit code that passes `status` on: the child's own code, or 128 plus the
+/// signal number if a signal killed it, as shells report.
+fn exit_code(status: ExitStatus) -> ExitCode {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return ExitCode::from(128u8.saturating_add(signal as u8));
+    }
+    match status.code() {
+        Some(code) => ExitCode::from(u8::try_from(code).unwrap_or(1)),
+        None => ExitCode::FAILURE,
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut bell = None;
+    let mut args = args.iter();
+    let command: Vec<&String> = loop {
+        match args.next().map(String::as_str) {
+            Some("--bell") => {
+                bell = Some(CompletionAlert::bell(parse_secs("--bell", args.next())?))
+            }
+            Some("--") => break args.collect(),
+            _ => return Err(USAGE.to_string()),
+        }
+    };
+    let (program, program_args) = command.split_first().ok_or(USAGE)?;
+
+    // Ctrl-C reaches the child through the terminal; stay alive to report its time.
+    ctrlc::set_handler(|| {}).map_err(|e| e.to_string())?;
+    let timing = measure_command(Command::new(program).args(program_args))
+        .map_err(|e| format!("{}: {}", program, e))?;
+    eprintln!("{}", timing);
+    if let Some(bell) = bell {
+        bell.check(timing.wall);
+    }
+    Ok(exit_code(timing.status))
+}
+
+/// Input to the `stopwatch` loop.
+enum StopwatchEvent {
+    Lap,
+    Stop,
+}
+
+fn stopwatch(args: &[String]) -> Result<ExitCode, String> {
+    if !args.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let (events, received) = mpsc::channel();
+    let stop = events.clone();
+    ctrlc::set_handler(move || {
+        let _ = stop.send(StopwatchEvent::Stop);
+    })
+    .map_err(|e| e.to_string())?;
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            if line.is_err() || events.send(StopwatchEvent::Lap).is_err() {
+                return;
+            }
+        }
+        let _ = events.send(StopwatchEvent::Stop);
+    });
+
+    eprintln!("Enter records a lap, Ctrl-C stops.");
+    let animate = io::stderr().is_terminal();
+    let timer = run_stopwatch(&received, &mut io::stderr(), animate, STOPWATCH_REFRESH)
+        .map_err(|e| e.to_string())?;
+    println!("{}", timer);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Times laps until a stop event, reporting each lap on `out` and, if `animate`, redrawing
+/// a live `"1m 2.3s  lap 3: 12.1s"` line every `refresh`.
+///
+/// The time since the last lap is recorded as a final lap when the stopwatch stops.
+fn run_stopwatch<W: Write>(
+    events: &Receiver<StopwatchEvent>,
+    out: &mut W,
+    animate: bool,
+    refresh: Duration,
+) -> io::Result<LapTimer> {
+    let live = DurationFormatter::new().precision(1);
+    let mut timer = LapTimer::start();
+    let mut laps = 0;
+    let mut laps_total = Duration::ZERO;
+    loop {
+        match events.recv_timeout(refresh) {
+            Ok(StopwatchEvent::Lap) => {
+                laps += 1;
+                let lap = timer.lap(&format!("lap {}", laps));
+                laps_total += lap;
+                if animate {
+                    // The echoed Enter left the live line above the cursor; replace it.
+                    write!(out, "\x1b[1A\r\x1b[2K")?;
+                }
+                writeln!(
+                    out,
+                    "lap {}: {} (total {})",
+                    laps,
+                    format_duration(lap),
+                    format_duration(laps_total)
+                )?;
+            }
+            Ok(StopwatchEvent::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+        if animate {
+            let total = timer.total();
+            write!(
+                out,
+                "\r\x1b[2K{}  lap {}: {}",
+                live.format(total),
+                laps + 1,
+                live.format(total.saturating_sub(laps_total))
+            )?;
+            out.flush()?;
+        }
+    }
+    if animate {
+        write!(out, "\r\x1b[2K")?;
+    }
+    // Pausing first makes the final lap end exactly where the total does.
+    timer.pause();
+    timer.lap(&format!("lap {}", laps + 1));
+    Ok(timer)
+}
+
+/// Reads the nanosecond timing of one report entry.
+///
+/// Besides plain numbers and `*_ns` fields, this reads the crate's own
+/// `{"nanos": ..., "formatted": ...}` duration objects, directly or under a `duration`,
+/// `mean` or `total` field, so `timers::report_json()` output can be diffed as is.
+fn entry_duration(value: &Value) -> Option<Duration> {
+    match value {
+        Value::Number(n) => n.as_u64().map(Duration::from_nanos),
+        Value::Object(fields) => match fields.get("nanos") {
+            Some(nanos) => nanos.as_u64().map(Duration::from_nanos),
+            None => ["duration_ns", "duration", "mean_ns", "mean", "total_ns", "total"]
+                .iter()
+                .find_map(|key| entry_duration(fields.get(*key)?)),
+        },
+        _ => None,
+    }
+}
+
+/// Parses a report file into `(label, duration)` pairs.
+///
+/// Accepts either an array of objects with a `label` and a timing, or an object mapping
+/// labels to timings. A timing is a nanosecond count, a `duration_ns` (or `mean_ns` /
+/// `total_ns`) field, or a `{"nanos": ...}` object as written by the crate's JSON exports.
+fn parse_report(text: &str) -> Result<Vec<(String, Duration)>, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    match value {
+        Value::Array(entries) => entries
+            .iter()
+            .map(|entry| {
+                let label = entry
+                    .get("label")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("entry without a \"label\": {}", entry))?;
+                let duration = entry_duration(entry)
+                    .ok_or_else(|| format!("entry \"{}\" has no duration_ns", label))?;
+                Ok((label.to_string(), duration))
+            })
+            .collect(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(label, entry)| {
+                let duration = entry_duration(entry)
+                    .ok_or_else(|| format!("entry \"{}\" has no duration_ns", label))?;
+                Ok((label.clone(), duration))
+            })
+            .collect(),
+        _ => Err("expected a JSON array or object".to_string()),
+    }
+}
+
+fn load_report(path: &str) -> Result<Vec<(String, Duration)>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    parse_report(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn diff(args: &[String]) -> Result<ExitCode, String> {
+    let mut threshold_pct = 5.0;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threshold" => {
+                let value = args.next().ok_or("--threshold needs a value")?;
+                threshold_pct = value
+                    .trim_end_matches('%')
+                    .parse()
+                    .map_err(|_| format!("invalid threshold \"{}\"", value))?;
+            }
+            option if option.starts_with('-') => {
+                return Err(format!("unknown option \"{}\"\n\n{}", option, USAGE))
+            }
+            _ => paths.push(arg.as_str()),
+        }
+    }
+    let [old, new] = paths[..] else {
+        return Err(USAGE.to_string());
+    };
+
+    let comparison = Comparison::new(load_report(old)?, load_report(new)?, threshold_pct / 100.0);
+    let mut table = comparison.to_string();
+    if io::stdout().is_terminal() {
+        table = table.replace("REGRESSION", "\x1b[31mREGRESSION\x1b[0m");
+    }
+    println!("{}", table);
+
+    Ok(if comparison.regressions().next().is_some() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Summarizes all runs so far as `"run 3: last 1.2s  mean ...  min ...  max ...  ▁▅█"`.
+fn watch_summary(runs: &[Duration]) -> String {
+    let last = runs.last().copied().unwrap_or_default();
+    let total: Duration = runs.iter().sum();
+    let mean = total / runs.len().max(1) as u32;
+    let min = runs.iter().min().copied().unwrap_or_default();
+    let max = runs.iter().max().copied().unwrap_or_default();
+    let recent = &runs[runs.len().saturating_sub(SPARKLINE_WIDTH)..];
+    format!(
+        "run {}: last {}  mean {}  min {}  max {}  {}",
+        runs.len(),
+        format_duration(last),
+        format_duration(mean),
+        format_duration(min),
+        format_duration(max),
+        sparkline(recent)
+    )
+}
+
+fn watch(args: &[String]) -> Result<ExitCode, String> {
+    let mut interval = Duration::from_secs(2);
+    let mut count = None;
+    let mut args = args.iter();
+    let command: Vec<&String> = loop {
+        match args.next().map(String::as_str) {
+            Some("-n") => interval = parse_secs("-n", args.next())?,
+            Some("--count") => {
+                let value = args.next().ok_or("--count needs a value")?;
+                count = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid count \"{}\"", value))?,
+                );
+            }
+            Some("--") => break args.collect(),
+            _ => return Err(USAGE.to_string()),
+        }
+    };
+    let (program, program_args) = command.split_first().ok_or(USAGE)?;
+
+    let mut runs = Vec::new();
+    loop {
+        let start = Instant::now();
+        let status = Command::new(program)
+            .args(program_args)
+            .status()
+            .map_err(|e| format!("{}: {}", program, e))?;
+        runs.push(start.elapsed());
+
+        let mut summary = watch_summary(&runs);
+        if !status.success() {
+            summary.push_str(&format!("  ({})", status));
+        }
+        eprintln!("{}", summary);
+
+        if count.is_some_and(|count| runs.len() >= count) {
+            return Ok(ExitCode::SUCCESS);
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn logtail(args: &[String]) -> Result<ExitCode, String> {
+    let mut start = None;
+    let mut end = None;
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            flag @ ("--start" | "--end") => {
+                let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+                let regex =
+                    Regex::new(value).map_err(|e| format!("invalid {} pattern: {}", flag, e))?;
+                if flag == "--start" {
+                    start = Some(regex);
+                } else {
+                    end = Some(regex);
+                }
+            }
+            option if option.starts_with('-') => {
+                return Err(format!("unknown option \"{}\"\n\n{}", option, USAGE))
+            }
+            _ if path.is_none() => path = Some(arg.as_str()),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(USAGE.to_string());
+    };
+
+    let mut pairer = LogPairer::new(start, end);
+    let print = |span| println!("{}", span);
+    let result = match path {
+        Some(path) => {
+            let file = fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+            pairer.follow(BufReader::new(file), print)
+        }
+        None => pairer.follow(io::stdin().lock(), print),
+    };
+    result.map_err(|e| e.to_string())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn between(args: &[String]) -> Result<ExitCode, String> {
+    let mut format = TimestampFormat::Auto;
+    let mut timestamps = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = args.next().ok_or("--format needs a value")?;
+                format = match value.as_str() {
+                    "auto" => TimestampFormat::Auto,
+                    "rfc3339" => TimestampFormat::Rfc3339,
+                    "datetime" => TimestampFormat::DateTime,
+                    "clf" => TimestampFormat::Clf,
+                    "syslog" => TimestampFormat::Syslog,
+                    "clock" => TimestampFormat::Clock,
+                    _ => return Err(format!("unknown format \"{}\"", value)),
+                };
+            }
+            option if option.starts_with('-') => {
+                return Err(format!("unknown option \"{}\"\n\n{}", option, USAGE))
+            }
+            _ => timestamps.push(arg.as_str()),
+        }
+    }
+    let [a, b] = timestamps[..] else {
+        return Err(USAGE.to_string());
+    };
+    println!("{}", elapsed_between(a, b, format).map_err(|e| e.to_string())?);
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secs() {
+        let value = "1.5".to_string();
+        assert_eq!(parse_secs("-n", Some(&value)), Ok(Duration::from_millis(1500)));
+        let value = "-1".to_string();
+        assert_eq!(
+            parse_secs("--bell", Some(&value)).unwrap_err(),
+            "invalid --bell value \"-1\""
+        );
+        assert_eq!(parse_secs("-n", None).unwrap_err(), "-n needs a value");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_passes_status_on() {
+        use std::os::unix::process::ExitStatusExt;
+
+        assert_eq!(exit_code(ExitStatus::from_raw(0)), ExitCode::SUCCESS);
+        assert_eq!(exit_code(ExitStatus::from_raw(3 << 8)), ExitCode::from(3));
+        // Killed by SIGKILL (9).
+        assert_eq!(exit_code(ExitStatus::from_raw(9)), ExitCode::from(137));
+    }
+
+    #[test]
+    fn test_run_stopwatch() {
+        let (events, received) = mpsc::channel();
+        let mut out = Vec::new();
+        let refresh = Duration::from_millis(1);
+
+        events.send(StopwatchEvent::Lap).unwrap();
+        events.send(StopwatchEvent::Stop).unwrap();
+        let timer = run_stopwatch(&received, &mut out, false, refresh).unwrap();
+        let laps = timer.laps();
+        assert_eq!(laps.iter().map(|lap| lap.0).collect::<Vec<_>>(), ["lap 1", "lap 2"]);
+        assert_eq!(laps.iter().map(|lap| lap.1).sum::<Duration>(), timer.total());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("lap 1: ") && out.lines().count() == 1, "{:?}", out);
+        assert!(timer.to_string().ends_with(&format!("total  {}", format_duration(timer.total()))));
+
+        drop(events);
+        let mut out = Vec::new();
+        let timer = run_stopwatch(&received, &mut out, true, refresh).unwrap();
+        assert_eq!(timer.laps().len(), 1);
+        assert!(String::from_utf8(out).unwrap().ends_with("\r\x1b[2K"));
+    }
+
+    #[test]
+    fn test_parse_report_shapes() {
+        let expected = vec![
+            ("parse".to_string(), Duration::from_millis(1200)),
+            ("db".to_string(), Duration::from_secs(2)),
+        ];
+        let array = r#"[{"label":"parse","duration_ns":1200000000,"formatted":"1.200s"},
+                        {"label":"db","mean_ns":2000000000}]"#;
+        assert_eq!(parse_report(array).unwrap(), expected);
+
+        let object = r#"{"parse":1200000000,"db":{"duration_ns":2000000000}}"#;
+        assert_eq!(parse_report(object).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_report_reads_crate_exports() {
+        use elapsed_time::{timers, Measurement};
+
+        timers::reset();
+        timers::record("parse", Duration::from_millis(1000));
+        timers::record("parse", Duration::from_millis(1400));
+        assert_eq!(
+            parse_report(&timers::report_json()).unwrap(),
+            [("parse".to_string(), Duration::from_millis(1200))]
+        );
+        timers::reset();
+
+        let measurement = Measurement::new(Duration::from_millis(1500));
+        let object = format!(r#"{{"build":{}}}"#, measurement.to_json());
+        assert_eq!(
+            parse_report(&object).unwrap(),
+            [("build".to_string(), Duration::from_millis(1500))]
+        );
+    }
+
+    #[test]
+    fn test_parse_report_errors() {
+        assert!(parse_report("42").is_err());
+        assert!(parse_report(r#"[{"duration_ns":1}]"#).unwrap_err().contains("label"));
+        assert!(parse_report(r#"{"x":"soon"}"#).unwrap_err().contains("\"x\""));
+    }
+
+    #[test]
+    fn test_diff_rejects_unknown_options() {
+        let args: Vec<String> = ["--treshold", "10", "old.json", "new.json"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert!(diff(&args).unwrap_err().starts_with("unknown option \"--treshold\""));
+    }
+
+    #[test]
+    fn test_logtail_rejects_unknown_options() {
+        let args: Vec<String> = ["--start", "a", "--end", "b", "--bogus"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert!(logtail(&args).unwrap_err().starts_with("unknown option \"--bogus\""));
+    }
+
+    #[test]
+    fn test_between_rejects_unknown_options() {
+        let args: Vec<String> = ["--fromat", "rfc3339", "10:00:00", "10:05:00"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert!(between(&args).unwrap_err().starts_with("unknown option \"--fromat\""));
+    }
+
+    #[test]
+    fn test_watch_summary() {
+        let runs = [Duration::from_secs(1), Duration::from_secs(3), Duration::from_secs(2)];
+        assert_eq!(
+            watch_summary(&runs),
+            "run 3: last 2s  mean 2s  min 1s  max 3s  ▁█▅"
+        );
+    }
+}
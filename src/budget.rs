@@ -0,0 +1,342 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::{format_duration, Deadline};
+
+/// An error returned by [`Budget::consume`] once a stage overruns the allowance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetExhausted {
+    /// The whole allowance.
+    pub total: Duration,
+    /// Time spent by all stages, including the one that overran.
+    pub spent: Duration,
+}
+
+impl BudgetExhausted {
+    /// Returns how far over the allowance the stages went.
+    pub fn overrun(&self) -> Duration {
+        self.spent.saturating_sub(self.total)
+    }
+}
+
+impl fmt::Display for BudgetExhausted {
+    /// Renders `"budget of 2s exhausted: spent 2.300s (0.300s over)"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "budget of {} exhausted: spent {} ({} over)",
+            format_duration(self.total),
+            format_duration(self.spent),
+            format_duration(self.overrun())
+        )
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+/// A time allowance shared by the stages of a request.
+///
+/// Each stage reports how long it took with [`consume`](Budget::consume), which fails once
+/// the allowance is used up. What is left can be formatted for logs or turned into a
+/// [`Deadline`] to pass on to the next call.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::Budget;
+///
+/// let mut budget = Budget::new(Duration::from_secs(2));
+/// budget.consume(Duration::from_millis(1500)).unwrap();
+/// assert_eq!(budget.format_remaining(), "0.500s");
+///
+/// let err = budget.consume(Duration::from_millis(800)).unwrap_err();
+/// assert_eq!(err.to_string(), "budget of 2s exhausted: spent 2.300s (0.300s over)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    total: Duration,
+    spent: Duration,
+}
+
+impl Budget {
+    /// Creates a budget with `total` to spend.
+    pub fn new(total: Duration) -> Self {
+        Budget {
+            total,
+            spent: Duration::ZERO,
+        }
+    }
+
+    /// Records that a stage took `elapsed`.
+    ///
+    /// # Returns
+    ///
+    /// The time remaining afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BudgetExhausted` if the stages have now spent more than the allowance. The
+    /// time is recorded either way.
+    pub fn consume(&mut self, elapsed: Duration) -> Result<Duration, BudgetExhausted> {
+        self.spent = self.spent.saturating_add(elapsed);
+        if self.spent > self.total {
+            return Err(BudgetExhausted {
+                total: self.total,
+                spent: self.spent,
+            });
+        }
+        Ok(self.remaining())
+    }
+
+    /// Runs `f` as one stage, consuming the time it takes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BudgetExhausted` if the stage overran the budget; its result is discarded.
+    pub fn time<T, F>(&mut self, f: F) -> Result<T, BudgetExhausted>
+    where
+        F: FnOnce() -> T,
+    {
+        let start = Instant::now();
+        let result = f();
+        self.consume(start.elapsed())?;
+        Ok(result)
+    }
+
+    /// Returns the whole allowance.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Returns the time spent by all stages so far.
+    pub fn spent(&self) -> Duration {
+        self.spent
+    }
+
+    /// Returns the time left, or zero once the budget is exhausted.
+    pub fn remaining(&self) -> Duration {
+        self.total.saturating_sub(self.spent)
+    }
+
+    /// Returns `true` once the stages have spent the whole allowance.
+    pub fn is_exhausted(&self) -> bool {
+        self.spent >= self.total
+    }
+
+    /// Returns the time left formatted with [`format_duration`].
+    pub fn format_remaining(&self) -> String {
+        format_duration(self.remaining())
+    }
+
+    /// Returns a deadline the remaining budget from now, to hand to a downstream call.
+    pub fn deadline(&self) -> Deadline {
+        Deadline::after(self.remaining())
+    }
+}
+
+impl fmt::Display for Budget {
+    /// Renders `"0.500s of 2s left"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} left",
+            self.format_remaining(),
+            format_duration(self.total)
+        )
+    }
+}
+
+/// Whether a closure run with [`measure_with_budget`] finished within its time budget.
+///
+/// Both variants carry the closure's value, how long it took and the budget. Its `Display`
+/// implementation renders `"1.200s, within 5s budget"` or `"exceeded 5s budget by 1.200s"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetResult<T> {
+    /// The closure took no longer than the budget.
+    Within {
+        value: T,
+        elapsed: Duration,
+        budget: Duration,
+    },
+    /// The closure took longer than the budget.
+    Exceeded {
+        value: T,
+        elapsed: Duration,
+        budget: Duration,
+    },
+}
+
+impl<T> BudgetResult<T> {
+    /// Classifies a run that took `elapsed` against `budget`.
+    pub fn new(value: T, elapsed: Duration, budget: Duration) -> Self {
+        if elapsed > budget {
+            BudgetResult::Exceeded {
+                value,
+                elapsed,
+                budget,
+            }
+        } else {
+            BudgetResult::Within {
+                value,
+                elapsed,
+                budget,
+            }
+        }
+    }
+
+    /// Returns `true` if the closure finished within the budget.
+    pub fn is_within(&self) -> bool {
+        matches!(self, BudgetResult::Within { .. })
+    }
+
+    /// Returns how long the closure took.
+    pub fn elapsed(&self) -> Duration {
+        match self {
+            BudgetResult::Within { elapsed, .. } | BudgetResult::Exceeded { elapsed, .. } => {
+                *elapsed
+            }
+        }
+    }
+
+    /// Returns the budget the closure was measured against.
+    pub fn budget(&self) -> Duration {
+        match self {
+            BudgetResult::Within { budget, .. } | BudgetResult::Exceeded { budget, .. } => *budget,
+        }
+    }
+
+    /// Returns how far over the budget the closure went, or zero if it was within it.
+    pub fn overshoot(&self) -> Duration {
+        self.elapsed().saturating_sub(self.budget())
+    }
+
+    /// Returns the closure's value.
+    pub fn value(&self) -> &T {
+        match self {
+            BudgetResult::Within { value, .. } | BudgetResult::Exceeded { value, .. } => value,
+        }
+    }
+
+    /// Returns the closure's value, discarding the timing.
+    pub fn into_value(self) -> T {
+        match self {
+            BudgetResult::Within { value, .. } | BudgetResult::Exceeded { value, .. } => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for BudgetResult<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetResult::Within {
+                elapsed, budget, ..
+            } => write!(
+                f,
+                "{}, within {} budget",
+                format_duration(*elapsed),
+                format_duration(*budget)
+            ),
+            BudgetResult::Exceeded { budget, .. } => write!(
+                f,
+                "exceeded {} budget by {}",
+                format_duration(*budget),
+                format_duration(self.overshoot())
+            ),
+        }
+    }
+}
+
+/// Runs a closure and reports whether it finished within `budget`, for SLA checks and frame
+/// budgets.
+///
+/// The closure always runs to completion; use a [`Deadline`] to stop work early instead.
+///
+/// # Arguments
+///
+/// * `budget` - The time the closure is allowed to take.
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// A [`BudgetResult`] with the closure's value and timing.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{measure_with_budget, BudgetResult};
+///
+/// match measure_with_budget(Duration::from_millis(16), || render_frame()) {
+///     BudgetResult::Within { .. } => {}
+///     exceeded => eprintln!("frame {}", exceeded), // "frame exceeded 0.016s budget by 0.004s"
+/// }
+/// # fn render_frame() {}
+/// ```
+pub fn measure_with_budget<F, T>(budget: Duration, f: F) -> BudgetResult<T>
+where
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+    let value = f();
+    BudgetResult::new(value, start.elapsed(), budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consume_until_exhausted() {
+        let mut budget = Budget::new(Duration::from_secs(10));
+        assert_eq!(budget.consume(Duration::from_secs(4)), Ok(Duration::from_secs(6)));
+        assert_eq!(budget.to_string(), "6s of 10s left");
+        assert_eq!(budget.consume(Duration::from_secs(6)), Ok(Duration::ZERO));
+        assert!(budget.is_exhausted());
+
+        let err = budget.consume(Duration::from_secs(1)).unwrap_err();
+        assert_eq!(err.overrun(), Duration::from_secs(1));
+        assert_eq!(budget.spent(), Duration::from_secs(11));
+        assert_eq!(budget.remaining(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_time_and_deadline() {
+        let mut budget = Budget::new(Duration::from_secs(60));
+        assert_eq!(budget.time(|| 42), Ok(42));
+        assert!(budget.remaining() < Duration::from_secs(60));
+        assert!(!budget.deadline().is_expired());
+        assert!(!Budget::new(Duration::MAX).deadline().is_expired());
+
+        let mut empty = Budget::new(Duration::ZERO);
+        assert!(empty.deadline().is_expired());
+        assert!(empty.time(|| std::thread::sleep(Duration::from_millis(1))).is_err());
+    }
+
+    #[test]
+    fn test_budget_result() {
+        let budget = Duration::from_secs(5);
+        let within = BudgetResult::new("ok", Duration::from_millis(1_200), budget);
+        assert!(within.is_within());
+        assert_eq!(within.overshoot(), Duration::ZERO);
+        assert_eq!(within.to_string(), "1.200s, within 5s budget");
+        assert!(BudgetResult::new((), budget, budget).is_within());
+
+        let exceeded = BudgetResult::new(7, Duration::from_millis(6_200), budget);
+        assert!(!exceeded.is_within());
+        assert_eq!(exceeded.overshoot(), Duration::from_millis(1_200));
+        assert_eq!(exceeded.to_string(), "exceeded 5s budget by 1.200s");
+        assert_eq!(exceeded.into_value(), 7);
+    }
+
+    #[test]
+    fn test_measure_with_budget() {
+        let result = measure_with_budget(Duration::from_secs(60), || 42);
+        assert!(result.is_within());
+        assert_eq!(*result.value(), 42);
+        let result = measure_with_budget(Duration::ZERO, || {
+            std::thread::sleep(Duration::from_millis(1))
+        });
+        assert!(matches!(result, BudgetResult::Exceeded { .. }));
+    }
+}
@@ -0,0 +1,215 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::format_duration;
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A day of the week, for choosing which days [`BusinessHours`] counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// Returns the weekday of a day counted from 1970-01-01, which was a Thursday.
+    fn from_epoch_day(day: i64) -> Weekday {
+        const DAYS: [Weekday; 7] = [
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+            Weekday::Saturday,
+            Weekday::Sunday,
+        ];
+        DAYS[(day + 3).rem_euclid(7) as usize]
+    }
+}
+
+/// A weekly working schedule for measuring elapsed time in working hours only.
+///
+/// SLA reports usually count only the time a team is working: a ticket opened on Friday
+/// at 16:00 and answered on Monday at 10:00 took two working hours on a 9–17 schedule,
+/// not 66 calendar hours.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use elapsed_time::BusinessHours;
+///
+/// let schedule = BusinessHours::new(9, 17); // Monday to Friday, 9:00 to 17:00 UTC
+///
+/// let friday_4pm = UNIX_EPOCH + Duration::from_secs(1_709_308_800); // 2024-03-01 16:00
+/// let monday_10am = friday_4pm + Duration::from_secs(66 * 60 * 60);
+/// assert_eq!(schedule.elapsed(friday_4pm, monday_10am), Duration::from_secs(2 * 60 * 60));
+///
+/// let worked = Duration::from_secs((2 * 8 + 3) * 60 * 60);
+/// assert_eq!(schedule.format(worked), "2 business days 3h 0m 0s");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusinessHours {
+    days: Vec<Weekday>,
+    open: i64,
+    close: i64,
+    utc_offset: i64,
+}
+
+impl BusinessHours {
+    /// Creates a Monday-to-Friday schedule from `open_hour` to `close_hour`, in UTC.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `open_hour < close_hour <= 24`.
+    pub fn new(open_hour: u32, close_hour: u32) -> Self {
+        assert!(
+            open_hour < close_hour && close_hour <= 24,
+            "business hours must satisfy open < close <= 24"
+        );
+        BusinessHours {
+            days: vec![
+                Weekday::Monday,
+                Weekday::Tuesday,
+                Weekday::Wednesday,
+                Weekday::Thursday,
+                Weekday::Friday,
+            ],
+            open: open_hour as i64 * 3600,
+            close: close_hour as i64 * 3600,
+            utc_offset: 0,
+        }
+    }
+
+    /// Sets which days of the week are working days.
+    pub fn days(mut self, days: &[Weekday]) -> Self {
+        self.days = days.to_vec();
+        self
+    }
+
+    /// Interprets the opening hours in a time zone `offset_secs` east of UTC, e.g.
+    /// `-5 * 3600` for New York in winter.
+    pub fn utc_offset(mut self, offset_secs: i32) -> Self {
+        self.utc_offset = offset_secs as i64;
+        self
+    }
+
+    /// Returns the length of one working day.
+    pub fn day_length(&self) -> Duration {
+        Duration::from_secs((self.close - self.open) as u64)
+    }
+
+    /// Returns the working time between `start` and `end`, or zero if `end` is earlier.
+    pub fn elapsed(&self, start: SystemTime, end: SystemTime) -> Duration {
+        let local_nanos = |t: SystemTime| -> i128 {
+            let unix = match t.duration_since(UNIX_EPOCH) {
+                Ok(after) => after.as_nanos() as i128,
+                Err(before) => -(before.duration().as_nanos() as i128),
+            };
+            unix + self.utc_offset as i128 * 1_000_000_000
+        };
+        let (start, end) = (local_nanos(start), local_nanos(end));
+        if end <= start {
+            return Duration::ZERO;
+        }
+
+        let day_nanos = SECS_PER_DAY as i128 * 1_000_000_000;
+        let first_day = start.div_euclid(day_nanos) as i64;
+        let last_day = end.div_euclid(day_nanos) as i64;
+        let mut total: u128 = 0;
+        for day in first_day..=last_day {
+            if !self.days.contains(&Weekday::from_epoch_day(day)) {
+                continue;
+            }
+            let midnight = day as i128 * day_nanos;
+            let open = (midnight + self.open as i128 * 1_000_000_000).max(start);
+            let close = (midnight + self.close as i128 * 1_000_000_000).min(end);
+            if close > open {
+                total += (close - open) as u128;
+            }
+        }
+        Duration::new(
+            (total / 1_000_000_000) as u64,
+            (total % 1_000_000_000) as u32,
+        )
+    }
+
+    /// Formats working time as whole business days plus the remainder, e.g.
+    /// `"2 business days 3h 0m 0s"`.
+    pub fn format(&self, worked: Duration) -> String {
+        let day = self.day_length();
+        let days = worked.as_nanos() / day.as_nanos();
+        let rest = worked - day * days as u32;
+        let days_part = match days {
+            0 => return format_duration(rest),
+            1 => "1 business day".to_string(),
+            n => format!("{} business days", n),
+        };
+        if rest.is_zero() {
+            days_part
+        } else {
+            format!("{} {}", days_part, format_duration(rest))
+        }
+    }
+
+    /// Returns the working time between `start` and `end`, formatted with
+    /// [`format`](BusinessHours::format).
+    pub fn format_elapsed(&self, start: SystemTime, end: SystemTime) -> String {
+        self.format(self.elapsed(start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-03-01, a Friday, at the given hour.
+    fn friday(hour: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_709_251_200 + hour * 3600)
+    }
+
+    fn hours(h: u64) -> Duration {
+        Duration::from_secs(h * 3600)
+    }
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(Weekday::from_epoch_day(0), Weekday::Thursday);
+        assert_eq!(Weekday::from_epoch_day(-1), Weekday::Wednesday);
+        assert_eq!(Weekday::from_epoch_day(19_783), Weekday::Friday);
+    }
+
+    #[test]
+    fn test_elapsed_skips_nights_and_weekends() {
+        let schedule = BusinessHours::new(9, 17);
+        assert_eq!(schedule.elapsed(friday(10), friday(12)), hours(2));
+        assert_eq!(schedule.elapsed(friday(6), friday(20)), hours(8));
+        // Friday 16:00 to the next Friday 16:00 is five full working days.
+        assert_eq!(schedule.elapsed(friday(16), friday(16 + 7 * 24)), hours(40));
+        assert_eq!(schedule.elapsed(friday(12), friday(10)), Duration::ZERO);
+
+        let weekends = BusinessHours::new(0, 24).days(&[Weekday::Saturday, Weekday::Sunday]);
+        assert_eq!(weekends.elapsed(friday(0), friday(7 * 24)), hours(48));
+    }
+
+    #[test]
+    fn test_utc_offset() {
+        // 9–17 in UTC-5 is 14–22 UTC.
+        let schedule = BusinessHours::new(9, 17).utc_offset(-5 * 3600);
+        assert_eq!(schedule.elapsed(friday(12), friday(15)), hours(1));
+    }
+
+    #[test]
+    fn test_format() {
+        let schedule = BusinessHours::new(9, 17);
+        assert_eq!(schedule.format(hours(3)), "3h 0m 0s");
+        assert_eq!(schedule.format(hours(8)), "1 business day");
+        assert_eq!(schedule.format(hours(19)), "2 business days 3h 0m 0s");
+        assert_eq!(schedule.format_elapsed(friday(16), friday(16 + 72)), "1 business day");
+    }
+}
@@ -0,0 +1,228 @@
+//! Calendar-aware elapsed time, with real month lengths and daylight saving transitions.
+//!
+//! Enabled with the `jiff` feature.
+
+use std::fmt;
+use std::time::Duration;
+
+use jiff::{Span, Unit, Zoned};
+
+use crate::{format_duration, SignedDuration};
+
+/// The calendar difference between two points in time.
+///
+/// Years, months and days are counted on the calendar of the start's time zone, so a month
+/// is as long as the month it covers and a day across a daylight saving change is still one
+/// day, even though it lasts 23 or 25 hours. The remainder below a day is in `time`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarElapsed {
+    /// `true` if the end comes before the start.
+    pub negative: bool,
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+    pub time: Duration,
+}
+
+impl CalendarElapsed {
+    /// Computes the calendar difference from `start` to `end`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the two times cannot be compared, e.g. because of arithmetic
+    /// overflow at the edges of the supported range.
+    pub fn between(start: &Zoned, end: &Zoned) -> Result<Self, jiff::Error> {
+        let negative = end < start;
+        let (from, to) = if negative { (end, start) } else { (start, end) };
+        let span = from.until((Unit::Year, to))?;
+        Ok(CalendarElapsed {
+            negative,
+            years: span.get_years() as i64,
+            months: span.get_months() as i64,
+            days: span.get_days() as i64,
+            time: time_of_span(&span),
+        })
+    }
+
+    /// Splits a `jiff::Span` into calendar units and a remainder below a day.
+    ///
+    /// Weeks count as seven days, and whole 24 hours in the time part are counted as days,
+    /// so a span of `"1d 36h"` becomes two days and 12 hours.
+    pub fn from_span(span: &Span) -> Self {
+        let time = time_of_span(span);
+        let whole_days = time.as_secs() / 86_400;
+        CalendarElapsed {
+            negative: span.is_negative(),
+            years: (span.get_years() as i64).abs(),
+            months: (span.get_months() as i64).abs(),
+            days: (span.get_weeks() as i64 * 7 + span.get_days() as i64).abs() + whole_days as i64,
+            time: time - Duration::from_secs(whole_days * 86_400),
+        }
+    }
+}
+
+/// Returns the part of `span` below a day as a `Duration`.
+fn time_of_span(span: &Span) -> Duration {
+    let nanos = span.get_hours() as i128 * 3_600_000_000_000
+        + span.get_minutes() as i128 * 60_000_000_000
+        + span.get_seconds() as i128 * 1_000_000_000
+        + span.get_milliseconds() as i128 * 1_000_000
+        + span.get_microseconds() as i128 * 1_000
+        + span.get_nanoseconds() as i128;
+    let nanos = nanos.unsigned_abs();
+    Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    )
+}
+
+impl fmt::Display for CalendarElapsed {
+    /// Renders `"2 months 3 days 4h 0m 0s"`, leaving out calendar units that are zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        for (count, unit) in [(self.years, "year"), (self.months, "month"), (self.days, "day")] {
+            match count {
+                0 => {}
+                1 => parts.push(format!("1 {}", unit)),
+                n => parts.push(format!("{} {}s", n, unit)),
+            }
+        }
+        if parts.is_empty() || !self.time.is_zero() {
+            parts.push(format_duration(self.time));
+        }
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+/// Formats the calendar time from `start` to `end`, e.g. `"2 months 3 days 4h 0m 0s"`.
+///
+/// Unlike [`format_duration`], which works in fixed-length units, this counts the months
+/// and days actually on the calendar between the two times, which is what "up since"
+/// displays and anniversaries need.
+///
+/// # Arguments
+///
+/// * `start` - The earlier time; its time zone decides where days begin.
+/// * `end` - The later time.
+///
+/// # Returns
+///
+/// The formatted difference, prefixed with `-` if `end` is earlier than `start`.
+///
+/// # Errors
+///
+/// Returns an error if the two times cannot be compared.
+///
+/// # Example
+///
+/// ```
+/// use jiff::Zoned;
+/// use elapsed_time::calendar::elapsed_calendar;
+///
+/// let start: Zoned = "2024-01-15T10:00[America/New_York]".parse().unwrap();
+/// let end: Zoned = "2024-03-18T14:00[America/New_York]".parse().unwrap();
+/// assert_eq!(elapsed_calendar(&start, &end).unwrap(), "2 months 3 days 4h 0m 0s");
+/// ```
+pub fn elapsed_calendar(start: &Zoned, end: &Zoned) -> Result<String, jiff::Error> {
+    CalendarElapsed::between(start, end).map(|elapsed| elapsed.to_string())
+}
+
+impl From<jiff::SignedDuration> for SignedDuration {
+    fn from(duration: jiff::SignedDuration) -> Self {
+        SignedDuration::new(duration.is_negative(), duration.unsigned_abs())
+    }
+}
+
+/// Formats a `jiff::Span`, including any years, months, weeks and days it holds.
+///
+/// Calendar units are written out as in [`elapsed_calendar`] and the rest in the
+/// [`format_duration`] format, so `"P1Y2M3DT4H"` reads `"1 year 2 months 3 days 4h 0m 0s"`.
+/// A `jiff::SignedDuration` has no calendar units; pass it to
+/// [`format_signed_duration`](crate::format_signed_duration) instead.
+///
+/// # Arguments
+///
+/// * `span` - The span to format.
+///
+/// # Returns
+///
+/// The formatted span, prefixed with `-` if it is negative.
+///
+/// # Example
+///
+/// ```
+/// use jiff::{Span, ToSpan};
+/// use elapsed_time::calendar::format_span;
+/// use elapsed_time::format_signed_duration;
+///
+/// let span: Span = "P1Y2M3DT4H".parse().unwrap();
+/// assert_eq!(format_span(&span), "1 year 2 months 3 days 4h 0m 0s");
+/// assert_eq!(format_span(&-90.seconds()), "-1m 30s");
+/// assert_eq!(format_signed_duration(jiff::SignedDuration::from_millis(-2_300)), "-2.300s");
+/// ```
+pub fn format_span(span: &Span) -> String {
+    CalendarElapsed::from_span(span).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zoned(s: &str) -> Zoned {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_month_lengths() {
+        let elapsed = CalendarElapsed::between(
+            &zoned("2024-01-31T00:00[UTC]"),
+            &zoned("2024-03-01T00:00[UTC]"),
+        )
+        .unwrap();
+        assert_eq!((elapsed.years, elapsed.months, elapsed.days), (0, 1, 1));
+        assert_eq!(elapsed.to_string(), "1 month 1 day");
+
+        let start = zoned("2021-06-01T08:00[UTC]");
+        let end = zoned("2024-06-01T08:00:01.5[UTC]");
+        assert_eq!(elapsed_calendar(&start, &end).unwrap(), "3 years 1.500s");
+        assert_eq!(elapsed_calendar(&end, &start).unwrap(), "-3 years 1.500s");
+    }
+
+    #[test]
+    fn test_daylight_saving_day_is_one_day() {
+        // Clocks in New York sprang forward on 2024-03-10, so this day lasted 23 hours.
+        let start = zoned("2024-03-09T12:00[America/New_York]");
+        let end = zoned("2024-03-10T12:00[America/New_York]");
+        assert_eq!(elapsed_calendar(&start, &end).unwrap(), "1 day");
+        assert_eq!(end.duration_since(&start).as_secs(), 23 * 60 * 60);
+    }
+
+    #[test]
+    fn test_spans() {
+        use jiff::ToSpan;
+
+        let span = 2.weeks().days(1).hours(36);
+        let elapsed = CalendarElapsed::from_span(&span);
+        assert_eq!((elapsed.days, elapsed.time), (16, Duration::from_secs(12 * 3600)));
+        assert_eq!(format_span(&span), "16 days 12h 0m 0s");
+        assert_eq!(format_span(&-1.month()), "-1 month");
+        assert_eq!(format_span(&Span::new()), "0s");
+    }
+
+    #[test]
+    fn test_signed_duration() {
+        let span = SignedDuration::from(jiff::SignedDuration::from_secs(-5));
+        assert!(span.is_negative());
+        assert_eq!(span.magnitude(), Duration::from_secs(5));
+        assert!(!SignedDuration::from(jiff::SignedDuration::MIN).magnitude().is_zero());
+    }
+
+    #[test]
+    fn test_zero() {
+        let now = zoned("2024-03-09T12:00[UTC]");
+        assert_eq!(elapsed_calendar(&now, &now).unwrap(), format_duration(Duration::ZERO));
+    }
+}
@@ -0,0 +1,133 @@
+//! Completion messages posted to Slack or Discord incoming webhooks.
+//!
+//! Enabled with the `chat` feature.
+
+use std::time::Duration;
+
+use crate::format_duration;
+use crate::json::push_string;
+use crate::webhook::WebhookError;
+
+/// How long a chat request may take before it is abandoned.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The chat service a [`ChatSink`] posts to; each expects a different payload field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatService {
+    /// Slack incoming webhooks, which read the `text` field.
+    Slack,
+    /// Discord webhooks, which read the `content` field.
+    Discord,
+}
+
+/// Posts messages like "`nightly-etl` finished in 1h 12m 0s" to a chat webhook.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use elapsed_time::chat::ChatSink;
+///
+/// let sink = ChatSink::slack("https://hooks.slack.com/services/T000/B000/XXXX")
+///     .min_duration(Duration::from_secs(60));
+/// sink.report("nightly-etl", Duration::from_secs(4320))?;
+/// # Ok::<(), elapsed_time::webhook::WebhookError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChatSink {
+    service: ChatService,
+    url: String,
+    min_duration: Duration,
+    agent: ureq::Agent,
+}
+
+impl ChatSink {
+    /// Creates a sink posting to a Slack incoming-webhook URL.
+    pub fn slack(url: impl Into<String>) -> Self {
+        Self::new(ChatService::Slack, url)
+    }
+
+    /// Creates a sink posting to a Discord webhook URL.
+    pub fn discord(url: impl Into<String>) -> Self {
+        Self::new(ChatService::Discord, url)
+    }
+
+    /// Creates a sink for `service` posting to `url`.
+    pub fn new(service: ChatService, url: impl Into<String>) -> Self {
+        ChatSink {
+            service,
+            url: url.into(),
+            min_duration: Duration::ZERO,
+            agent: ureq::AgentBuilder::new().timeout(DEFAULT_TIMEOUT).build(),
+        }
+    }
+
+    /// Only posts measurements that took at least `min_duration`.
+    pub fn min_duration(mut self, min_duration: Duration) -> Self {
+        self.min_duration = min_duration;
+        self
+    }
+
+    /// Returns the message text for a measurement.
+    pub fn message(&self, label: &str, duration: Duration) -> String {
+        format!("`{}` finished in {}", label, format_duration(duration))
+    }
+
+    /// Returns the JSON payload for a measurement in the service's expected shape.
+    pub fn payload(&self, label: &str, duration: Duration) -> String {
+        let field = match self.service {
+            ChatService::Slack => "text",
+            ChatService::Discord => "content",
+        };
+        let mut out = format!("{{\"{}\":", field);
+        push_string(&mut out, &self.message(label, duration));
+        out.push('}');
+        out
+    }
+
+    /// Posts a measurement to the chat webhook if it took at least the minimum duration.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the message was sent, `Ok(false)` if the measurement was too short.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WebhookError` if the request could not be sent or the service answered
+    /// with an error status.
+    pub fn report(&self, label: &str, duration: Duration) -> Result<bool, WebhookError> {
+        if duration < self.min_duration {
+            return Ok(false);
+        }
+        self.agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&self.payload(label, duration))?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_per_service() {
+        let duration = Duration::from_secs(72 * 60);
+        assert_eq!(
+            ChatSink::slack("http://localhost/").payload("nightly-etl", duration),
+            r#"{"text":"`nightly-etl` finished in 1h 12m 0s"}"#
+        );
+        assert_eq!(
+            ChatSink::discord("http://localhost/").payload("nightly-etl", duration),
+            r#"{"content":"`nightly-etl` finished in 1h 12m 0s"}"#
+        );
+    }
+
+    #[test]
+    fn test_short_measurements_are_skipped() {
+        // The URL is never contacted because the measurement is under the minimum.
+        let sink = ChatSink::slack("http://127.0.0.1:9/").min_duration(Duration::from_secs(60));
+        assert!(!sink.report("quick", Duration::from_secs(5)).unwrap());
+    }
+}
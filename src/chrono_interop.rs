@@ -0,0 +1,89 @@
+//! Formatting `chrono` time deltas and date-times.
+//!
+//! Enabled with the `chrono` feature.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, TimeZone};
+
+use crate::SignedDuration;
+
+impl From<TimeDelta> for SignedDuration {
+    fn from(delta: TimeDelta) -> Self {
+        let magnitude = delta.abs().to_std().unwrap_or(Duration::MAX);
+        SignedDuration::new(delta < TimeDelta::zero(), magnitude)
+    }
+}
+
+/// Formats a `chrono::TimeDelta`, prefixing `"-"` when it is negative.
+///
+/// # Arguments
+///
+/// * `delta` - The delta to format.
+///
+/// # Returns
+///
+/// A `String` in the [`format_duration`](crate::format_duration) format, with a leading `"-"`
+/// for negative deltas.
+///
+/// # Example
+///
+/// ```
+/// use chrono::TimeDelta;
+/// use elapsed_time::format_time_delta;
+///
+/// assert_eq!(format_time_delta(TimeDelta::milliseconds(90_500)), "1m 30.500s");
+/// assert_eq!(format_time_delta(TimeDelta::seconds(-5)), "-5s");
+/// ```
+pub fn format_time_delta(delta: TimeDelta) -> String {
+    SignedDuration::from(delta).to_string()
+}
+
+/// Formats the time from `start` to `end`, which may be in different time zones.
+///
+/// # Arguments
+///
+/// * `start` - The earlier point in time.
+/// * `end` - The later point in time.
+///
+/// # Returns
+///
+/// A `String` in the [`format_duration`](crate::format_duration) format, with a leading `"-"`
+/// if `end` comes before `start`.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{DateTime, FixedOffset, Utc};
+/// use elapsed_time::format_between;
+///
+/// let start: DateTime<Utc> = "2024-03-01T12:00:00Z".parse().unwrap();
+/// let end: DateTime<FixedOffset> = "2024-03-01T14:30:05+02:00".parse().unwrap();
+/// assert_eq!(format_between(&start, &end), "30m 5s");
+/// assert_eq!(format_between(&end, &start), "-30m 5s");
+/// ```
+pub fn format_between<A: TimeZone, B: TimeZone>(start: &DateTime<A>, end: &DateTime<B>) -> String {
+    format_time_delta(end.naive_utc() - start.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_signed_duration;
+
+    #[test]
+    fn test_negative_delta_keeps_its_sign() {
+        let delta = TimeDelta::milliseconds(-2_300);
+        let span = SignedDuration::from(delta);
+        assert!(span.is_negative());
+        assert_eq!(span.magnitude(), Duration::from_millis(2_300));
+        assert_eq!(format_signed_duration(delta), "-2.300s");
+    }
+
+    #[test]
+    fn test_extremes() {
+        assert_eq!(format_time_delta(TimeDelta::zero()), "0s");
+        assert!(SignedDuration::from(TimeDelta::MIN).is_negative());
+        assert!(!SignedDuration::from(TimeDelta::MAX).is_negative());
+    }
+}
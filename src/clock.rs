@@ -0,0 +1,270 @@
+use alloc::string::String;
+use core::time::Duration;
+#[cfg(feature = "test-util")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "test-util")]
+use std::sync::Arc;
+#[cfg(all(feature = "std", not(all(feature = "wasm", target_arch = "wasm32"))))]
+use std::time::Instant;
+
+use crate::format_duration;
+
+/// A source of monotonic time for measurements.
+///
+/// `std::time::Instant` is not available on every target. Implement this trait over a
+/// cycle counter or hardware timer to measure on embedded targets, or over a counter you
+/// control to make timing-dependent tests deterministic. Any `Fn() -> u64` closure returning
+/// nanoseconds is already a clock.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::Cell;
+/// use elapsed_time::{measure_elapsed_time_with_clock, Clock};
+///
+/// let ticks = Cell::new(0u64);
+/// let clock = || ticks.get();
+/// let elapsed = measure_elapsed_time_with_clock(&clock, || ticks.set(1_500_000_000));
+/// assert_eq!(elapsed, "1.500s");
+/// ```
+pub trait Clock {
+    /// Returns the current reading in nanoseconds since an arbitrary fixed origin.
+    ///
+    /// Readings must never decrease.
+    fn now(&self) -> u64;
+
+    /// Runs `f` and returns its result with the time it took on this clock.
+    fn measure<F, T>(&self, f: F) -> (T, Duration)
+    where
+        F: FnOnce() -> T,
+    {
+        let start = self.now();
+        let result = f();
+        (
+            result,
+            Duration::from_nanos(self.now().saturating_sub(start)),
+        )
+    }
+}
+
+impl<F: Fn() -> u64> Clock for F {
+    fn now(&self) -> u64 {
+        self()
+    }
+}
+
+/// The platform's monotonic clock, backed by `std::time::Instant`.
+///
+/// Readings count from when the clock was created. With the `wasm` feature on `wasm32`
+/// targets, where `Instant::now()` panics, it reads `performance.now()` instead, which
+/// browsers, web workers and Node.js all provide.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct StdClock {
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    origin: Instant,
+    /// The `performance.now()` reading at creation, in milliseconds.
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    origin: f64,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    /// Creates a clock that reads zero now.
+    pub fn new() -> Self {
+        StdClock {
+            #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+            origin: Instant::now(),
+            #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+            origin: performance_now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        StdClock::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    fn now(&self) -> u64 {
+        u64::try_from(self.origin.elapsed().as_nanos()).unwrap_or(u64::MAX)
+    }
+
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    fn now(&self) -> u64 {
+        // `as` saturates, and clamps the negative readings of a clock that went backwards to 0.
+        ((performance_now() - self.origin) * 1_000_000.0) as u64
+    }
+}
+
+/// Reads `performance.now()` from the JavaScript global object, in milliseconds.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+fn performance_now() -> f64 {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let performance = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .ok()
+        .filter(|performance| !performance.is_undefined())
+        .expect("`performance` is not available in this JavaScript environment");
+    performance.unchecked_into::<web_sys::Performance>().now()
+}
+
+/// Runs `f` and returns its result with the time it took on [`StdClock`].
+#[cfg(feature = "std")]
+pub(crate) fn measure_call<F, T>(f: F) -> (T, Duration)
+where
+    F: FnOnce() -> T,
+{
+    StdClock::new().measure(f)
+}
+
+/// A clock that only moves when told to, for testing timing-dependent code without sleeping.
+///
+/// Clones share the same reading, so a test can keep one handle and advance it from inside
+/// the code being measured. Enabled with the `test-util` feature.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{measure_elapsed_time_with_clock, ManualClock};
+///
+/// let clock = ManualClock::new();
+/// let handle = clock.clone();
+/// let elapsed = measure_elapsed_time_with_clock(&clock, || {
+///     handle.advance(Duration::from_secs(125));
+/// });
+/// assert_eq!(elapsed, "2m 5s");
+/// ```
+#[cfg(feature = "test-util")]
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+#[cfg(feature = "test-util")]
+impl ManualClock {
+    /// Creates a clock that reads zero.
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    /// Moves the clock forward by `by`, saturating at `u64::MAX` nanoseconds.
+    pub fn advance(&self, by: Duration) {
+        let by = u64::try_from(by.as_nanos()).unwrap_or(u64::MAX);
+        let _ = self
+            .nanos
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |now| {
+                Some(now.saturating_add(by))
+            });
+    }
+
+    /// Returns the time the clock has been advanced by in total.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.now())
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.nanos.load(Ordering::SeqCst)
+    }
+}
+
+/// Measures the elapsed time of a function on the given clock and formats it.
+///
+/// This is [`measure_elapsed_time`](crate::measure_elapsed_time) with the time read from
+/// `clock` instead of `std::time::Instant`, so it also works without `std`.
+///
+/// # Arguments
+///
+/// * `clock` - The [`Clock`] to read before and after the call.
+/// * `f` - A closure that takes no arguments and returns nothing (`FnOnce()`).
+///
+/// # Returns
+///
+/// A `String` representing the formatted elapsed time.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{measure_elapsed_time_with_clock, StdClock};
+///
+/// let elapsed = measure_elapsed_time_with_clock(&StdClock::new(), || {
+///     std::thread::sleep(std::time::Duration::from_millis(20));
+/// });
+/// println!("took {}", elapsed);
+/// ```
+pub fn measure_elapsed_time_with_clock<C, F>(clock: &C, f: F) -> String
+where
+    C: Clock,
+    F: FnOnce(),
+{
+    let ((), elapsed) = clock.measure(f);
+    format_duration(elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// A clock that advances by a fixed step on every reading.
+    struct SteppingClock {
+        ticks: Cell<u64>,
+        step: u64,
+    }
+
+    impl Clock for SteppingClock {
+        fn now(&self) -> u64 {
+            let now = self.ticks.get();
+            self.ticks.set(now + self.step);
+            now
+        }
+    }
+
+    #[test]
+    fn test_custom_clock() {
+        let clock = SteppingClock {
+            ticks: Cell::new(0),
+            step: 125_000_000_000,
+        };
+        assert_eq!(measure_elapsed_time_with_clock(&clock, || {}), "2m 5s");
+        let (value, elapsed) = clock.measure(|| 42);
+        assert_eq!((value, elapsed), (42, Duration::from_secs(125)));
+    }
+
+    #[test]
+    fn test_backwards_reading_saturates() {
+        let readings = Cell::new(10u64);
+        let clock = || readings.replace(0);
+        assert_eq!(clock.measure(|| ()).1, Duration::ZERO);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new();
+        let handle = clock.clone();
+        let (_, elapsed) = clock.measure(|| handle.advance(Duration::from_millis(1_500)));
+        assert_eq!(elapsed, Duration::from_millis(1_500));
+        assert_eq!(clock.elapsed(), Duration::from_millis(1_500));
+        clock.advance(Duration::MAX);
+        assert_eq!(handle.now(), u64::MAX);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_std_clock() {
+        let clock = StdClock::new();
+        let (_, elapsed) = clock.measure(|| std::thread::sleep(Duration::from_millis(5)));
+        assert!(elapsed >= Duration::from_millis(5));
+        assert!(clock.now() >= 5_000_000);
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use std::io;
+use std::process::{Command, ExitStatus};
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// The outcome of running and timing an external command with [`measure_command`].
+///
+/// Its `Display` implementation renders `"1.200s real, 0.800s user, 0.100s sys (exit
+/// status: 0)"`, or `"1.200s (exit status: 0)"` when the child's CPU times are unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandTiming {
+    /// How the command exited.
+    pub status: ExitStatus,
+    /// Real time from spawning the command until it exited.
+    pub wall: Duration,
+    /// CPU time the command spent in its own code, if known.
+    ///
+    /// This is only measured with the `cpu-time` feature, on Unix and Windows.
+    pub user: Option<Duration>,
+    /// CPU time the kernel spent working on the command's behalf, if known.
+    pub system: Option<Duration>,
+}
+
+impl fmt::Display for CommandTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.user, self.system) {
+            (Some(user), Some(system)) => write!(
+                f,
+                "{} real, {} user, {} sys",
+                format_duration(self.wall),
+                format_duration(user),
+                format_duration(system)
+            )?,
+            _ => f.write_str(&format_duration(self.wall))?,
+        }
+        write!(f, " ({})", self.status)
+    }
+}
+
+/// Spawns an external command, waits for it to exit and times it.
+///
+/// The command inherits this process's standard streams unless `command` says otherwise.
+/// With the `cpu-time` feature, the child's own user and system CPU time are collected as
+/// it is reaped (`wait4` on Unix, `GetProcessTimes` on Windows).
+///
+/// # Arguments
+///
+/// * `command` - The command to run.
+///
+/// # Returns
+///
+/// A [`CommandTiming`] with the exit status and the elapsed time.
+///
+/// # Errors
+///
+/// Returns an error if the command cannot be spawned or waited for.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::process::Command;
+/// use elapsed_time::measure_command;
+///
+/// let timing = measure_command(Command::new("cargo").arg("build")).unwrap();
+/// if !timing.status.success() {
+///     eprintln!("build failed after {}", timing);
+/// }
+/// ```
+pub fn measure_command(command: &mut Command) -> io::Result<CommandTiming> {
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+    #[cfg(all(feature = "cpu-time", any(unix, windows)))]
+    let (status, times) = crate::cpu::wait_child(&mut child)?;
+    #[cfg(not(all(feature = "cpu-time", any(unix, windows))))]
+    let (status, times) = (child.wait()?, None);
+    Ok(CommandTiming {
+        status,
+        wall: start.elapsed(),
+        user: times.map(|(user, _)| user),
+        system: times.map(|(_, system)| system),
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_status_and_wall_time() {
+        let timing =
+            measure_command(Command::new("sh").args(["-c", "sleep 0.05; exit 3"])).unwrap();
+        assert_eq!(timing.status.code(), Some(3));
+        assert!(timing.wall >= Duration::from_millis(50));
+        assert!(timing.to_string().ends_with("(exit status: 3)"));
+        #[cfg(feature = "cpu-time")]
+        assert!(timing.user.is_some() && timing.system.is_some());
+    }
+
+    #[test]
+    fn test_display() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let mut timing = CommandTiming {
+            status: ExitStatus::from_raw(0),
+            wall: Duration::from_millis(1_200),
+            user: None,
+            system: None,
+        };
+        assert_eq!(timing.to_string(), "1.200s (exit status: 0)");
+        timing.user = Some(Duration::from_millis(800));
+        timing.system = Some(Duration::from_millis(100));
+        assert_eq!(
+            timing.to_string(),
+            "1.200s real, 0.800s user, 0.100s sys (exit status: 0)"
+        );
+    }
+
+    #[test]
+    fn test_spawn_error() {
+        assert!(measure_command(&mut Command::new("/nonexistent/elapsed-time-test")).is_err());
+    }
+}
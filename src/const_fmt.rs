@@ -0,0 +1,163 @@
+use core::time::Duration;
+
+use crate::DurationComponents;
+
+/// Longest possible output: "30500568904943w 6d 23h 59m 59.999s" is 34 bytes.
+const CAPACITY: usize = 40;
+
+/// A formatted duration stored inline, produced by [`format_duration_const`].
+///
+/// The text is kept in a fixed-size byte buffer so it can be built in `const`
+/// contexts without allocating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstFormattedDuration {
+    buf: [u8; CAPACITY],
+    len: usize,
+}
+
+impl ConstFormattedDuration {
+    const fn new() -> Self {
+        ConstFormattedDuration {
+            buf: [0; CAPACITY],
+            len: 0,
+        }
+    }
+
+    const fn push(mut self, byte: u8) -> Self {
+        self.buf[self.len] = byte;
+        self.len += 1;
+        self
+    }
+
+    const fn push_u64(mut self, mut value: u64) -> Self {
+        let mut digits = [0u8; 20];
+        let mut count = 0;
+        loop {
+            digits[count] = b'0' + (value % 10) as u8;
+            count += 1;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        while count > 0 {
+            count -= 1;
+            self = self.push(digits[count]);
+        }
+        self
+    }
+
+    const fn push_unit(self, value: u64, unit: u8) -> Self {
+        self.push_u64(value).push(unit).push(b' ')
+    }
+
+    const fn push_seconds(self, secs: u64, ms: u32) -> Self {
+        let out = self.push_u64(secs);
+        let out = if ms > 0 {
+            out.push(b'.')
+                .push(b'0' + (ms / 100) as u8)
+                .push(b'0' + (ms / 10 % 10) as u8)
+                .push(b'0' + (ms % 10) as u8)
+        } else {
+            out
+        };
+        out.push(b's')
+    }
+
+    /// Returns the formatted duration as a string slice.
+    pub const fn as_str(&self) -> &str {
+        let (bytes, _) = self.buf.split_at(self.len);
+        match core::str::from_utf8(bytes) {
+            Ok(s) => s,
+            Err(_) => panic!("formatted duration is always ASCII"),
+        }
+    }
+}
+
+impl core::fmt::Display for ConstFormattedDuration {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Formats a Duration at compile time.
+///
+/// This is a `const fn` counterpart of [`format_duration`](crate::format_duration) producing
+/// exactly the same text, so display strings for durations known at compile time (configured
+/// timeouts, retry intervals, ...) can live in `const` items.
+///
+/// # Arguments
+///
+/// * `duration` - A `std::time::Duration` to be formatted.
+///
+/// # Returns
+///
+/// A `ConstFormattedDuration` holding the formatted text; use `as_str()` or `Display` to read it.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{format_duration_const, ConstFormattedDuration};
+///
+/// const TIMEOUT: Duration = Duration::from_secs(150);
+/// const TIMEOUT_LABEL: ConstFormattedDuration = format_duration_const(TIMEOUT);
+/// assert_eq!(TIMEOUT_LABEL.as_str(), "2m 30s");
+/// ```
+pub const fn format_duration_const(duration: Duration) -> ConstFormattedDuration {
+    let c = DurationComponents::from_duration(duration);
+    let mut out = ConstFormattedDuration::new();
+
+    if c.weeks > 0 {
+        out = out.push_unit(c.weeks, b'w');
+    }
+    if c.weeks > 0 || c.remaining_days > 0 {
+        out = out.push_unit(c.remaining_days, b'd');
+    }
+    if c.weeks > 0 || c.remaining_days > 0 || c.remaining_hours > 0 {
+        out = out.push_unit(c.remaining_hours, b'h');
+        out = out.push_unit(c.minutes, b'm');
+    } else if c.minutes > 0 {
+        if c.seconds == 0 && c.milliseconds == 0 {
+            return out.push_u64(c.minutes).push(b'm');
+        }
+        out = out.push_unit(c.minutes, b'm');
+    }
+    out.push_seconds(c.seconds, c.milliseconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_duration;
+
+    #[test]
+    fn test_matches_format_duration() {
+        let test_cases = [
+            Duration::from_millis(0),
+            Duration::from_millis(6),
+            Duration::from_millis(500),
+            Duration::from_secs(5),
+            Duration::from_secs(60),
+            Duration::from_millis(60001),
+            Duration::from_secs(185),
+            Duration::from_secs(3600),
+            Duration::from_secs(90061),
+            Duration::from_millis(788_645_006),
+            Duration::from_secs(u64::MAX),
+            Duration::MAX,
+        ];
+
+        for duration in test_cases {
+            assert_eq!(format_duration_const(duration).as_str(), format_duration(duration));
+        }
+    }
+
+    #[test]
+    fn test_usable_in_const() {
+        const LABEL: ConstFormattedDuration = format_duration_const(Duration::from_secs(3665));
+        const TEXT: &str = LABEL.as_str();
+        assert_eq!(TEXT, "1h 1m 5s");
+        assert_eq!(LABEL.to_string(), "1h 1m 5s");
+    }
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// An error annotated with how long the failing operation ran, e.g.
+/// `"connection reset (after 2m 13s)"`.
+///
+/// Created by [`ElapsedContext::elapsed_context`]. The message already includes the
+/// wrapped error's, so [`source`](Error::source) skips it and returns the wrapped error's
+/// own source; reporters that print the whole chain show each message once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElapsedError<E> {
+    /// The original error.
+    pub error: E,
+    /// How long the operation had been running when it failed.
+    pub elapsed: Duration,
+}
+
+impl<E> ElapsedError<E> {
+    /// Returns the original error, discarding the elapsed time.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ElapsedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (after {})",
+            self.error,
+            format_duration(self.elapsed)
+        )
+    }
+}
+
+impl<E: Error + 'static> Error for ElapsedError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Attaches the elapsed time to the error of a `Result`.
+///
+/// When a long operation fails, how far in it failed helps tell a timeout from a bad
+/// input. The success value is passed through untouched.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Instant;
+/// use elapsed_time::ElapsedContext;
+///
+/// let start = Instant::now();
+/// let err = "x".parse::<u32>().elapsed_context(start).unwrap_err();
+/// assert!(err.to_string().starts_with("invalid digit found in string (after "));
+/// // e.g. "invalid digit found in string (after 0s)"
+/// ```
+pub trait ElapsedContext<T, E> {
+    /// Wraps the error, if any, in an [`ElapsedError`] with the time since `start`.
+    fn elapsed_context(self, start: Instant) -> Result<T, ElapsedError<E>>;
+}
+
+impl<T, E> ElapsedContext<T, E> for Result<T, E> {
+    fn elapsed_context(self, start: Instant) -> Result<T, ElapsedError<E>> {
+        self.map_err(|error| ElapsedError {
+            error,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[derive(Debug)]
+    struct Query(io::Error);
+
+    impl fmt::Display for Query {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("query failed")
+        }
+    }
+
+    impl Error for Query {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_elapsed_error() {
+        let err = ElapsedError {
+            error: io::Error::new(io::ErrorKind::ConnectionReset, "connection reset"),
+            elapsed: Duration::from_secs(133),
+        };
+        assert_eq!(err.to_string(), "connection reset (after 2m 13s)");
+        assert!(err.source().is_none());
+        assert_eq!(err.into_inner().kind(), io::ErrorKind::ConnectionReset);
+    }
+
+    #[test]
+    fn test_source_skips_the_wrapped_message() {
+        let err = ElapsedError {
+            error: Query(io::Error::new(io::ErrorKind::TimedOut, "timed out")),
+            elapsed: Duration::from_secs(30),
+        };
+        assert_eq!(err.to_string(), "query failed (after 30s)");
+        assert_eq!(err.source().unwrap().to_string(), "timed out");
+    }
+
+    #[test]
+    fn test_elapsed_context() {
+        let start = Instant::now();
+        assert_eq!(Ok::<_, ()>(5).elapsed_context(start), Ok(5));
+        std::thread::sleep(Duration::from_millis(10));
+        let err = Err::<(), _>("boom").elapsed_context(start).unwrap_err();
+        assert!(err.elapsed >= Duration::from_millis(10));
+        assert_eq!(err.error, "boom");
+    }
+}
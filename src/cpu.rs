@@ -0,0 +1,417 @@
+//! CPU time alongside wall time, to tell a closure that was busy from one that was blocked.
+//!
+//! [`measure_times`] counts the calling thread's CPU time, and [`measure_process_cpu`] the
+//! whole process's user and system time, as `time(1)` reports them.
+//!
+//! Enabled with the `cpu-time` feature, on Unix and Windows.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// Wall-clock and CPU time for one measured call.
+///
+/// Its `Display` implementation renders `"1.200s wall, 0.300s cpu"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuTimes {
+    /// Real time that passed.
+    pub wall: Duration,
+    /// CPU time the calling thread consumed.
+    pub cpu: Duration,
+}
+
+impl CpuTimes {
+    /// Returns the fraction of the wall time spent on the CPU, e.g. `0.25` for a call that
+    /// waited on I/O three quarters of the time, or `0.0` if no wall time passed.
+    pub fn utilization(&self) -> f64 {
+        crate::ratio(self.cpu, self.wall)
+    }
+}
+
+impl fmt::Display for CpuTimes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} wall, {} cpu",
+            format_duration(self.wall),
+            format_duration(self.cpu)
+        )
+    }
+}
+
+/// Returns the CPU time the calling thread has consumed since it started.
+///
+/// This reads `clock_gettime(CLOCK_THREAD_CPUTIME_ID)` on Unix and `GetThreadTimes` on
+/// Windows, counting user and kernel time together. Returns `None` if the platform refuses.
+pub fn thread_cpu_time() -> Option<Duration> {
+    imp::thread_cpu_time()
+}
+
+/// Runs a closure and measures both the wall time and the CPU time it took.
+///
+/// Only the calling thread's CPU time is counted; work the closure hands to other threads
+/// shows up in wall time alone.
+///
+/// # Arguments
+///
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// A tuple of the closure's result and its [`CpuTimes`]. The CPU time is zero if the
+/// platform's thread clock could not be read.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::cpu::measure_times;
+///
+/// let ((), times) = measure_times(|| std::thread::sleep(Duration::from_millis(50)));
+/// assert!(times.wall >= Duration::from_millis(50));
+/// assert!(times.cpu < times.wall); // sleeping is waiting, not computing
+/// println!("{}", times); // e.g. "0.050s wall, 0s cpu"
+/// ```
+pub fn measure_times<F, T>(f: F) -> (T, CpuTimes)
+where
+    F: FnOnce() -> T,
+{
+    let cpu_start = thread_cpu_time();
+    let start = Instant::now();
+    let result = f();
+    let wall = start.elapsed();
+    let cpu = match (cpu_start, thread_cpu_time()) {
+        (Some(start), Some(end)) => end.saturating_sub(start),
+        _ => Duration::ZERO,
+    };
+    (result, CpuTimes { wall, cpu })
+}
+
+/// Runs a closure and returns its result with the formatted CPU time it consumed.
+///
+/// This is [`measure_times`] keeping only the CPU time, formatted by [`format_duration`].
+///
+/// # Arguments
+///
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// A tuple of the closure's result and a `String` representing the formatted CPU time.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::cpu::measure_cpu_time;
+///
+/// let (sum, cpu) = measure_cpu_time(|| (1..=1_000_000u64).sum::<u64>());
+/// assert_eq!(sum, 500_000_500_000);
+/// println!("summed using {} of CPU", cpu);
+/// ```
+pub fn measure_cpu_time<F, T>(f: F) -> (T, String)
+where
+    F: FnOnce() -> T,
+{
+    let (result, times) = measure_times(f);
+    (result, format_duration(times.cpu))
+}
+
+/// Wall-clock time and the process's user and system CPU time for one measured call.
+///
+/// Its `Display` implementation renders the `time(1)`-style
+/// `"1.200s real, 0.800s user, 0.100s sys"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessTimes {
+    /// Real time that passed.
+    pub wall: Duration,
+    /// CPU time all of the process's threads spent running its own code.
+    pub user: Duration,
+    /// CPU time the kernel spent working on the process's behalf.
+    pub system: Duration,
+}
+
+impl ProcessTimes {
+    /// Returns the total CPU time, `user + system`.
+    pub fn cpu(&self) -> Duration {
+        self.user.saturating_add(self.system)
+    }
+}
+
+impl fmt::Display for ProcessTimes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} real, {} user, {} sys",
+            format_duration(self.wall),
+            format_duration(self.user),
+            format_duration(self.system)
+        )
+    }
+}
+
+/// Returns the user and system CPU time the whole process has consumed since it started.
+///
+/// This reads `getrusage(RUSAGE_SELF)` on Unix and `GetProcessTimes` on Windows. Returns
+/// `None` if the platform refuses.
+pub fn process_cpu_time() -> Option<(Duration, Duration)> {
+    imp::process_cpu_time()
+}
+
+/// Waits for `child` to exit and returns its status with the user and system CPU time it
+/// consumed, via `wait4` on Unix and `GetProcessTimes` on Windows.
+pub(crate) fn wait_child(
+    child: &mut std::process::Child,
+) -> std::io::Result<(std::process::ExitStatus, Option<(Duration, Duration)>)> {
+    imp::wait_child(child)
+}
+
+/// Runs a closure and measures the wall time and the process-wide user and system CPU time
+/// consumed while it ran.
+///
+/// Unlike [`measure_times`], this counts every thread in the process, including work the
+/// closure hands to a thread pool, and also anything other threads did at the same time.
+///
+/// # Arguments
+///
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// A tuple of the closure's result and its [`ProcessTimes`]. The CPU times are zero if
+/// the platform's counters could not be read.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::cpu::measure_process_cpu;
+///
+/// let (sum, times) = measure_process_cpu(|| (1..=1_000_000u64).sum::<u64>());
+/// assert_eq!(sum, 500_000_500_000);
+/// println!("{}", times); // e.g. "0.004s real, 0.004s user, 0s sys"
+/// ```
+pub fn measure_process_cpu<F, T>(f: F) -> (T, ProcessTimes)
+where
+    F: FnOnce() -> T,
+{
+    let cpu_start = process_cpu_time();
+    let start = Instant::now();
+    let result = f();
+    let wall = start.elapsed();
+    let (user, system) = match (cpu_start, process_cpu_time()) {
+        (Some((user, system)), Some((user_end, system_end))) => (
+            user_end.saturating_sub(user),
+            system_end.saturating_sub(system),
+        ),
+        _ => (Duration::ZERO, Duration::ZERO),
+    };
+    (result, ProcessTimes { wall, user, system })
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::{Child, ExitStatus};
+    use std::time::Duration;
+
+    pub fn thread_cpu_time() -> Option<Duration> {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: `ts` is a valid, writable `timespec`.
+        if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) } != 0 {
+            return None;
+        }
+        Some(Duration::new(
+            u64::try_from(ts.tv_sec).ok()?,
+            u32::try_from(ts.tv_nsec).ok()?,
+        ))
+    }
+
+    pub fn process_cpu_time() -> Option<(Duration, Duration)> {
+        // SAFETY: `rusage` is plain old data, for which all zeroes is a valid value.
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `usage` is a valid, writable `rusage`.
+        if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+            return None;
+        }
+        Some((
+            timeval_to_duration(usage.ru_utime)?,
+            timeval_to_duration(usage.ru_stime)?,
+        ))
+    }
+
+    pub fn wait_child(child: &mut Child) -> io::Result<(ExitStatus, Option<(Duration, Duration)>)> {
+        // Close our end of a piped stdin first, as `Child::wait` does, so a child reading
+        // to EOF can exit.
+        drop(child.stdin.take());
+        let pid = child.id() as libc::pid_t;
+        let mut status = 0;
+        // SAFETY: `rusage` is plain old data, for which all zeroes is a valid value.
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `status` and `usage` are valid and writable, and `pid` is our own child,
+        // which nothing else waits on while we hold `&mut Child`.
+        while unsafe { libc::wait4(pid, &mut status, 0, &mut usage) } != pid {
+            let error = io::Error::last_os_error();
+            if error.kind() != io::ErrorKind::Interrupted {
+                return Err(error);
+            }
+        }
+        let times = timeval_to_duration(usage.ru_utime).zip(timeval_to_duration(usage.ru_stime));
+        Ok((ExitStatus::from_raw(status), times))
+    }
+
+    fn timeval_to_duration(time: libc::timeval) -> Option<Duration> {
+        let micros = u32::try_from(time.tv_usec).ok()?;
+        Some(Duration::new(
+            u64::try_from(time.tv_sec).ok()?,
+            micros * 1_000,
+        ))
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+    use std::process::{Child, ExitStatus};
+    use std::time::Duration;
+
+    use windows_sys::Win32::Foundation::{FILETIME, HANDLE};
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentThread, GetProcessTimes, GetThreadTimes,
+    };
+
+    /// Converts a `FILETIME` span, in 100-nanosecond ticks, to a `Duration`.
+    fn filetime_to_duration(time: &FILETIME) -> Duration {
+        let ticks = (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+        Duration::from_nanos(ticks.saturating_mul(100))
+    }
+
+    pub fn thread_cpu_time() -> Option<Duration> {
+        let zero = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let (mut creation, mut exit, mut kernel, mut user) = (zero, zero, zero, zero);
+        // SAFETY: the pseudo-handle from `GetCurrentThread` is always valid and all four
+        // pointers are to writable `FILETIME`s.
+        let ok = unsafe {
+            GetThreadTimes(
+                GetCurrentThread(),
+                &mut creation,
+                &mut exit,
+                &mut kernel,
+                &mut user,
+            )
+        };
+        if ok == 0 {
+            return None;
+        }
+        Some(filetime_to_duration(&kernel) + filetime_to_duration(&user))
+    }
+
+    pub fn process_cpu_time() -> Option<(Duration, Duration)> {
+        // SAFETY: `GetCurrentProcess` returns a pseudo-handle that is always valid.
+        unsafe { process_times(GetCurrentProcess()) }
+    }
+
+    pub fn wait_child(child: &mut Child) -> io::Result<(ExitStatus, Option<(Duration, Duration)>)> {
+        let status = child.wait()?;
+        // SAFETY: the handle stays open for as long as `child` is alive.
+        let times = unsafe { process_times(child.as_raw_handle()) };
+        Ok((status, times))
+    }
+
+    /// Returns the user and kernel time of the process behind `process`.
+    ///
+    /// # Safety
+    ///
+    /// `process` must be a valid process handle with query access.
+    unsafe fn process_times(process: HANDLE) -> Option<(Duration, Duration)> {
+        let zero = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let (mut creation, mut exit, mut kernel, mut user) = (zero, zero, zero, zero);
+        // SAFETY: the caller guarantees `process` is valid, and all four pointers are to
+        // writable `FILETIME`s.
+        let ok =
+            unsafe { GetProcessTimes(process, &mut creation, &mut exit, &mut kernel, &mut user) };
+        if ok == 0 {
+            return None;
+        }
+        Some((filetime_to_duration(&user), filetime_to_duration(&kernel)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_busy_closure_uses_cpu() {
+        let ((), times) = measure_times(|| {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(30) {
+                std::hint::black_box(0u64);
+            }
+        });
+        assert!(times.cpu > Duration::ZERO);
+        assert!(times.cpu <= times.wall + Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_sleep_uses_little_cpu() {
+        let ((), times) = measure_times(|| std::thread::sleep(Duration::from_millis(50)));
+        assert!(times.wall >= Duration::from_millis(50));
+        assert!(times.utilization() < 0.5);
+    }
+
+    #[test]
+    fn test_process_times() {
+        let ((), times) = measure_process_cpu(|| {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(30) {
+                std::hint::black_box(0u64);
+            }
+        });
+        assert!(times.wall >= Duration::from_millis(30));
+        assert!(times.cpu() > Duration::ZERO);
+
+        let times = ProcessTimes {
+            wall: Duration::from_millis(1_200),
+            user: Duration::from_millis(800),
+            system: Duration::from_millis(100),
+        };
+        assert_eq!(times.to_string(), "1.200s real, 0.800s user, 0.100s sys");
+        assert_eq!(times.cpu(), Duration::from_millis(900));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_child_closes_stdin() {
+        use std::process::{Command, Stdio};
+
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        let (status, times) = wait_child(&mut child).unwrap();
+        assert!(status.success());
+        assert!(times.is_some());
+    }
+
+    #[test]
+    fn test_display() {
+        let times = CpuTimes {
+            wall: Duration::from_millis(1_200),
+            cpu: Duration::from_millis(300),
+        };
+        assert_eq!(times.to_string(), "1.200s wall, 0.300s cpu");
+        assert_eq!(times.utilization(), 0.25);
+    }
+}
@@ -0,0 +1,33 @@
+//! Minimal CSV writing helpers for the `write_csv` exports.
+
+use std::borrow::Cow;
+use std::io;
+
+/// Writes one CSV record, quoting any field that contains a comma, quote or line break.
+pub(crate) fn write_record<W: io::Write>(out: &mut W, fields: &[&str]) -> io::Result<()> {
+    let fields: Vec<_> = fields.iter().map(|field| escape(field)).collect();
+    writeln!(out, "{}", fields.join(","))
+}
+
+fn escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_record_quotes_when_needed() {
+        let mut out = Vec::new();
+        write_record(&mut out, &["plain", "a,b", "say \"hi\"", "two\nlines"]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\"\n"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// How far ahead a deadline is placed when its timeout does not fit in an `Instant`;
+/// about 30 years, which is never reached in practice.
+const FAR_FUTURE: Duration = Duration::from_secs(30 * 365 * 24 * 60 * 60);
+
+/// A point in time by which something should finish.
+///
+/// Its `Display` implementation shows the time remaining, read from the clock when it is
+/// formatted, or `"expired"` once the deadline has passed, so the same value can drive a
+/// timeout check and report it.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::Deadline;
+///
+/// let deadline = Deadline::after(Duration::from_secs(30));
+/// while !deadline.is_expired() {
+///     // ... poll for a result ...
+///     println!("waiting, {} left", deadline);
+///     break;
+/// }
+/// assert!(deadline.remaining() <= Duration::from_secs(30));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// Creates a deadline `timeout` from now.
+    ///
+    /// Timeouts too long for the platform's clock, such as `Duration::MAX`, give a
+    /// deadline in the far future instead.
+    pub fn after(timeout: Duration) -> Self {
+        Deadline::after_at(Instant::now(), timeout)
+    }
+
+    fn after_at(now: Instant, timeout: Duration) -> Self {
+        let at = now
+            .checked_add(timeout)
+            .or_else(|| now.checked_add(timeout.min(FAR_FUTURE)))
+            .unwrap_or(now);
+        Deadline::at(at)
+    }
+
+    /// Creates a deadline at the given instant.
+    pub fn at(at: Instant) -> Self {
+        Deadline { at }
+    }
+
+    /// Returns the instant the deadline falls at.
+    pub fn instant(&self) -> Instant {
+        self.at
+    }
+
+    /// Returns `true` if the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Instant::now())
+    }
+
+    fn is_expired_at(&self, now: Instant) -> bool {
+        now >= self.at
+    }
+
+    /// Returns the time left until the deadline, or zero once it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> Duration {
+        self.at.saturating_duration_since(now)
+    }
+
+    fn format_at(&self, now: Instant) -> String {
+        if self.is_expired_at(now) {
+            "expired".to_string()
+        } else {
+            format_duration(self.remaining_at(now))
+        }
+    }
+}
+
+impl From<Instant> for Deadline {
+    fn from(at: Instant) -> Self {
+        Deadline::at(at)
+    }
+}
+
+impl From<Duration> for Deadline {
+    /// Creates a deadline `timeout` from now.
+    fn from(timeout: Duration) -> Self {
+        Deadline::after(timeout)
+    }
+}
+
+impl fmt::Display for Deadline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_at(Instant::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_and_expiry() {
+        let t0 = Instant::now();
+        let deadline = Deadline::at(t0 + Duration::from_secs(90));
+        assert!(!deadline.is_expired_at(t0));
+        assert_eq!(deadline.remaining_at(t0 + Duration::from_secs(30)), Duration::from_secs(60));
+        assert_eq!(deadline.format_at(t0 + Duration::from_secs(5)), "1m 25s");
+
+        let later = t0 + Duration::from_secs(91);
+        assert!(deadline.is_expired_at(later));
+        assert_eq!(deadline.remaining_at(later), Duration::ZERO);
+        assert_eq!(deadline.format_at(later), "expired");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let t0 = Instant::now();
+        assert_eq!(Deadline::from(t0).instant(), t0);
+        let deadline: Deadline = Duration::from_secs(60).into();
+        assert!(deadline.instant() >= t0 + Duration::from_secs(60));
+        assert!(Deadline::after(Duration::ZERO).is_expired());
+    }
+
+    #[test]
+    fn test_overlong_timeout_saturates() {
+        let t0 = Instant::now();
+        let far = t0 + FAR_FUTURE;
+        for timeout in [Duration::MAX, Duration::from_secs(u64::MAX / 2)] {
+            let deadline = Deadline::after_at(t0, timeout);
+            assert!(deadline.instant() >= far);
+            assert!(!deadline.is_expired());
+            assert!(Deadline::from(timeout).remaining() > Duration::from_secs(60));
+        }
+    }
+}
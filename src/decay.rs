@@ -0,0 +1,267 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+use crate::rng::SplitMix64;
+
+/// Number of samples kept by [`DecayingStats::new`].
+const DEFAULT_SIZE: usize = 1028;
+
+/// Decay rate of [`DecayingStats::new`], which weights roughly the last five minutes.
+const DEFAULT_ALPHA: f64 = 0.015;
+
+/// How often sample weights are renormalized so they stay within `f64` range.
+const RESCALE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    priority: f64,
+    weight: f64,
+    duration: Duration,
+}
+
+/// Statistics over a stream of durations that favour recent measurements.
+///
+/// A fixed number of samples is kept, chosen so that each measurement's influence decays
+/// exponentially with its age (forward-decay priority sampling, as in the Metrics
+/// library's exponentially decaying reservoir). Take a [`snapshot`](DecayingStats::snapshot)
+/// on each flush of a periodic reporter to get min, mean and percentiles that reflect the
+/// last few minutes rather than the whole life of the process.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::DecayingStats;
+///
+/// let mut stats = DecayingStats::new();
+/// for ms in [120, 80, 95, 430] {
+///     stats.update(Duration::from_millis(ms));
+/// }
+///
+/// let snapshot = stats.snapshot();
+/// assert_eq!(snapshot.max(), Some(Duration::from_millis(430)));
+/// println!("{}", snapshot); // "min 0.080s  mean 0.181s  p50 0.095s  p99 0.430s  max 0.430s"
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecayingStats {
+    size: usize,
+    alpha: f64,
+    landmark: Instant,
+    next_rescale: Instant,
+    samples: Vec<Sample>,
+    rng: SplitMix64,
+}
+
+impl Default for DecayingStats {
+    fn default() -> Self {
+        DecayingStats::new()
+    }
+}
+
+impl DecayingStats {
+    /// Creates a collector keeping 1028 samples that are biased towards the last five minutes.
+    pub fn new() -> Self {
+        DecayingStats::with_params(DEFAULT_SIZE, DEFAULT_ALPHA)
+    }
+
+    /// Creates a collector keeping `size` samples, where a measurement `t` seconds old
+    /// weighs `e^(-alpha * t)` relative to a new one.
+    pub fn with_params(size: usize, alpha: f64) -> Self {
+        DecayingStats::with_params_at(size, alpha, Instant::now(), SplitMix64::from_entropy())
+    }
+
+    fn with_params_at(size: usize, alpha: f64, now: Instant, rng: SplitMix64) -> Self {
+        DecayingStats {
+            size,
+            alpha,
+            landmark: now,
+            next_rescale: now + RESCALE_INTERVAL,
+            samples: Vec::with_capacity(size),
+            rng,
+        }
+    }
+
+    /// Records one measurement.
+    pub fn update(&mut self, duration: Duration) {
+        self.update_at(duration, Instant::now());
+    }
+
+    fn update_at(&mut self, duration: Duration, now: Instant) {
+        if now >= self.next_rescale {
+            self.rescale(now);
+        }
+        let age = now.saturating_duration_since(self.landmark).as_secs_f64();
+        let weight = (self.alpha * age).exp();
+        let sample = Sample {
+            priority: weight / self.rng.unit(),
+            weight,
+            duration,
+        };
+
+        if self.samples.len() < self.size {
+            self.samples.push(sample);
+            return;
+        }
+        let lowest = self
+            .samples
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.priority.total_cmp(&b.priority))
+            .map(|(i, _)| i);
+        if let Some(lowest) = lowest {
+            if self.samples[lowest].priority < sample.priority {
+                self.samples[lowest] = sample;
+            }
+        }
+    }
+
+    /// Moves the landmark to `now`, scaling existing weights down so they don't overflow.
+    fn rescale(&mut self, now: Instant) {
+        let age = now.saturating_duration_since(self.landmark).as_secs_f64();
+        let factor = (-self.alpha * age).exp();
+        for sample in &mut self.samples {
+            sample.priority *= factor;
+            sample.weight *= factor;
+        }
+        self.landmark = now;
+        self.next_rescale = now + RESCALE_INTERVAL;
+    }
+
+    /// Returns the current weighted samples for computing statistics.
+    pub fn snapshot(&self) -> DecayingSnapshot {
+        let total: f64 = self.samples.iter().map(|s| s.weight).sum();
+        let mut values: Vec<(Duration, f64)> = self
+            .samples
+            .iter()
+            .map(|s| (s.duration, s.weight / total))
+            .collect();
+        values.sort_unstable_by_key(|(duration, _)| *duration);
+        DecayingSnapshot { values }
+    }
+}
+
+/// A point-in-time view of a [`DecayingStats`], with each sample's normalized weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayingSnapshot {
+    values: Vec<(Duration, f64)>,
+}
+
+impl DecayingSnapshot {
+    /// Returns the number of samples in the snapshot.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no measurements have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the smallest sampled duration.
+    pub fn min(&self) -> Option<Duration> {
+        self.values.first().map(|(d, _)| *d)
+    }
+
+    /// Returns the largest sampled duration.
+    pub fn max(&self) -> Option<Duration> {
+        self.values.last().map(|(d, _)| *d)
+    }
+
+    /// Returns the weighted mean, in which recent measurements count more.
+    pub fn mean(&self) -> Option<Duration> {
+        if self.is_empty() {
+            return None;
+        }
+        let secs: f64 = self.values.iter().map(|(d, w)| d.as_secs_f64() * w).sum();
+        Some(Duration::from_secs_f64(secs))
+    }
+
+    /// Returns the weighted `q`-quantile, e.g. `0.99` for p99.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        let mut seen = 0.0;
+        for (duration, weight) in &self.values {
+            seen += weight;
+            if seen >= q {
+                return Some(*duration);
+            }
+        }
+        self.max()
+    }
+}
+
+impl fmt::Display for DecayingSnapshot {
+    /// Renders `"min 0.080s  mean 0.181s  p50 0.095s  p99 0.430s  max 0.430s"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (Some(min), Some(mean), Some(p50), Some(p99), Some(max)) = (
+            self.min(),
+            self.mean(),
+            self.quantile(0.5),
+            self.quantile(0.99),
+            self.max(),
+        ) else {
+            return f.write_str("no samples");
+        };
+        write!(
+            f,
+            "min {}  mean {}  p50 {}  p99 {}  max {}",
+            format_duration(min),
+            format_duration(mean),
+            format_duration(p50),
+            format_duration(p99),
+            format_duration(max)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_below_capacity() {
+        let t0 = Instant::now();
+        let mut stats = DecayingStats::with_params_at(10, 0.0, t0, SplitMix64::new(7));
+        assert_eq!(stats.snapshot().to_string(), "no samples");
+        for ms in [300, 100, 200] {
+            stats.update_at(Duration::from_millis(ms), t0);
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot.min(), Some(Duration::from_millis(100)));
+        assert_eq!(snapshot.quantile(0.5), Some(Duration::from_millis(200)));
+        assert_eq!(snapshot.mean(), Some(Duration::from_millis(200)));
+        assert_eq!(
+            snapshot.to_string(),
+            "min 0.100s  mean 0.200s  p50 0.200s  p99 0.300s  max 0.300s"
+        );
+    }
+
+    #[test]
+    fn test_recent_measurements_dominate() {
+        let t0 = Instant::now();
+        let mut stats = DecayingStats::with_params_at(100, DEFAULT_ALPHA, t0, SplitMix64::new(1));
+        // Ten minutes of fast requests, then one minute of slow ones.
+        for s in 0..600 {
+            stats.update_at(Duration::from_millis(10), t0 + Duration::from_secs(s));
+        }
+        for s in 600..660 {
+            stats.update_at(Duration::from_millis(500), t0 + Duration::from_secs(s));
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 100);
+        // Only a tenth of all measurements are slow, but they carry most of the weight.
+        assert_eq!(snapshot.quantile(0.5), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_rescale_keeps_weights_finite() {
+        let t0 = Instant::now();
+        let mut stats = DecayingStats::with_params_at(10, DEFAULT_ALPHA, t0, SplitMix64::new(3));
+        for hour in 0..48 {
+            stats.update_at(Duration::from_secs(1), t0 + Duration::from_secs(hour * 3600));
+        }
+        assert!(stats.samples.iter().all(|s| s.weight.is_finite() && s.priority.is_finite()));
+        assert_eq!(stats.snapshot().mean(), Some(Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,208 @@
+//! Comparing two sets of labelled measurements, e.g. a baseline run and a new run.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::{format_duration, SignedDuration};
+
+/// One label's timings in the old and new runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRow {
+    pub label: String,
+    /// The baseline timing, or `None` if the label is new.
+    pub old: Option<Duration>,
+    /// The new timing, or `None` if the label disappeared.
+    pub new: Option<Duration>,
+}
+
+impl DiffRow {
+    /// Returns `new - old` in nanoseconds, if the label is present in both runs.
+    pub fn delta_nanos(&self) -> Option<i128> {
+        Some(self.new?.as_nanos() as i128 - self.old?.as_nanos() as i128)
+    }
+
+    /// Returns how many times faster the new run is (`old / new`), e.g. `2.0` for twice as fast.
+    pub fn speedup(&self) -> Option<f64> {
+        let (old, new) = (self.old?, self.new?);
+        if new.is_zero() {
+            return None;
+        }
+        Some(old.as_secs_f64() / new.as_secs_f64())
+    }
+
+    /// Returns `true` if the new timing is slower than the old one by more than `threshold`,
+    /// a fraction such as `0.05` for 5%.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        match (self.old, self.new) {
+            (Some(old), Some(new)) => new.as_secs_f64() > old.as_secs_f64() * (1.0 + threshold),
+            _ => false,
+        }
+    }
+}
+
+/// Formats a signed nanosecond delta as `"+1.200s"` or `"-0.300s"`.
+fn format_delta(nanos: i128) -> String {
+    format!("{:+}", SignedDuration::from_nanos(nanos))
+}
+
+/// The result of comparing two runs, label by label.
+///
+/// Its `Display` implementation renders an aligned table with deltas and speedups and
+/// marks rows slower than the threshold with `REGRESSION`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::diff::Comparison;
+///
+/// let old = vec![("parse".to_string(), Duration::from_millis(1200))];
+/// let new = vec![("parse".to_string(), Duration::from_millis(1500))];
+/// let comparison = Comparison::new(old, new, 0.05);
+///
+/// assert_eq!(comparison.regressions().count(), 1);
+/// println!("{}", comparison);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub rows: Vec<DiffRow>,
+    /// Fraction by which a timing may grow before it counts as a regression.
+    pub threshold: f64,
+}
+
+impl Comparison {
+    /// Aligns `old` and `new` measurements by label.
+    ///
+    /// Rows follow the order of `old`, followed by labels only present in `new`. If a label
+    /// appears more than once in a run, its last timing wins.
+    pub fn new<I, J>(old: I, new: J, threshold: f64) -> Self
+    where
+        I: IntoIterator<Item = (String, Duration)>,
+        J: IntoIterator<Item = (String, Duration)>,
+    {
+        let mut rows: Vec<DiffRow> = Vec::new();
+        for (label, duration) in old {
+            match rows.iter_mut().find(|row| row.label == label) {
+                Some(row) => row.old = Some(duration),
+                None => rows.push(DiffRow {
+                    label,
+                    old: Some(duration),
+                    new: None,
+                }),
+            }
+        }
+        for (label, duration) in new {
+            match rows.iter_mut().find(|row| row.label == label) {
+                Some(row) => row.new = Some(duration),
+                None => rows.push(DiffRow {
+                    label,
+                    old: None,
+                    new: Some(duration),
+                }),
+            }
+        }
+        Comparison { rows, threshold }
+    }
+
+    /// Returns the rows that got slower by more than the threshold.
+    pub fn regressions(&self) -> impl Iterator<Item = &DiffRow> {
+        self.rows.iter().filter(move |row| row.is_regression(self.threshold))
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let missing = || "-".to_string();
+        let cells: Vec<[String; 5]> = self
+            .rows
+            .iter()
+            .map(|row| {
+                [
+                    row.label.clone(),
+                    row.old.map_or_else(missing, format_duration),
+                    row.new.map_or_else(missing, format_duration),
+                    row.delta_nanos().map_or_else(missing, format_delta),
+                    row.speedup().map_or_else(missing, |s| format!("{:.2}x", s)),
+                ]
+            })
+            .collect();
+
+        let header = ["label", "old", "new", "delta", "speedup"].map(String::from);
+        let mut widths = header.clone().map(|h| h.len());
+        for row in &cells {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let write_row = |f: &mut fmt::Formatter<'_>, row: &[String; 5]| -> fmt::Result {
+            write!(f, "{:<w$}", row[0], w = widths[0])?;
+            for (cell, width) in row[1..].iter().zip(&widths[1..]) {
+                write!(f, "  {:>w$}", cell, w = width)?;
+            }
+            Ok(())
+        };
+
+        write_row(f, &header)?;
+        for (row, cells) in self.rows.iter().zip(&cells) {
+            writeln!(f)?;
+            write_row(f, cells)?;
+            if row.is_regression(self.threshold) {
+                write!(f, "  REGRESSION")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(entries: &[(&str, u64)]) -> Vec<(String, Duration)> {
+        entries
+            .iter()
+            .map(|(label, ms)| (label.to_string(), Duration::from_millis(*ms)))
+            .collect()
+    }
+
+    #[test]
+    fn test_rows_aligned_by_label() {
+        let comparison = Comparison::new(
+            run(&[("parse", 1200), ("db", 2000), ("gone", 10)]),
+            run(&[("db", 2500), ("parse", 1000), ("added", 5)]),
+            0.05,
+        );
+        let labels: Vec<_> = comparison.rows.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels, ["parse", "db", "gone", "added"]);
+
+        let parse = &comparison.rows[0];
+        assert_eq!(parse.delta_nanos(), Some(-200_000_000));
+        assert_eq!(parse.speedup(), Some(1.2));
+        assert!(!parse.is_regression(0.05));
+
+        let db = &comparison.rows[1];
+        assert!(db.is_regression(0.05));
+        assert!(!db.is_regression(0.5));
+        assert_eq!(comparison.regressions().count(), 1);
+
+        assert_eq!(comparison.rows[2].new, None);
+        assert_eq!(comparison.rows[3].speedup(), None);
+    }
+
+    #[test]
+    fn test_display_table() {
+        let comparison = Comparison::new(
+            run(&[("parse", 1200), ("db", 2000)]),
+            run(&[("parse", 1000), ("db", 2500), ("new", 5)]),
+            0.05,
+        );
+        assert_eq!(
+            comparison.to_string(),
+            "label     old     new    delta  speedup\n\
+             parse  1.200s      1s  -0.200s    1.20x\n\
+             db         2s  2.500s  +0.500s    0.80x  REGRESSION\n\
+             new         -  0.005s        -        -"
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// Smoothing factor used by [`RemainingEstimator::new`].
+const DEFAULT_SMOOTHING: f64 = 0.2;
+
+/// Estimates the time remaining for a job from periodic progress samples.
+///
+/// Each call to [`sample`](RemainingEstimator::sample) computes the rate since the previous
+/// sample and folds it into an exponentially smoothed rate, so a single slow or fast batch
+/// doesn't make the ETA jump around the way a naive `elapsed * total / done` estimate does.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use elapsed_time::RemainingEstimator;
+///
+/// let start = Instant::now();
+/// let mut estimator = RemainingEstimator::new(100);
+/// estimator.sample(start, 0);
+/// estimator.sample(start + Duration::from_secs(10), 10);
+///
+/// // 10 units per 10 seconds leaves 90 seconds for the remaining 90 units.
+/// assert_eq!(estimator.remaining(), Some(Duration::from_secs(90)));
+/// assert_eq!(estimator.eta().as_deref(), Some("1m 30s"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RemainingEstimator {
+    total: u64,
+    smoothing: f64,
+    last: Option<(Instant, u64)>,
+    rate: Option<f64>,
+}
+
+impl RemainingEstimator {
+    /// Creates an estimator for a job of `total` units with the default smoothing.
+    pub fn new(total: u64) -> Self {
+        Self::with_smoothing(total, DEFAULT_SMOOTHING)
+    }
+
+    /// Creates an estimator with a custom smoothing factor.
+    ///
+    /// `smoothing` is the weight given to the newest rate, clamped to `0.0..=1.0`:
+    /// `1.0` trusts only the latest sample, values near `0.0` change very slowly.
+    pub fn with_smoothing(total: u64, smoothing: f64) -> Self {
+        let smoothing = if smoothing.is_nan() { DEFAULT_SMOOTHING } else { smoothing.clamp(0.0, 1.0) };
+        RemainingEstimator {
+            total,
+            smoothing,
+            last: None,
+            rate: None,
+        }
+    }
+
+    /// Records that `completed` units were done at time `at`.
+    ///
+    /// Samples that don't move forward in time are ignored for the rate. A sample with
+    /// fewer completed units than the previous one resets the rate history.
+    pub fn sample(&mut self, at: Instant, completed: u64) {
+        let Some((last_at, last_completed)) = self.last else {
+            self.last = Some((at, completed));
+            return;
+        };
+
+        let elapsed = at.saturating_duration_since(last_at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return;
+        }
+        if completed < last_completed {
+            self.rate = None;
+            self.last = Some((at, completed));
+            return;
+        }
+
+        let instant_rate = (completed - last_completed) as f64 / elapsed;
+        self.rate = Some(match self.rate {
+            Some(rate) => self.smoothing * instant_rate + (1.0 - self.smoothing) * rate,
+            None => instant_rate,
+        });
+        self.last = Some((at, completed));
+    }
+
+    /// Returns the smoothed rate in units per second, if at least two samples were recorded.
+    pub fn rate(&self) -> Option<f64> {
+        self.rate
+    }
+
+    /// Returns the estimated time remaining after the latest sample.
+    ///
+    /// `None` until a rate is known, or while the smoothed rate is zero.
+    pub fn remaining(&self) -> Option<Duration> {
+        let (_, completed) = self.last?;
+        let rate = self.rate?;
+        let left = self.total.saturating_sub(completed);
+        if left == 0 {
+            return Some(Duration::ZERO);
+        }
+        if rate <= 0.0 {
+            return None;
+        }
+        Some(Duration::try_from_secs_f64(left as f64 / rate).unwrap_or(Duration::MAX))
+    }
+
+    /// Returns the estimated time remaining formatted with [`format_duration`].
+    ///
+    /// Sub-second estimates keep millisecond precision, so the string stays stable as the
+    /// job approaches completion.
+    pub fn eta(&self) -> Option<String> {
+        self.remaining().map(format_duration)
+    }
+}
+
+/// Tracks a batch job's progress from its start and estimates when it will finish.
+///
+/// Feed it the number of completed units with [`update`](Progress::update) or
+/// [`inc`](Progress::inc); the rate and ETA are the average over the whole run so far, which
+/// suits jobs whose units take roughly the same time. For jobs with uneven phases, a
+/// [`RemainingEstimator`] reacts faster. Its `Display` implementation renders
+/// `"45/100 (45%), about 4m 30s remaining"`.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::Progress;
+///
+/// let items = vec![1, 2, 3, 4];
+/// let mut progress = Progress::new(items.len() as u64);
+/// for _item in &items {
+///     // ... process the item ...
+///     progress.inc(1);
+///     if let Some(eta) = progress.eta_formatted() {
+///         println!("{}", eta); // "about 4m 30s remaining"
+///     }
+/// }
+/// assert!(progress.is_done());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Progress {
+    start: Instant,
+    completed: u64,
+    total: u64,
+}
+
+impl Progress {
+    /// Starts tracking a job of `total` units, counting from now.
+    pub fn new(total: u64) -> Self {
+        Progress::starting_at(Instant::now(), total)
+    }
+
+    fn starting_at(start: Instant, total: u64) -> Self {
+        Progress {
+            start,
+            completed: 0,
+            total,
+        }
+    }
+
+    /// Records that `completed` units are done in total.
+    pub fn update(&mut self, completed: u64) {
+        self.completed = completed;
+    }
+
+    /// Records that `n` more units are done.
+    pub fn inc(&mut self, n: u64) {
+        self.completed = self.completed.saturating_add(n);
+    }
+
+    /// Changes the size of the job, for jobs that discover more work as they go.
+    pub fn set_total(&mut self, total: u64) {
+        self.total = total;
+    }
+
+    /// Returns the number of units done.
+    pub fn completed(&self) -> u64 {
+        self.completed
+    }
+
+    /// Returns the size of the job.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns `true` once every unit is done.
+    pub fn is_done(&self) -> bool {
+        self.completed >= self.total
+    }
+
+    /// Returns the fraction of the job done, from `0.0` to `1.0`; an empty job is done.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.completed as f64 / self.total as f64).min(1.0)
+    }
+
+    /// Returns the time since tracking started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the average rate so far in units per second, or `None` before any time has
+    /// passed.
+    pub fn rate(&self) -> Option<f64> {
+        self.rate_at(Instant::now())
+    }
+
+    fn rate_at(&self, now: Instant) -> Option<f64> {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f64();
+        (elapsed > 0.0).then(|| self.completed as f64 / elapsed)
+    }
+
+    /// Returns the estimated time until the job is done at the average rate so far.
+    ///
+    /// Zero once the job is done; `None` while nothing has been completed yet.
+    pub fn eta(&self) -> Option<Duration> {
+        self.eta_at(Instant::now())
+    }
+
+    fn eta_at(&self, now: Instant) -> Option<Duration> {
+        let left = self.total.saturating_sub(self.completed);
+        if left == 0 {
+            return Some(Duration::ZERO);
+        }
+        let rate = self.rate_at(now).filter(|rate| *rate > 0.0)?;
+        Some(Duration::try_from_secs_f64(left as f64 / rate).unwrap_or(Duration::MAX))
+    }
+
+    /// Returns the estimate as `"about 4m 30s remaining"`.
+    pub fn eta_formatted(&self) -> Option<String> {
+        self.eta().map(format_remaining)
+    }
+}
+
+/// Formats an ETA as `"about 4m 30s remaining"`.
+fn format_remaining(eta: Duration) -> String {
+    format!("about {} remaining", format_duration(eta))
+}
+
+impl fmt::Display for Progress {
+    /// Renders `"45/100 (45%), about 4m 30s remaining"`, or `"45/100 (45%)"` before an
+    /// estimate is available.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} ({:.0}%)",
+            self.completed,
+            self.total,
+            self.fraction() * 100.0
+        )?;
+        match self.eta() {
+            Some(eta) => write!(f, ", {}", format_remaining(eta)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_two_samples() {
+        let start = Instant::now();
+        let mut estimator = RemainingEstimator::new(10);
+        assert_eq!(estimator.eta(), None);
+        estimator.sample(start, 0);
+        assert_eq!(estimator.eta(), None);
+        estimator.sample(start, 1);
+        assert_eq!(estimator.rate(), None);
+        estimator.sample(start + Duration::from_secs(1), 1);
+        assert_eq!(estimator.eta().as_deref(), Some("9s"));
+    }
+
+    #[test]
+    fn test_smoothing_damps_jitter() {
+        let start = Instant::now();
+        let mut estimator = RemainingEstimator::with_smoothing(1000, 0.5);
+        estimator.sample(start, 0);
+        estimator.sample(start + Duration::from_secs(1), 10);
+        // A sudden burst of 30 units only moves the rate halfway there.
+        estimator.sample(start + Duration::from_secs(2), 40);
+        assert_eq!(estimator.rate(), Some(20.0));
+        assert_eq!(estimator.remaining(), Some(Duration::from_secs(48)));
+    }
+
+    #[test]
+    fn test_done_and_stalled() {
+        let start = Instant::now();
+        let mut estimator = RemainingEstimator::with_smoothing(5, 1.0);
+        estimator.sample(start, 0);
+        estimator.sample(start + Duration::from_secs(1), 0);
+        assert_eq!(estimator.remaining(), None);
+        estimator.sample(start + Duration::from_secs(2), 5);
+        assert_eq!(estimator.remaining(), Some(Duration::ZERO));
+        assert_eq!(estimator.eta().as_deref(), Some("0s"));
+    }
+
+    #[test]
+    fn test_progress_eta() {
+        let start = Instant::now();
+        let mut progress = Progress::starting_at(start, 100);
+        assert_eq!(progress.eta_at(start + Duration::from_secs(10)), None);
+
+        progress.update(45);
+        let later = start + Duration::from_secs(45);
+        assert_eq!(progress.rate_at(later), Some(1.0));
+        assert_eq!(progress.eta_at(later), Some(Duration::from_secs(55)));
+        assert_eq!(progress.rate_at(start), None);
+        assert_eq!(
+            format_remaining(progress.eta_at(later).unwrap()),
+            "about 55s remaining"
+        );
+
+        progress.inc(55);
+        assert!(progress.is_done());
+        assert_eq!(progress.eta_at(later), Some(Duration::ZERO));
+        progress.set_total(200);
+        assert_eq!(progress.fraction(), 0.5);
+        assert_eq!(Progress::new(0).fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_progress_display() {
+        let mut progress = Progress::new(100);
+        assert_eq!(progress.to_string(), "0/100 (0%)");
+        progress.update(100);
+        assert_eq!(progress.to_string(), "100/100 (100%), about 0s remaining");
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(feature = "std")]
+use alloc::string::String;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::{DisplayDuration, DurationFormatter};
+
+/// Formats a `Duration` fluently: `start.elapsed().human()`.
+///
+/// Both methods return a [`DisplayDuration`], which formats only when displayed; call
+/// `to_string()` on it for an owned `String`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{DurationExt, DurationFormatter, UnitStyle};
+///
+/// let took = Duration::from_millis(90_500);
+/// assert_eq!(took.human().to_string(), "1m 30.500s");
+///
+/// let long = DurationFormatter::new().precision(0).unit_style(UnitStyle::Long);
+/// println!("took {}", took.human_with(&long)); // "took 1 minute 30 seconds"
+/// ```
+pub trait DurationExt {
+    /// Returns the duration in the [`format_duration`](crate::format_duration) format.
+    fn human(&self) -> DisplayDuration<'static>;
+
+    /// Returns the duration formatted with `formatter`'s settings.
+    fn human_with<'a>(&self, formatter: &'a DurationFormatter) -> DisplayDuration<'a>;
+}
+
+impl DurationExt for Duration {
+    fn human(&self) -> DisplayDuration<'static> {
+        DisplayDuration::new(*self)
+    }
+
+    fn human_with<'a>(&self, formatter: &'a DurationFormatter) -> DisplayDuration<'a> {
+        formatter.display(*self)
+    }
+}
+
+/// Formats the time since an `Instant` directly: `start.elapsed_human()`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Instant;
+/// use elapsed_time::InstantExt;
+///
+/// let start = Instant::now();
+/// let checkpoint = Instant::now();
+/// println!("ready after {}", start.elapsed_human()); // e.g. "ready after 0.012s"
+/// println!("setup took {}", checkpoint.elapsed_since_human(start));
+/// assert_eq!(start.elapsed_since_human(checkpoint), "0s");
+/// ```
+#[cfg(feature = "std")]
+pub trait InstantExt {
+    /// Returns the time elapsed since this instant, formatted with
+    /// [`format_duration`](crate::format_duration).
+    fn elapsed_human(&self) -> String;
+
+    /// Returns the time from `earlier` to this instant, formatted with
+    /// [`format_duration`](crate::format_duration), or `"0s"` if `earlier` is later.
+    fn elapsed_since_human(&self, earlier: Instant) -> String;
+}
+
+#[cfg(feature = "std")]
+impl InstantExt for Instant {
+    fn elapsed_human(&self) -> String {
+        crate::format_duration(self.elapsed())
+    }
+
+    fn elapsed_since_human(&self, earlier: Instant) -> String {
+        crate::format_duration(self.saturating_duration_since(earlier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human() {
+        let took = Duration::from_secs(125);
+        assert_eq!(took.human().to_string(), "2m 5s");
+        assert_eq!(took.human().duration(), took);
+        let formatter = DurationFormatter::new().separator("");
+        assert_eq!(took.human_with(&formatter).to_string(), "2m5s");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_instant_ext() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(125);
+        assert_eq!(later.elapsed_since_human(start), "2m 5s");
+        assert_eq!(start.elapsed_since_human(later), "0s");
+        std::thread::sleep(Duration::from_millis(10));
+        assert_ne!(start.elapsed_human(), "0s");
+    }
+}
@@ -0,0 +1,124 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// Measures how long a future takes to complete.
+///
+/// The clock starts when the returned future is first polled, i.e. when it is awaited, and
+/// includes time spent waiting on I/O or timers. It works with any executor.
+///
+/// # Arguments
+///
+/// * `fut` - The future to await.
+///
+/// # Returns
+///
+/// A tuple of the future's output and the elapsed `Duration`.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_elapsed_time_async;
+///
+/// # async fn fetch() -> u32 { 42 }
+/// # async fn run() {
+/// let (value, elapsed) = measure_elapsed_time_async(fetch()).await;
+/// println!("fetched {} in {:?}", value, elapsed);
+/// # }
+/// ```
+pub async fn measure_elapsed_time_async<F>(fut: F) -> (F::Output, Duration)
+where
+    F: Future,
+{
+    let start = Instant::now();
+    let output = fut.await;
+    (output, start.elapsed())
+}
+
+/// Measures how long a future takes to complete and formats the result.
+///
+/// This is [`measure_elapsed_time_async`] with the duration formatted by
+/// [`format_duration`].
+///
+/// # Arguments
+///
+/// * `fut` - The future to await.
+///
+/// # Returns
+///
+/// A tuple of the future's output and a `String` representing the formatted elapsed time.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_elapsed_time_async_formatted;
+///
+/// # async fn fetch() -> u32 { 42 }
+/// # async fn run() {
+/// let (value, elapsed) = measure_elapsed_time_async_formatted(fetch()).await;
+/// println!("fetched {} in {}", value, elapsed); // e.g. "fetched 42 in 0.120s"
+/// # }
+/// ```
+pub async fn measure_elapsed_time_async_formatted<F>(fut: F) -> (F::Output, String)
+where
+    F: Future,
+{
+    let (output, elapsed) = measure_elapsed_time_async(fut).await;
+    (output, format_duration(elapsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+    use std::thread;
+
+    /// Polls `fut` to completion on this thread, sleeping briefly while it is pending.
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// A future that stays pending until `until` has passed.
+    async fn sleep_until(until: Instant) {
+        std::future::poll_fn(|_| {
+            if Instant::now() >= until {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    #[test]
+    fn test_measures_time_across_pending_polls() {
+        let fut = async {
+            sleep_until(Instant::now() + Duration::from_millis(30)).await;
+            "done"
+        };
+        let (output, elapsed) = block_on(measure_elapsed_time_async(fut));
+        assert_eq!(output, "done");
+        assert!(elapsed >= Duration::from_millis(30));
+        assert!(elapsed < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_formatted() {
+        let fut = async {
+            sleep_until(Instant::now() + Duration::from_millis(30)).await;
+            5
+        };
+        let (output, elapsed) = block_on(measure_elapsed_time_async_formatted(fut));
+        assert_eq!(output, 5);
+        assert!(crate::parse_duration(&elapsed).unwrap() >= Duration::from_millis(30));
+    }
+}
@@ -0,0 +1,247 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// A named timer inside a [`TimerGroup`].
+#[derive(Debug, Clone)]
+struct Member {
+    name: String,
+    accumulated: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Member {
+    fn stop_at(&mut self, now: Instant) {
+        if let Some(started) = self.running_since.take() {
+            self.accumulated += now.saturating_duration_since(started);
+        }
+    }
+}
+
+/// A set of named timers measured against a common group total.
+///
+/// The group's clock starts when it is created. Member timers can be started and stopped
+/// any number of times (their time accumulates) and when the group is
+/// [closed](TimerGroup::close) the report lists every member, the group total, and the
+/// time not covered by any member.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::TimerGroup;
+///
+/// let mut group = TimerGroup::new("build");
+/// group.time("compile", || { /* ... */ });
+/// group.start("link");
+/// // ...
+/// group.stop("link");
+///
+/// let report = group.close();
+/// assert_eq!(report.members.len(), 2);
+/// println!("{}", report);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimerGroup {
+    name: String,
+    start: Instant,
+    members: Vec<Member>,
+}
+
+impl TimerGroup {
+    /// Creates a group called `name` and starts its clock.
+    pub fn new(name: impl Into<String>) -> Self {
+        TimerGroup {
+            name: name.into(),
+            start: Instant::now(),
+            members: Vec::new(),
+        }
+    }
+
+    fn member(&mut self, name: &str) -> &mut Member {
+        let index = match self.members.iter().position(|m| m.name == name) {
+            Some(index) => index,
+            None => {
+                self.members.push(Member {
+                    name: name.to_string(),
+                    accumulated: Duration::ZERO,
+                    running_since: None,
+                });
+                self.members.len() - 1
+            }
+        };
+        &mut self.members[index]
+    }
+
+    /// Starts (or resumes) the member timer `name`. Starting a running timer has no effect.
+    pub fn start(&mut self, name: &str) {
+        self.start_at(name, Instant::now());
+    }
+
+    fn start_at(&mut self, name: &str, now: Instant) {
+        let member = self.member(name);
+        if member.running_since.is_none() {
+            member.running_since = Some(now);
+        }
+    }
+
+    /// Stops the member timer `name`.
+    ///
+    /// # Returns
+    ///
+    /// The member's accumulated time, or `None` if no member is called `name`.
+    pub fn stop(&mut self, name: &str) -> Option<Duration> {
+        self.stop_at(name, Instant::now())
+    }
+
+    fn stop_at(&mut self, name: &str, now: Instant) -> Option<Duration> {
+        let member = self.members.iter_mut().find(|m| m.name == name)?;
+        member.stop_at(now);
+        Some(member.accumulated)
+    }
+
+    /// Runs a closure, adding its elapsed time to the member timer `name`.
+    pub fn time<F, T>(&mut self, name: &str, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        self.start(name);
+        let value = f();
+        self.stop(name);
+        value
+    }
+
+    /// Returns the time since the group was created.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Stops every running member and the group clock, and returns the report.
+    pub fn close(self) -> GroupReport {
+        self.close_at(Instant::now())
+    }
+
+    fn close_at(mut self, now: Instant) -> GroupReport {
+        for member in &mut self.members {
+            member.stop_at(now);
+        }
+        GroupReport {
+            name: self.name,
+            total: now.saturating_duration_since(self.start),
+            members: self
+                .members
+                .into_iter()
+                .map(|m| (m.name, m.accumulated))
+                .collect(),
+        }
+    }
+}
+
+/// The result of closing a [`TimerGroup`].
+///
+/// Its `Display` implementation renders an aligned summary:
+///
+/// ```text
+/// build: 12.500s
+///   compile        10s
+///   link           2s
+///   (unaccounted)  0.500s
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupReport {
+    /// The group's name.
+    pub name: String,
+    /// Time from the group's creation until it was closed.
+    pub total: Duration,
+    /// Each member's accumulated time, in the order members were first started.
+    pub members: Vec<(String, Duration)>,
+}
+
+impl GroupReport {
+    /// Returns the sum of all member times.
+    pub fn accounted(&self) -> Duration {
+        self.members.iter().map(|(_, d)| *d).sum()
+    }
+
+    /// Returns the part of the group total not covered by any member.
+    ///
+    /// Overlapping members can account for more than the total; this then returns zero.
+    pub fn unaccounted(&self) -> Duration {
+        self.total.saturating_sub(self.accounted())
+    }
+}
+
+impl fmt::Display for GroupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNACCOUNTED: &str = "(unaccounted)";
+        let width = self
+            .members
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .chain([UNACCOUNTED.len()])
+            .max()
+            .unwrap_or(0);
+
+        write!(f, "{}: {}", self.name, format_duration(self.total))?;
+        for (name, duration) in &self.members {
+            write!(f, "\n  {:<width$}  {}", name, format_duration(*duration), width = width)?;
+        }
+        write!(
+            f,
+            "\n  {:<width$}  {}",
+            UNACCOUNTED,
+            format_duration(self.unaccounted()),
+            width = width
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_report() {
+        let mut group = TimerGroup::new("build");
+        let t0 = group.start;
+        group.start_at("compile", t0);
+        group.stop_at("compile", t0 + Duration::from_secs(10));
+        group.start_at("link", t0 + Duration::from_secs(10));
+        group.stop_at("link", t0 + Duration::from_secs(11));
+        group.start_at("link", t0 + Duration::from_secs(12));
+        let report = group.close_at(t0 + Duration::from_millis(13_500));
+
+        assert_eq!(report.total, Duration::from_millis(13_500));
+        assert_eq!(
+            report.members,
+            [
+                ("compile".to_string(), Duration::from_secs(10)),
+                ("link".to_string(), Duration::from_millis(2_500)),
+            ]
+        );
+        assert_eq!(report.unaccounted(), Duration::from_secs(1));
+        assert_eq!(
+            report.to_string(),
+            "build: 13.500s\n  compile        10s\n  link           2.500s\n  (unaccounted)  1s"
+        );
+    }
+
+    #[test]
+    fn test_stop_unknown_member() {
+        let mut group = TimerGroup::new("empty");
+        assert_eq!(group.stop("missing"), None);
+        assert_eq!(group.time("work", || 5), 5);
+        assert!(group.close().members[0].1 < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_overlapping_members_saturate() {
+        let mut group = TimerGroup::new("overlap");
+        let t0 = group.start;
+        group.start_at("a", t0);
+        group.start_at("b", t0);
+        let report = group.close_at(t0 + Duration::from_secs(4));
+        assert_eq!(report.accounted(), Duration::from_secs(8));
+        assert_eq!(report.unaccounted(), Duration::ZERO);
+    }
+}
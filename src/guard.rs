@@ -0,0 +1,228 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::{sink, CompletionAlert};
+
+/// A user-supplied receiver for a [`ScopedTimer`]'s label and elapsed time.
+type ReportHook = Box<dyn FnMut(&str, Duration) + Send>;
+
+/// Where a [`ScopedTimer`] sends its report.
+enum Report {
+    /// Send the label and elapsed time to the global [`sink`].
+    Sink,
+    /// Call a user-supplied hook with the label and elapsed time.
+    Hook(ReportHook),
+}
+
+/// A guard that reports how long it was alive when it is dropped.
+///
+/// Bind one at the top of a function or block and the elapsed time is reported however the
+/// scope is left, including early `return`s, `?` and panics. By default the report goes to
+/// the global [`sink`](crate::sink), which prints `"<label>: <elapsed>"` to stderr; use
+/// [`on_drop`](ScopedTimer::on_drop) to send this timer's report somewhere else.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::ScopedTimer;
+///
+/// fn load(path: &str) -> std::io::Result<String> {
+///     let _timer = ScopedTimer::new("load");
+///     let text = std::fs::read_to_string(path)?;
+///     Ok(text.trim().to_string())
+/// } // stderr: e.g. "load: 0.002s", even when `?` returns early
+/// # let _ = load("Cargo.toml");
+/// ```
+pub struct ScopedTimer {
+    label: String,
+    start: Instant,
+    report: Report,
+    threshold: Option<Duration>,
+    alert: Option<CompletionAlert>,
+}
+
+impl ScopedTimer {
+    /// Starts a timer that reports to the global [`sink`] when dropped.
+    pub fn new(label: impl Into<String>) -> Self {
+        ScopedTimer {
+            label: label.into(),
+            start: Instant::now(),
+            report: Report::Sink,
+            threshold: None,
+            alert: None,
+        }
+    }
+
+    /// Calls `hook` with the label and elapsed time on drop instead of reporting to the sink.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    /// use elapsed_time::ScopedTimer;
+    ///
+    /// let (tx, rx) = mpsc::channel();
+    /// {
+    ///     let _timer = ScopedTimer::new("query").on_drop(move |label, elapsed| {
+    ///         tx.send((label.to_string(), elapsed)).unwrap();
+    ///     });
+    /// }
+    /// assert_eq!(rx.recv().unwrap().0, "query");
+    /// ```
+    pub fn on_drop<H>(mut self, hook: H) -> Self
+    where
+        H: FnMut(&str, Duration) + Send + 'static,
+    {
+        self.report = Report::Hook(Box::new(hook));
+        self
+    }
+
+    /// Only reports if the scope took longer than `threshold`, so hot paths can stay
+    /// instrumented and only slow outliers are heard about.
+    ///
+    /// An [`alert`](ScopedTimer::alert) still checks its own threshold.
+    pub fn report_if_slower_than(mut self, threshold: Duration) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Also fires `alert` on drop if the scope took longer than its threshold.
+    pub fn alert(mut self, alert: CompletionAlert) -> Self {
+        self.alert = Some(alert);
+        self
+    }
+
+    /// Returns the label the timer reports under.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the time since the timer was created.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for ScopedTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        if self.threshold.is_none_or(|threshold| elapsed > threshold) {
+            match &mut self.report {
+                Report::Sink => sink::record(&self.label, elapsed),
+                Report::Hook(hook) => hook(&self.label, elapsed),
+            }
+        }
+        if let Some(alert) = &self.alert {
+            alert.check(elapsed);
+        }
+    }
+}
+
+impl fmt::Debug for ScopedTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedTimer")
+            .field("label", &self.label)
+            .field("start", &self.start)
+            .field("threshold", &self.threshold)
+            .field("alert", &self.alert)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs a closure and reports it to the global [`sink`] under `label`, but only if it took
+/// longer than `threshold`.
+///
+/// # Arguments
+///
+/// * `threshold` - The elapsed time a run must exceed to be reported.
+/// * `label` - Name of the operation, used in the report.
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// The closure's return value.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::report_if_slower_than;
+///
+/// // Silent unless the lookup takes more than 50ms; then stderr gets e.g. "lookup: 0.120s".
+/// let value = report_if_slower_than(Duration::from_millis(50), "lookup", || 42);
+/// assert_eq!(value, 42);
+/// ```
+pub fn report_if_slower_than<F, T>(threshold: Duration, label: &str, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let (result, elapsed) = crate::clock::measure_call(f);
+    if elapsed > threshold {
+        sink::record(label, elapsed);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    type Reports = Arc<Mutex<Vec<(String, Duration)>>>;
+
+    fn recorder() -> (Reports, impl FnMut(&str, Duration) + Send) {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        (reports, move |label: &str, elapsed| {
+            sink.lock().unwrap().push((label.to_string(), elapsed))
+        })
+    }
+
+    #[test]
+    fn test_reports_on_early_return() {
+        let (reports, hook) = recorder();
+        let mut hook = Some(hook);
+        let mut parse = |s: &str| -> Result<u32, std::num::ParseIntError> {
+            let _timer = ScopedTimer::new("parse").on_drop(hook.take().unwrap());
+            let n: u32 = s.parse()?;
+            Ok(n)
+        };
+        assert!(parse("x").is_err());
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].0, "parse");
+        assert!(reports[0].1 < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_alert_fires_past_threshold() {
+        let fired = Arc::new(Mutex::new(None));
+        let seen = Arc::clone(&fired);
+        let alert = CompletionAlert::hook(Duration::ZERO, move |elapsed| {
+            *seen.lock().unwrap() = Some(elapsed);
+        });
+        let (_, hook) = recorder();
+        drop(ScopedTimer::new("build").on_drop(hook).alert(alert));
+        assert!(fired.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_threshold_silences_fast_scopes() {
+        let (reports, hook) = recorder();
+        drop(
+            ScopedTimer::new("fast")
+                .on_drop(hook)
+                .report_if_slower_than(Duration::from_secs(60)),
+        );
+        assert!(reports.lock().unwrap().is_empty());
+
+        let (reports, hook) = recorder();
+        let timer = ScopedTimer::new("slow")
+            .on_drop(hook)
+            .report_if_slower_than(Duration::from_millis(5));
+        std::thread::sleep(Duration::from_millis(10));
+        drop(timer);
+        assert_eq!(reports.lock().unwrap()[0].0, "slow");
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::format_duration;
+
+/// Bars used by [`sparkline`], from shortest to tallest.
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Width of the longest bar drawn by [`Histogram`]'s `Display` implementation.
+const HISTOGRAM_BAR_WIDTH: usize = 20;
+
+/// Draws one bar per duration, scaled between the smallest and largest value.
+///
+/// # Arguments
+///
+/// * `durations` - The values to draw, in order.
+///
+/// # Returns
+///
+/// A string of block characters, one per duration, or an empty string for no durations.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::sparkline;
+///
+/// let runs = [100, 200, 800].map(Duration::from_millis);
+/// assert_eq!(sparkline(&runs), "▁▂█");
+/// ```
+pub fn sparkline(durations: &[Duration]) -> String {
+    let (Some(min), Some(max)) = (durations.iter().min(), durations.iter().max()) else {
+        return String::new();
+    };
+    let range = max.saturating_sub(*min).as_secs_f64();
+    durations
+        .iter()
+        .map(|d| {
+            if range == 0.0 {
+                return SPARK_BARS[0];
+            }
+            let level = (d.saturating_sub(*min).as_secs_f64() / range * 7.0).round() as usize;
+            SPARK_BARS[level.min(7)]
+        })
+        .collect()
+}
+
+/// One bucket of a [`Histogram`]: the durations in `start..=end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramBucket {
+    pub start: Duration,
+    pub end: Duration,
+    pub count: usize,
+}
+
+/// Durations counted into equal-width buckets between the smallest and largest value.
+///
+/// Its `Display` implementation draws one line per bucket, labelled with the bucket's
+/// range and followed by its share of all durations:
+///
+/// ```text
+/// 0.010s–0.040s  ████████████████████   60%
+/// 0.040s–0.070s  ███████                20%
+/// 0.070s–0.100s  ███████                20%
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::Histogram;
+///
+/// let samples = [10, 20, 30, 50, 100].map(Duration::from_millis);
+/// let histogram = Histogram::new(&samples, 3);
+///
+/// assert_eq!(histogram.buckets.len(), 3);
+/// assert_eq!(histogram.buckets[0].count, 3);
+/// println!("{}", histogram);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    pub buckets: Vec<HistogramBucket>,
+}
+
+impl Histogram {
+    /// Counts `durations` into `buckets` equal-width buckets.
+    ///
+    /// All durations fall into a single bucket if they are equal, and an empty slice
+    /// produces no buckets.
+    pub fn new(durations: &[Duration], buckets: usize) -> Self {
+        let (Some(&min), Some(&max)) = (durations.iter().min(), durations.iter().max()) else {
+            return Histogram {
+                buckets: Vec::new(),
+            };
+        };
+        let range = (max - min).as_nanos();
+        let buckets = if range == 0 { 1 } else { buckets.max(1) };
+        let width = range.div_ceil(buckets as u128).max(1);
+
+        let bound = |i: usize| {
+            let nanos = min.as_nanos() + (width * i as u128).min(range);
+            Duration::new(
+                (nanos / 1_000_000_000) as u64,
+                (nanos % 1_000_000_000) as u32,
+            )
+        };
+        let mut result: Vec<HistogramBucket> = (0..buckets)
+            .map(|i| HistogramBucket {
+                start: bound(i),
+                end: bound(i + 1),
+                count: 0,
+            })
+            .collect();
+        for duration in durations {
+            let index = ((*duration - min).as_nanos() / width) as usize;
+            result[index.min(buckets - 1)].count += 1;
+        }
+        Histogram { buckets: result }
+    }
+
+    /// Returns the number of durations counted.
+    pub fn total(&self) -> usize {
+        self.buckets.iter().map(|bucket| bucket.count).sum()
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<String> = self
+            .buckets
+            .iter()
+            .map(|b| format!("{}–{}", format_duration(b.start), format_duration(b.end)))
+            .collect();
+        let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let max_count = self.buckets.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+        let total = self.total().max(1);
+
+        for (i, (bucket, label)) in self.buckets.iter().zip(&labels).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let bar_len = (bucket.count * HISTOGRAM_BAR_WIDTH).div_ceil(max_count);
+            write!(
+                f,
+                "{:<lw$}  {:<bw$}  {:>3.0}%",
+                label,
+                "█".repeat(bar_len),
+                bucket.count as f64 / total as f64 * 100.0,
+                lw = label_width,
+                bw = HISTOGRAM_BAR_WIDTH
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&ms(&[5, 5])), "▁▁");
+        assert_eq!(sparkline(&ms(&[100, 200, 800])), "▁▂█");
+    }
+
+    #[test]
+    fn test_buckets() {
+        let histogram = Histogram::new(&ms(&[10, 20, 30, 50, 100]), 3);
+        let counts: Vec<_> = histogram.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [3, 1, 1]);
+        assert_eq!(histogram.buckets[0].start, Duration::from_millis(10));
+        assert_eq!(histogram.buckets[2].end, Duration::from_millis(100));
+        assert_eq!(histogram.total(), 5);
+
+        assert_eq!(Histogram::new(&ms(&[7, 7, 7]), 4).buckets.len(), 1);
+        assert!(Histogram::new(&[], 4).buckets.is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let histogram = Histogram::new(&ms(&[10, 20, 30, 50, 100]), 3);
+        assert_eq!(
+            histogram.to_string(),
+            "0.010s–0.040s  ████████████████████   60%\n\
+             0.040s–0.070s  ███████                20%\n\
+             0.070s–0.100s  ███████                20%"
+        );
+    }
+}
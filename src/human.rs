@@ -0,0 +1,190 @@
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::{fmt_duration, parse_duration, DurationFormatter, ParseError};
+
+/// A `Duration` that displays and parses in this crate's human-readable format.
+///
+/// `Display` writes [`format_duration`](crate::format_duration) output and `FromStr` reads
+/// anything [`parse_duration`] accepts, so a `HumanDuration` can sit directly in config
+/// structs, command-line value parsers and format strings. It dereferences to the inner
+/// `Duration`. Displaying it writes straight into the formatter, without allocating.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::HumanDuration;
+///
+/// let timeout: HumanDuration = "2m 30s".parse().unwrap();
+/// assert_eq!(*timeout, Duration::from_secs(150));
+/// assert_eq!(timeout.as_secs(), 150);
+/// assert_eq!(timeout.to_string(), "2m 30s");
+///
+/// let elapsed = HumanDuration::from(Duration::from_millis(1500));
+/// println!("took {}", elapsed); // "took 1.500s"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HumanDuration(pub Duration);
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_duration(f, self.0)
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(HumanDuration)
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        HumanDuration(duration)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+impl Deref for HumanDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Duration {
+        &self.0
+    }
+}
+
+/// A `Duration` paired with a way to format it, formatted only when displayed.
+///
+/// Building one costs nothing, so it can be handed to a logging macro whose level may be
+/// disabled: `debug!("took {}", DisplayDuration::new(elapsed))` never formats anything
+/// unless the message is actually written. With the default format the text goes straight
+/// into the formatter; a custom [`DurationFormatter`] is applied through
+/// [`DurationFormatter::display`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{DisplayDuration, DurationFormatter, UnitStyle};
+///
+/// let elapsed = Duration::from_millis(90_500);
+/// assert_eq!(DisplayDuration::new(elapsed).to_string(), "1m 30.500s");
+///
+/// let long = DurationFormatter::new().precision(0).unit_style(UnitStyle::Long);
+/// assert_eq!(long.display(elapsed).to_string(), "1 minute 30 seconds");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayDuration<'a> {
+    duration: Duration,
+    formatter: Option<&'a DurationFormatter>,
+}
+
+impl DisplayDuration<'static> {
+    /// Wraps `duration` to display in the [`format_duration`](crate::format_duration) format.
+    pub fn new(duration: Duration) -> Self {
+        DisplayDuration {
+            duration,
+            formatter: None,
+        }
+    }
+}
+
+impl<'a> DisplayDuration<'a> {
+    /// Wraps `duration` to display with `formatter`'s settings.
+    pub(crate) fn with_formatter(duration: Duration, formatter: &'a DurationFormatter) -> Self {
+        DisplayDuration {
+            duration,
+            formatter: Some(formatter),
+        }
+    }
+
+    /// Returns the wrapped duration.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl fmt::Display for DisplayDuration<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.formatter {
+            Some(formatter) => f.write_str(&formatter.format(self.duration)),
+            None => fmt_duration(f, self.duration),
+        }
+    }
+}
+
+impl From<Duration> for DisplayDuration<'static> {
+    fn from(duration: Duration) -> Self {
+        DisplayDuration::new(duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for input in ["0s", "0.500s", "2m", "1h 0m 5s", "1w 2d 3h 4m 5.006s"] {
+            let parsed: HumanDuration = input.parse().unwrap();
+            assert_eq!(parsed.to_string(), input);
+        }
+        let parsed: HumanDuration = "1.5h".parse().unwrap();
+        assert_eq!(parsed.to_string(), "1h 30m 0s");
+        assert_eq!(
+            "soon".parse::<HumanDuration>().unwrap_err(),
+            ParseError::InvalidNumber("soon".into())
+        );
+    }
+
+    #[test]
+    fn test_ordering_and_conversions() {
+        let mut all: Vec<HumanDuration> = ["1h", "5s", "2m"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        all.sort();
+        assert_eq!(
+            all.iter().map(|d| d.as_secs()).collect::<Vec<_>>(),
+            [5, 120, 3600]
+        );
+        assert_eq!(Duration::from(all[0]), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_display_duration_is_lazy() {
+        use core::cell::Cell;
+
+        /// A writer that counts the writes it receives.
+        struct Counting<'a>(&'a Cell<usize>);
+
+        impl fmt::Write for Counting<'_> {
+            fn write_str(&mut self, _: &str) -> fmt::Result {
+                self.0.set(self.0.get() + 1);
+                Ok(())
+            }
+        }
+
+        let writes = Cell::new(0);
+        let display = DisplayDuration::new(Duration::from_millis(1_500));
+        assert_eq!(writes.get(), 0);
+        fmt::write(&mut Counting(&writes), format_args!("{}", display)).unwrap();
+        assert!(writes.get() > 0);
+        assert_eq!(display.to_string(), "1.500s");
+        assert_eq!(display.duration(), Duration::from_millis(1_500));
+
+        let formatter = DurationFormatter::new().separator("");
+        let display = formatter.display(Duration::from_secs(125));
+        assert_eq!(display.to_string(), "2m5s");
+    }
+}
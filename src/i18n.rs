@@ -0,0 +1,298 @@
+//! Unit names in other languages, for [`DurationFormatter`](crate::DurationFormatter).
+//!
+//! Pick one of the built-in [`Locale`] tables with
+//! [`DurationFormatter::locale`](crate::DurationFormatter::locale), or implement
+//! [`UnitNames`] for any other language and pass it to
+//! [`DurationFormatter::unit_names`](crate::DurationFormatter::unit_names).
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use elapsed_time::i18n::Locale;
+//! use elapsed_time::{DurationFormatter, UnitStyle};
+//!
+//! let d = Duration::from_millis(5_401_500);
+//! let french = DurationFormatter::new().locale(Locale::French);
+//! assert_eq!(french.format(d), "1 h 30 min 1,500 s");
+//!
+//! let german = DurationFormatter::verbose().locale(Locale::German);
+//! assert_eq!(german.format(d), "1 Stunde 30 Minuten 1,500 Sekunden");
+//! ```
+
+use core::fmt;
+
+use crate::TimeUnit;
+
+/// The words a [`DurationFormatter`](crate::DurationFormatter) writes for units of time.
+///
+/// Only [`short`](UnitNames::short) and [`long`](UnitNames::long) are required; the other
+/// methods default to English conventions.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::i18n::UnitNames;
+/// use elapsed_time::{DurationFormatter, TimeUnit};
+///
+/// #[derive(Debug)]
+/// struct Dutch;
+///
+/// impl UnitNames for Dutch {
+///     fn short(&self, unit: TimeUnit) -> &str {
+///         match unit {
+///             TimeUnit::Years => "j",
+///             TimeUnit::Months => "mnd",
+///             TimeUnit::Weeks => "w",
+///             TimeUnit::Days => "d",
+///             TimeUnit::Hours => "u",
+///             TimeUnit::Minutes => "m",
+///             TimeUnit::Seconds => "s",
+///         }
+///     }
+///
+///     fn long(&self, unit: TimeUnit, plural: bool) -> &str {
+///         match (unit, plural) {
+///             (TimeUnit::Years, _) => "jaar",
+///             (TimeUnit::Months, false) => "maand",
+///             (TimeUnit::Months, true) => "maanden",
+///             (TimeUnit::Weeks, false) => "week",
+///             (TimeUnit::Weeks, true) => "weken",
+///             (TimeUnit::Days, false) => "dag",
+///             (TimeUnit::Days, true) => "dagen",
+///             (TimeUnit::Hours, _) => "uur",
+///             (TimeUnit::Minutes, false) => "minuut",
+///             (TimeUnit::Minutes, true) => "minuten",
+///             (TimeUnit::Seconds, false) => "seconde",
+///             (TimeUnit::Seconds, true) => "seconden",
+///         }
+///     }
+/// }
+///
+/// let dutch = DurationFormatter::new().unit_names(Dutch);
+/// assert_eq!(dutch.format(Duration::from_secs(5400)), "1u 30m 0s");
+/// ```
+pub trait UnitNames: fmt::Debug + Send + Sync {
+    /// Returns the abbreviation of `unit`, as in `"min"`.
+    fn short(&self, unit: TimeUnit) -> &str;
+
+    /// Returns the full name of `unit` in the singular or plural.
+    fn long(&self, unit: TimeUnit, plural: bool) -> &str;
+
+    /// Returns the full name of microseconds in the singular or plural.
+    fn long_micros(&self, plural: bool) -> &str {
+        if plural {
+            "microseconds"
+        } else {
+            "microsecond"
+        }
+    }
+
+    /// Returns the full name of nanoseconds in the singular or plural.
+    fn long_nanos(&self, plural: bool) -> &str {
+        if plural {
+            "nanoseconds"
+        } else {
+            "nanosecond"
+        }
+    }
+
+    /// Returns `true` if a count of `value`, with a fractional part if `fractional`, takes
+    /// the plural. By default everything but exactly one does.
+    fn is_plural(&self, value: u64, fractional: bool) -> bool {
+        value != 1 || fractional
+    }
+
+    /// Returns the character between whole and fractional seconds, `'.'` by default.
+    fn decimal_mark(&self) -> char {
+        '.'
+    }
+
+    /// Returns `true` if abbreviations are set apart from their value by a space, as in
+    /// `"1 h"`. By default they are attached: `"1h"`.
+    fn space_before_short(&self) -> bool {
+        false
+    }
+}
+
+/// The built-in unit name tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Locale {
+    /// `"1h 30m 5s"`, `"1 hour 30 minutes 5 seconds"`; the default.
+    #[default]
+    English,
+    /// `"1 Std. 30 Min. 5 Sek."`, `"1 Stunde 30 Minuten 5 Sekunden"`.
+    German,
+    /// `"1 h 30 min 5 s"`, `"1 heure 30 minutes 5 secondes"`.
+    French,
+    /// `"1 h 30 min 5 s"`, `"1 hora 30 minutos 5 segundos"`.
+    Spanish,
+}
+
+impl UnitNames for Locale {
+    fn short(&self, unit: TimeUnit) -> &str {
+        use TimeUnit::*;
+        match (self, unit) {
+            (Locale::English, Years) => "y",
+            (Locale::English, Months) => "mo",
+            (Locale::English, Weeks) => "w",
+            (Locale::English, Days) => "d",
+            (Locale::English, Hours) => "h",
+            (Locale::English, Minutes) => "m",
+            (Locale::English, Seconds) => "s",
+            (Locale::German, Years) => "J.",
+            (Locale::German, Months) => "Mon.",
+            (Locale::German, Weeks) => "Wo.",
+            (Locale::German, Days) => "Tg.",
+            (Locale::German, Hours) => "Std.",
+            (Locale::German, Minutes) => "Min.",
+            (Locale::German, Seconds) => "Sek.",
+            (Locale::French, Years) => "an",
+            (Locale::French, Months) => "mois",
+            (Locale::Spanish, Years) => "a",
+            (Locale::Spanish, Months) => "mes",
+            (Locale::French | Locale::Spanish, Weeks) => "sem.",
+            (Locale::French, Days) => "j",
+            (Locale::Spanish, Days) => "d",
+            (Locale::French | Locale::Spanish, Hours) => "h",
+            (Locale::French | Locale::Spanish, Minutes) => "min",
+            (Locale::French | Locale::Spanish, Seconds) => "s",
+        }
+    }
+
+    fn long(&self, unit: TimeUnit, plural: bool) -> &str {
+        use TimeUnit::*;
+        let (singular, plural_form) = match (self, unit) {
+            (Locale::English, Years) => ("year", "years"),
+            (Locale::English, Months) => ("month", "months"),
+            (Locale::English, Weeks) => ("week", "weeks"),
+            (Locale::English, Days) => ("day", "days"),
+            (Locale::English, Hours) => ("hour", "hours"),
+            (Locale::English, Minutes) => ("minute", "minutes"),
+            (Locale::English, Seconds) => ("second", "seconds"),
+            (Locale::German, Years) => ("Jahr", "Jahre"),
+            (Locale::German, Months) => ("Monat", "Monate"),
+            (Locale::German, Weeks) => ("Woche", "Wochen"),
+            (Locale::German, Days) => ("Tag", "Tage"),
+            (Locale::German, Hours) => ("Stunde", "Stunden"),
+            (Locale::German, Minutes) => ("Minute", "Minuten"),
+            (Locale::German, Seconds) => ("Sekunde", "Sekunden"),
+            (Locale::French, Years) => ("an", "ans"),
+            (Locale::French, Months) => ("mois", "mois"),
+            (Locale::French, Weeks) => ("semaine", "semaines"),
+            (Locale::French, Days) => ("jour", "jours"),
+            (Locale::French, Hours) => ("heure", "heures"),
+            (Locale::French, Minutes) => ("minute", "minutes"),
+            (Locale::French, Seconds) => ("seconde", "secondes"),
+            (Locale::Spanish, Years) => ("año", "años"),
+            (Locale::Spanish, Months) => ("mes", "meses"),
+            (Locale::Spanish, Weeks) => ("semana", "semanas"),
+            (Locale::Spanish, Days) => ("día", "días"),
+            (Locale::Spanish, Hours) => ("hora", "horas"),
+            (Locale::Spanish, Minutes) => ("minuto", "minutos"),
+            (Locale::Spanish, Seconds) => ("segundo", "segundos"),
+        };
+        if plural {
+            plural_form
+        } else {
+            singular
+        }
+    }
+
+    fn long_micros(&self, plural: bool) -> &str {
+        match (self, plural) {
+            (Locale::English, false) => "microsecond",
+            (Locale::English, true) => "microseconds",
+            (Locale::German, false) => "Mikrosekunde",
+            (Locale::German, true) => "Mikrosekunden",
+            (Locale::French, false) => "microseconde",
+            (Locale::French, true) => "microsecondes",
+            (Locale::Spanish, false) => "microsegundo",
+            (Locale::Spanish, true) => "microsegundos",
+        }
+    }
+
+    fn long_nanos(&self, plural: bool) -> &str {
+        match (self, plural) {
+            (Locale::English, false) => "nanosecond",
+            (Locale::English, true) => "nanoseconds",
+            (Locale::German, false) => "Nanosekunde",
+            (Locale::German, true) => "Nanosekunden",
+            (Locale::French, false) => "nanoseconde",
+            (Locale::French, true) => "nanosecondes",
+            (Locale::Spanish, false) => "nanosegundo",
+            (Locale::Spanish, true) => "nanosegundos",
+        }
+    }
+
+    fn is_plural(&self, value: u64, fractional: bool) -> bool {
+        match self {
+            // French counts below two in the singular: "1,5 heure".
+            Locale::French => value >= 2,
+            _ => value != 1 || fractional,
+        }
+    }
+
+    fn decimal_mark(&self) -> char {
+        match self {
+            Locale::English => '.',
+            Locale::German | Locale::French | Locale::Spanish => ',',
+        }
+    }
+
+    fn space_before_short(&self) -> bool {
+        !matches!(self, Locale::English)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{format_duration, DurationFormatter, UnitStyle};
+
+    #[test]
+    fn test_english_matches_format_duration() {
+        let english = DurationFormatter::new().locale(Locale::English);
+        for secs in [0, 1, 59, 60, 3_661, 1_000_000] {
+            let d = Duration::from_millis(secs * 1000 + 250);
+            assert_eq!(english.format(d), format_duration(d));
+        }
+    }
+
+    #[test]
+    fn test_locales() {
+        let d = Duration::from_secs(90 * 60);
+        let short = DurationFormatter::new().max_units(2);
+        assert_eq!(
+            short.clone().locale(Locale::German).format(d),
+            "1 Std. 30 Min."
+        );
+        assert_eq!(short.clone().locale(Locale::French).format(d), "1 h 30 min");
+        assert_eq!(short.locale(Locale::Spanish).format(d), "1 h 30 min");
+
+        let long = DurationFormatter::verbose();
+        let d = Duration::from_millis(1500);
+        assert_eq!(
+            long.clone().locale(Locale::French).format(d),
+            "1,500 seconde"
+        );
+        assert_eq!(
+            long.clone().locale(Locale::Spanish).format(d),
+            "1,500 segundos"
+        );
+        let d = Duration::from_secs(2 * 24 * 60 * 60);
+        assert_eq!(long.locale(Locale::German).format(d), "2 Tage");
+
+        let precise = DurationFormatter::precise().unit_style(UnitStyle::Long);
+        let precise = precise.locale(Locale::German);
+        assert_eq!(precise.format(Duration::from_nanos(1)), "1 Nanosekunde");
+        assert_eq!(
+            precise.format(Duration::from_micros(20)),
+            "20 Mikrosekunden"
+        );
+    }
+}
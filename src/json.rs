@@ -0,0 +1,65 @@
+//! JSON helpers: minimal writing for the payloads this crate sends, and the shared shape
+//! of exported durations.
+
+#[cfg(feature = "webhook")]
+use std::fmt::Write;
+#[cfg(feature = "serde_json")]
+use std::time::Duration;
+
+#[cfg(feature = "serde_json")]
+use crate::format_duration;
+
+/// Returns `{"nanos": 1500000000, "formatted": "1.500s"}` for `duration`, the form every
+/// `to_json` export uses for a duration so consumers get both the exact value and its
+/// display text.
+#[cfg(feature = "serde_json")]
+pub(crate) fn duration_value(duration: Duration) -> serde_json::Value {
+    serde_json::json!({
+        "nanos": u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX),
+        "formatted": format_duration(duration),
+    })
+}
+
+/// Appends `value` to `out` as a quoted, escaped JSON string.
+#[cfg(feature = "webhook")]
+pub(crate) fn push_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "webhook")]
+    #[test]
+    fn test_push_string_escapes() {
+        let mut out = String::new();
+        push_string(&mut out, "say \"hi\"\\\n\u{1}é");
+        assert_eq!(out, r#""say \"hi\"\\\n\u0001é""#);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_duration_value() {
+        let value = duration_value(Duration::from_millis(1500));
+        assert_eq!(
+            value.to_string(),
+            r#"{"nanos":1500000000,"formatted":"1.500s"}"#
+        );
+        assert_eq!(duration_value(Duration::MAX)["nanos"], u64::MAX);
+    }
+}
@@ -1,4 +1,187 @@
-use std::time::Instant;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+#[cfg(feature = "macros")]
+extern crate self as elapsed_time;
+
+#[cfg(feature = "std")]
+mod alert;
+#[cfg(feature = "std")]
+mod budget;
+#[cfg(feature = "std")]
+mod business;
+mod clock;
+#[cfg(feature = "std")]
+mod command;
+mod const_fmt;
+#[cfg(feature = "std")]
+mod context;
+#[cfg(feature = "std")]
+mod csv;
+#[cfg(feature = "std")]
+mod deadline;
+#[cfg(feature = "std")]
+mod decay;
+#[cfg(feature = "std")]
+mod estimate;
+mod ext;
+#[cfg(feature = "std")]
+mod future;
+#[cfg(feature = "std")]
+mod group;
+#[cfg(feature = "std")]
+mod guard;
+#[cfg(feature = "std")]
+mod histogram;
+mod human;
+#[cfg(feature = "std")]
+mod live;
+mod math;
+#[cfg(feature = "std")]
+mod measurement;
+#[cfg(feature = "std")]
+mod meter;
+mod options;
+mod parse;
+mod relative;
+#[cfg(feature = "std")]
+mod reservoir;
+#[cfg(feature = "std")]
+mod rng;
+mod signed;
+#[cfg(feature = "std")]
+mod sketch;
+#[cfg(feature = "std")]
+mod stopwatch;
+#[cfg(feature = "std")]
+mod timestamp;
+mod units;
+#[cfg(feature = "std")]
+mod uptime;
+
+#[cfg(feature = "std")]
+pub mod diff;
+pub mod i18n;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod scope;
+#[cfg(feature = "std")]
+pub mod sink;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod timers;
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
+
+#[cfg(feature = "jiff")]
+pub mod calendar;
+#[cfg(feature = "chat")]
+pub mod chat;
+#[cfg(feature = "chrono")]
+mod chrono_interop;
+#[cfg(all(feature = "cpu-time", any(unix, windows)))]
+pub mod cpu;
+#[cfg(any(feature = "serde_json", feature = "webhook"))]
+mod json;
+#[cfg(feature = "log")]
+mod log_interop;
+#[cfg(feature = "regex")]
+pub mod logtail;
+#[cfg(feature = "notify-rust")]
+pub mod notify;
+#[cfg(feature = "serde")]
+pub mod serde_human;
+#[cfg(feature = "time")]
+mod time_interop;
+#[cfg(feature = "tracing")]
+mod tracing_interop;
+#[cfg(feature = "ratatui")]
+pub mod tui;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+#[cfg(feature = "std")]
+pub use alert::{measure_with_bell, CompletionAlert};
+#[cfg(feature = "std")]
+pub use budget::{measure_with_budget, Budget, BudgetExhausted, BudgetResult};
+#[cfg(feature = "std")]
+pub use business::{BusinessHours, Weekday};
+#[cfg(feature = "chrono")]
+pub use chrono_interop::{format_between, format_time_delta};
+pub use clock::{measure_elapsed_time_with_clock, Clock};
+#[cfg(feature = "test-util")]
+pub use clock::ManualClock;
+#[cfg(feature = "std")]
+pub use clock::StdClock;
+#[cfg(feature = "std")]
+pub use command::{measure_command, CommandTiming};
+pub use const_fmt::{format_duration_const, ConstFormattedDuration};
+#[cfg(feature = "std")]
+pub use context::{ElapsedContext, ElapsedError};
+#[cfg(feature = "std")]
+pub use deadline::Deadline;
+#[cfg(feature = "std")]
+pub use decay::{DecayingSnapshot, DecayingStats};
+#[cfg(feature = "macros")]
+pub use elapsed_time_macros::timed;
+#[cfg(feature = "std")]
+pub use estimate::{Progress, RemainingEstimator};
+pub use ext::DurationExt;
+#[cfg(feature = "std")]
+pub use ext::InstantExt;
+#[cfg(feature = "std")]
+pub use future::{measure_elapsed_time_async, measure_elapsed_time_async_formatted};
+#[cfg(feature = "std")]
+pub use group::{GroupReport, TimerGroup};
+#[cfg(feature = "std")]
+pub use guard::{report_if_slower_than, ScopedTimer};
+#[cfg(feature = "std")]
+pub use histogram::{sparkline, Histogram, HistogramBucket};
+pub use human::{DisplayDuration, HumanDuration};
+#[cfg(feature = "std")]
+pub use live::{measure_with_spinner, LiveElapsed};
+#[cfg(feature = "log")]
+pub use log_interop::measure_and_log;
+pub use math::{
+    extrapolate, extrapolate_remaining, format_extrapolated, format_percent, format_rate,
+    format_ratio, format_throughput, ratio, scale,
+};
+#[cfg(feature = "std")]
+pub use measurement::{measure, measure_result, Measurement, Timed, TimedResult};
+#[cfg(feature = "std")]
+pub use meter::Meter;
+pub use options::{
+    format_duration_compact, format_duration_iso8601, format_duration_verbose, format_durations,
+    DurationFormatter, FormatOptions, Rounding, TimeUnit, UnitStyle,
+};
+pub use parse::{duration_from_human, parse_duration, ParseError};
+pub use relative::{format_ago, format_approximate, format_in, RelativeFormatter};
+#[cfg(feature = "std")]
+pub use reservoir::Reservoir;
+pub use signed::{format_signed_duration, SignedDuration};
+#[cfg(feature = "std")]
+pub use signed::format_between_system_times;
+#[cfg(feature = "std")]
+pub use sketch::DurationSketch;
+#[cfg(feature = "std")]
+pub use stopwatch::{LapTimer, Stopwatch};
+#[cfg(feature = "time")]
+pub use time_interop::{format_time_duration, parse_time_duration};
+#[cfg(feature = "std")]
+pub use timestamp::{elapsed_between, TimestampError, TimestampFormat};
+#[cfg(feature = "tracing")]
+pub use tracing_interop::{measure_in_span, measure_traced, trace_elapsed};
+pub use units::{days, hours, millis, mins, secs, weeks, Hours, Minutes, Seconds};
+#[cfg(feature = "std")]
+pub use uptime::{init_uptime, uptime, uptime_formatted};
 
 /// Measures the elapsed time of a given function and returns a formatted string representation.
 ///
@@ -31,20 +214,102 @@ use std::time::Instant;
 /// });
 /// assert_eq!(elapsed_time, "1.500s");
 ///
-/// // Measure a longer operation
-/// let elapsed_time = measure_elapsed_time(|| {
-///     sleep(Duration::from_secs(125)); // 2 minutes and 5 seconds
+/// ```
+///
+/// To check how a longer operation reads without waiting for it, measure on a clock you
+/// control. Any closure returning nanoseconds is a clock, so the operation can move it
+/// forward itself:
+///
+/// ```
+/// use std::cell::Cell;
+/// use elapsed_time::measure_elapsed_time_with_clock;
+///
+/// let nanos = Cell::new(0);
+/// let clock = || nanos.get();
+/// let elapsed_time = measure_elapsed_time_with_clock(&clock, || {
+///     nanos.set(125_000_000_000); // 2 minutes and 5 seconds
 /// });
 /// assert_eq!(elapsed_time, "2m 5s");
 /// ```
+#[cfg(feature = "std")]
 pub fn measure_elapsed_time<F>(f: F) -> String
 where
     F: FnOnce(),
 {
-    let start = Instant::now();
-    f();
-    let duration = start.elapsed();
-    format_duration(duration)
+    let ((), elapsed) = measure_elapsed_time_with_result(f);
+    elapsed
+}
+
+/// Measures the elapsed time of a function and returns its result along with the formatted time.
+///
+/// This is [`measure_elapsed_time`] for closures that compute something, so the value doesn't
+/// have to be smuggled out through a captured variable.
+///
+/// # Arguments
+///
+/// * `f` - A closure that takes no arguments and returns a value of any type.
+///
+/// # Returns
+///
+/// A tuple of the closure's result and a `String` representing the formatted elapsed time.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_elapsed_time_with_result;
+///
+/// let (sum, elapsed) = measure_elapsed_time_with_result(|| (1..=100u32).sum::<u32>());
+/// assert_eq!(sum, 5050);
+/// println!("summed in {}", elapsed);
+/// ```
+#[cfg(feature = "std")]
+pub fn measure_elapsed_time_with_result<F, T>(f: F) -> (T, String)
+where
+    F: FnOnce() -> T,
+{
+    let (result, duration) = clock::measure_call(f);
+    (result, format_duration(duration))
+}
+
+/// Measures the elapsed time of a function that may panic and returns the outcome along with
+/// the formatted time.
+///
+/// The closure runs under [`std::panic::catch_unwind`], so the time is measured even when it
+/// panics. Teardown code can then log how long the failing operation ran before passing the
+/// panic on with [`std::panic::resume_unwind`]. The panic hook still runs first, so the panic
+/// message is printed as usual.
+///
+/// # Arguments
+///
+/// * `f` - A closure that takes no arguments and returns a value of any type.
+///
+/// # Returns
+///
+/// A tuple of `Ok` with the closure's result, or `Err` with the panic payload, and a `String`
+/// representing the formatted elapsed time.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_elapsed_time_catching;
+///
+/// let (outcome, elapsed) = measure_elapsed_time_catching(|| migrate());
+/// match outcome {
+///     Ok(version) => println!("migrated to {} in {}", version, elapsed),
+///     Err(payload) => {
+///         eprintln!("migration panicked after {}", elapsed);
+///         std::panic::resume_unwind(payload);
+///     }
+/// }
+/// # fn migrate() -> u32 { 7 }
+/// ```
+#[cfg(feature = "std")]
+pub fn measure_elapsed_time_catching<F, T>(f: F) -> (std::thread::Result<T>, String)
+where
+    F: FnOnce() -> T + std::panic::UnwindSafe,
+{
+    let (outcome, duration) = clock::measure_call(|| std::panic::catch_unwind(f));
+    (outcome, format_duration(duration))
 }
 
 /// A struct to hold the calculated duration components.
@@ -52,18 +317,75 @@ where
 /// This struct stores the broken-down components of a duration, with each field
 /// representing a specific time unit. The fields are stored in their "remaining" form,
 /// meaning they don't overlap (e.g., remaining_hours will be less than 24).
-#[derive(Debug)]
-struct DurationComponents {
-    weeks: u64,
-    remaining_days: u64,
-    remaining_hours: u64,
-    minutes: u64,
-    seconds: u64,
-    milliseconds: u32,
+///
+/// The decomposition is a `const fn`, so durations known at compile time can be
+/// broken down in `const` items.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::DurationComponents;
+///
+/// const PARTS: DurationComponents = DurationComponents::from_duration(Duration::from_secs(90061));
+/// assert_eq!(PARTS.remaining_days, 1);
+/// assert_eq!(PARTS.remaining_hours, 1);
+/// assert_eq!(PARTS.minutes, 1);
+/// assert_eq!(PARTS.seconds, 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationComponents {
+    /// 365-day years; only filled in by [`with_years_and_months`](Self::with_years_and_months).
+    pub years: u64,
+    /// Approximate months of 30 days and 10 hours, a twelfth of a year; only filled in by
+    /// [`with_years_and_months`](Self::with_years_and_months).
+    pub months: u64,
+    pub weeks: u64,
+    pub remaining_days: u64,
+    pub remaining_hours: u64,
+    pub minutes: u64,
+    pub seconds: u64,
+    pub milliseconds: u32,
+}
+
+impl DurationComponents {
+    /// Breaks a `Duration` down into its weeks, days, hours, minutes, seconds and milliseconds.
+    pub const fn from_duration(duration: core::time::Duration) -> Self {
+        format_duration_calculate(duration)
+    }
+
+    /// Breaks a `Duration` down like [`from_duration`](Self::from_duration), but counts
+    /// 365-day years and approximate months before weeks instead of piling everything up
+    /// in weeks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::DurationComponents;
+    ///
+    /// let year = 365 * 24 * 60 * 60;
+    /// let parts = DurationComponents::with_years_and_months(Duration::from_secs(10 * year + 60));
+    /// assert_eq!((parts.years, parts.months, parts.weeks, parts.minutes), (10, 0, 0, 1));
+    /// assert_eq!(DurationComponents::from_duration(Duration::from_secs(10 * year)).weeks, 521);
+    /// ```
+    pub const fn with_years_and_months(duration: core::time::Duration) -> Self {
+        const YEAR: u64 = 365 * 24 * 60 * 60;
+        const MONTH: u64 = YEAR / 12;
+        let total_seconds = duration.as_secs();
+        let rest = total_seconds % MONTH;
+        let mut components = format_duration_calculate(core::time::Duration::new(
+            rest,
+            duration.subsec_nanos(),
+        ));
+        components.years = total_seconds / YEAR;
+        components.months = total_seconds % YEAR / MONTH;
+        components
+    }
 }
 
 /// Calculates the duration components from a Duration.
-fn format_duration_calculate(duration: std::time::Duration) -> DurationComponents {
+const fn format_duration_calculate(duration: core::time::Duration) -> DurationComponents {
     let total_seconds = duration.as_secs();
     let hours = total_seconds / 3600;
     let days = hours / 24;
@@ -76,6 +398,8 @@ fn format_duration_calculate(duration: std::time::Duration) -> DurationComponent
     let milliseconds = duration.subsec_millis();
 
     DurationComponents {
+        years: 0,
+        months: 0,
         weeks,
         remaining_days,
         remaining_hours,
@@ -87,41 +411,170 @@ fn format_duration_calculate(duration: std::time::Duration) -> DurationComponent
 
 /// Formats the duration components into a human-readable string.
 fn format_duration_format(components: &DurationComponents) -> String {
-    // Helper function to format seconds with milliseconds
-    let format_seconds = |secs: u64, ms: u32| {
-        if secs == 0 && ms > 0 {
-            format!("{}.{:03}s", 0, ms)
-        } else if ms == 0 {
-            format!("{}s", secs)
+    let mut out = String::new();
+    // Writing to a `String` cannot fail.
+    let _ = write_components(&mut out, components);
+    out
+}
+
+/// Writes the duration components to `out` piece by piece, without building intermediate
+/// strings.
+fn write_components<W: core::fmt::Write + ?Sized>(
+    out: &mut W,
+    components: &DurationComponents,
+) -> core::fmt::Result {
+    // Seconds with milliseconds, e.g. "5s" or "5.006s"
+    let write_seconds = |out: &mut W, secs: u64, ms: u32| {
+        if ms == 0 {
+            write!(out, "{}s", secs)
         } else {
-            format!("{}.{:03}s", secs, ms)
+            write!(out, "{}.{:03}s", secs, ms)
         }
     };
 
-    if components.weeks > 0 {
-        format!("{}w {}d {}h {}m {}", 
-            components.weeks, components.remaining_days, components.remaining_hours, 
-            components.minutes, format_seconds(components.seconds, components.milliseconds))
+    match (components.years, components.months) {
+        (0, 0) => {}
+        (0, months) => write!(out, "{}mo ", months)?,
+        (years, months) => write!(out, "{}y {}mo ", years, months)?,
+    }
+    let seconds = components.seconds;
+    let ms = components.milliseconds;
+    if components.years > 0 || components.months > 0 || components.weeks > 0 {
+        write!(out, "{}w {}d {}h {}m ", components.weeks, components.remaining_days,
+            components.remaining_hours, components.minutes)?;
+        write_seconds(out, seconds, ms)
     } else if components.remaining_days > 0 {
-        format!("{}d {}h {}m {}", 
-            components.remaining_days, components.remaining_hours, 
-            components.minutes, format_seconds(components.seconds, components.milliseconds))
+        write!(out, "{}d {}h {}m ", components.remaining_days, components.remaining_hours,
+            components.minutes)?;
+        write_seconds(out, seconds, ms)
     } else if components.remaining_hours > 0 {
-        format!("{}h {}m {}", 
-            components.remaining_hours, components.minutes, 
-            format_seconds(components.seconds, components.milliseconds))
+        write!(out, "{}h {}m ", components.remaining_hours, components.minutes)?;
+        write_seconds(out, seconds, ms)
     } else if components.minutes > 0 {
-        if components.seconds > 0 || components.milliseconds > 0 {
-            format!("{}m {}", 
-                components.minutes, format_seconds(components.seconds, components.milliseconds))
+        if seconds > 0 || ms > 0 {
+            write!(out, "{}m ", components.minutes)?;
+            write_seconds(out, seconds, ms)
         } else {
-            format!("{}m", components.minutes)
+            write!(out, "{}m", components.minutes)
         }
     } else {
-        format_seconds(components.seconds, components.milliseconds)
+        write_seconds(out, seconds, ms)
     }
 }
 
+/// Writes a Duration into a formatter in the same format as [`format_duration`], without
+/// allocating.
+///
+/// Use it to implement `Display` for your own types, so hot logging paths don't build a
+/// `String` per message. Width, fill and alignment flags are not applied. For a value that
+/// formats itself this way, wrap the duration in [`HumanDuration`].
+///
+/// # Example
+///
+/// ```
+/// use std::fmt;
+/// use std::time::Duration;
+/// use elapsed_time::fmt_duration;
+///
+/// struct Request {
+///     path: &'static str,
+///     took: Duration,
+/// }
+///
+/// impl fmt::Display for Request {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "{} served in ", self.path)?;
+///         fmt_duration(f, self.took)
+///     }
+/// }
+///
+/// let request = Request { path: "/health", took: Duration::from_millis(1500) };
+/// assert_eq!(request.to_string(), "/health served in 1.500s");
+/// ```
+pub fn fmt_duration(
+    f: &mut core::fmt::Formatter<'_>,
+    duration: core::time::Duration,
+) -> core::fmt::Result {
+    format_duration_into(f, duration)
+}
+
+/// Writes a Duration into any `fmt::Write` sink in the same format as [`format_duration`],
+/// without allocating.
+///
+/// For embedded and high-frequency code where heap allocation is prohibited: the output
+/// goes straight into `out`, such as a fixed-capacity string or a UART writer.
+///
+/// # Errors
+///
+/// Returns an error if `out` fails to accept the text.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_duration_into;
+///
+/// let mut line = String::from("took ");
+/// format_duration_into(&mut line, Duration::from_secs(125)).unwrap();
+/// assert_eq!(line, "took 2m 5s");
+/// ```
+pub fn format_duration_into<W: core::fmt::Write + ?Sized>(
+    out: &mut W,
+    duration: core::time::Duration,
+) -> core::fmt::Result {
+    write_components(out, &format_duration_calculate(duration))
+}
+
+/// The longest text [`format_duration`] produces, in bytes: a buffer this size always
+/// fits the output of [`format_duration_to_buf`].
+pub const FORMATTED_DURATION_MAX_LEN: usize = 34;
+
+/// Formats a Duration into a caller-provided byte buffer, such as an array on the stack,
+/// and returns the formatted text borrowed from it.
+///
+/// # Errors
+///
+/// Returns an error if `buf` is too short for the output; a buffer of
+/// [`FORMATTED_DURATION_MAX_LEN`] bytes never is.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{format_duration_to_buf, FORMATTED_DURATION_MAX_LEN};
+///
+/// let mut buf = [0u8; FORMATTED_DURATION_MAX_LEN];
+/// let text = format_duration_to_buf(&mut buf, Duration::from_millis(1500)).unwrap();
+/// assert_eq!(text, "1.500s");
+/// assert!(format_duration_to_buf(&mut [0u8; 4], Duration::from_millis(1500)).is_err());
+/// ```
+pub fn format_duration_to_buf(
+    buf: &mut [u8],
+    duration: core::time::Duration,
+) -> Result<&str, core::fmt::Error> {
+    /// Writes into a byte slice, failing once it is full.
+    struct SliceWriter<'a> {
+        buf: &'a mut [u8],
+        len: usize,
+    }
+
+    impl core::fmt::Write for SliceWriter<'_> {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.len + s.len();
+            let dest = self.buf.get_mut(self.len..end).ok_or(core::fmt::Error)?;
+            dest.copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut writer = SliceWriter { buf, len: 0 };
+    format_duration_into(&mut writer, duration)?;
+    let SliceWriter { buf, len } = writer;
+    // Only whole `str`s were copied in, so the bytes are valid UTF-8.
+    core::str::from_utf8(&buf[..len]).map_err(|_| core::fmt::Error)
+}
+
 /// Formats a Duration into a human-readable string.
 ///
 /// This function takes a Duration and formats it into a human-readable string with appropriate
@@ -169,7 +622,7 @@ fn format_duration_format(components: &DurationComponents) -> String {
 /// );
 /// assert_eq!(format_duration(week_plus), "1w 2d 3h 4m 5s");
 /// ```
-pub fn format_duration(duration: std::time::Duration) -> String {
+pub fn format_duration(duration: core::time::Duration) -> String {
     let components = format_duration_calculate(duration);
     format_duration_format(&components)
 }
@@ -177,7 +630,7 @@ pub fn format_duration(duration: std::time::Duration) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
+    use core::time::Duration;
 
     #[test]
     fn test_duration_components_calculation() {
@@ -197,6 +650,8 @@ mod tests {
         let test_cases = vec![
             (
                 DurationComponents {
+                    years: 0,
+                    months: 0,
                     weeks: 1,
                     remaining_days: 2,
                     remaining_hours: 3,
@@ -208,6 +663,8 @@ mod tests {
             ),
             (
                 DurationComponents {
+                    years: 0,
+                    months: 0,
                     weeks: 0,
                     remaining_days: 2,
                     remaining_hours: 3,
@@ -219,6 +676,8 @@ mod tests {
             ),
             (
                 DurationComponents {
+                    years: 0,
+                    months: 0,
                     weeks: 0,
                     remaining_days: 0,
                     remaining_hours: 3,
@@ -230,6 +689,8 @@ mod tests {
             ),
             (
                 DurationComponents {
+                    years: 0,
+                    months: 0,
                     weeks: 0,
                     remaining_days: 0,
                     remaining_hours: 0,
@@ -241,6 +702,8 @@ mod tests {
             ),
             (
                 DurationComponents {
+                    years: 0,
+                    months: 0,
                     weeks: 0,
                     remaining_days: 0,
                     remaining_hours: 0,
@@ -252,6 +715,8 @@ mod tests {
             ),
             (
                 DurationComponents {
+                    years: 0,
+                    months: 0,
                     weeks: 0,
                     remaining_days: 0,
                     remaining_hours: 0,
@@ -263,6 +728,8 @@ mod tests {
             ),
             (
                 DurationComponents {
+                    years: 0,
+                    months: 0,
                     weeks: 0,
                     remaining_days: 0,
                     remaining_hours: 0,
@@ -279,6 +746,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_years_and_months_components() {
+        let year = 365 * 24 * 60 * 60;
+        let duration = Duration::new(2 * year + year / 12 + 3 * 24 * 60 * 60, 5_000_000);
+        let components = DurationComponents::with_years_and_months(duration);
+        assert_eq!((components.years, components.months, components.weeks), (2, 1, 0));
+        assert_eq!(components.remaining_days, 3);
+        assert_eq!(format_duration_format(&components), "2y 1mo 0w 3d 0h 0m 0.005s");
+
+        let components = DurationComponents::with_years_and_months(Duration::from_secs(year / 12));
+        assert_eq!(format_duration_format(&components), "1mo 0w 0d 0h 0m 0s");
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn test_measure_elapsed_time() {
         let elapsed_time = measure_elapsed_time(|| {
@@ -287,6 +768,70 @@ mod tests {
         assert!(elapsed_time == "1.500s");
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_measure_elapsed_time_with_result() {
+        let (value, elapsed_time) = measure_elapsed_time_with_result(|| {
+            std::thread::sleep(Duration::from_millis(250));
+            "done"
+        });
+        assert_eq!(value, "done");
+        // Scheduling can only add time, so only the lower bound is certain.
+        assert!(parse_duration(&elapsed_time).unwrap() >= Duration::from_millis(250));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_measure_elapsed_time_catching() {
+        let (outcome, elapsed_time) = measure_elapsed_time_catching(|| {
+            std::thread::sleep(Duration::from_millis(250));
+            panic!("connection reset");
+        });
+        let payload = outcome.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"connection reset"));
+        assert!(parse_duration(&elapsed_time).unwrap() >= Duration::from_millis(250));
+
+        let (outcome, _) = measure_elapsed_time_catching(|| 42);
+        assert_eq!(outcome.unwrap(), 42);
+    }
+
+    #[test]
+    fn test_fmt_duration_matches_format_duration() {
+        struct Wrapper(Duration);
+
+        impl core::fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                fmt_duration(f, self.0)
+            }
+        }
+
+        for duration in [
+            Duration::ZERO,
+            Duration::from_millis(500),
+            Duration::from_secs(120),
+            Duration::from_millis(125_250),
+            Duration::from_secs(7200),
+            Duration::from_secs(90061),
+            Duration::new(700_000, 5_000_000),
+            Duration::MAX,
+        ] {
+            assert_eq!(Wrapper(duration).to_string(), format_duration(duration));
+        }
+    }
+
+    #[test]
+    fn test_format_duration_to_buf() {
+        let mut buf = [0u8; FORMATTED_DURATION_MAX_LEN];
+        let longest = format_duration_to_buf(&mut buf, Duration::MAX).unwrap();
+        assert_eq!(longest, format_duration(Duration::MAX));
+        assert!(longest.len() <= FORMATTED_DURATION_MAX_LEN);
+
+        let mut small = [0u8; 6];
+        assert_eq!(format_duration_to_buf(&mut small, Duration::from_millis(1500)), Ok("1.500s"));
+        assert!(format_duration_to_buf(&mut small, Duration::from_secs(125)).is_ok());
+        assert!(format_duration_to_buf(&mut small, Duration::from_secs(3665)).is_err());
+    }
+
     #[test]
     fn test_format_duration() {
         // Test exact minutes
@@ -0,0 +1,267 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// Frames drawn by [`measure_with_spinner`], one per tick.
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How often the spinner redraws.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// ANSI sequence that returns to the start of the line and clears it.
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Formats the live counter with whole seconds so it doesn't flicker between redraws.
+fn live_elapsed(elapsed: Duration) -> String {
+    format_duration(Duration::from_secs(elapsed.as_secs()))
+}
+
+/// A background thread that keeps rewriting one terminal line until stopped.
+///
+/// Dropping the updater stops the thread and clears the line, so the terminal is left
+/// clean even when the measured work panics.
+struct Updater<W: Write + Send + 'static> {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<W>>,
+}
+
+impl<W: Write + Send + 'static> Updater<W> {
+    fn spawn<R>(mut out: W, interval: Duration, render: R) -> Self
+    where
+        R: Fn(u64, Duration) -> String + Send + 'static,
+    {
+        let (stop, stopped) = mpsc::channel();
+        let start = Instant::now();
+        let handle = thread::spawn(move || {
+            let mut tick = 0;
+            loop {
+                // Write errors only affect the decoration, never the measured work.
+                let _ = write!(out, "{}{}", CLEAR_LINE, render(tick, start.elapsed()));
+                let _ = out.flush();
+                tick += 1;
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            }
+            let _ = write!(out, "{}", CLEAR_LINE);
+            let _ = out.flush();
+            out
+        });
+        Updater {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+
+    /// Stops the thread, clears the line and hands the writer back.
+    fn finish(mut self) -> Option<W> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Option<W> {
+        drop(self.stop.take());
+        self.handle.take().and_then(|handle| handle.join().ok())
+    }
+}
+
+impl<W: Write + Send + 'static> Drop for Updater<W> {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn measure_with_spinner_to<W, F, T>(out: W, animate: bool, message: &str, f: F) -> (T, String, W)
+where
+    W: Write + Send + 'static,
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+    let (value, mut out) = if animate {
+        let label = message.to_string();
+        let updater = Updater::spawn(out, SPINNER_INTERVAL, move |tick, elapsed| {
+            let frame = SPINNER_FRAMES[tick as usize % SPINNER_FRAMES.len()];
+            format!("{} {} {}", frame, label, live_elapsed(elapsed))
+        });
+        let value = f();
+        let out = updater.finish().expect("spinner thread panicked");
+        (value, out)
+    } else {
+        (f(), out)
+    };
+    let formatted = format_duration(start.elapsed());
+    let _ = writeln!(out, "{} {}", message, formatted);
+    (value, formatted, out)
+}
+
+/// Runs a closure while showing a terminal spinner with a live elapsed counter.
+///
+/// The spinner is drawn on stderr and redrawn ten times a second. When the closure returns,
+/// the spinner line is replaced with `"<message> <elapsed>"`. If stderr is not a terminal,
+/// no animation is drawn and only the final line is printed.
+///
+/// # Arguments
+///
+/// * `message` - Text shown next to the spinner and in the final line.
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// The closure's return value and the formatted elapsed time.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_with_spinner;
+///
+/// let (sum, elapsed) = measure_with_spinner("Summing", || (0..1_000u64).sum::<u64>());
+/// assert_eq!(sum, 499_500);
+/// println!("took {}", elapsed);
+/// ```
+pub fn measure_with_spinner<F, T>(message: &str, f: F) -> (T, String)
+where
+    F: FnOnce() -> T,
+{
+    let animate = io::stderr().is_terminal();
+    let (value, formatted, _) = measure_with_spinner_to(io::stderr(), animate, message, f);
+    (value, formatted)
+}
+
+/// A single terminal line that shows the elapsed time while a long operation runs.
+///
+/// A background thread rewrites the line (`"elapsed: 1m 23s"`) on stderr at a fixed
+/// interval. Calling [`finish`](LiveElapsed::finish) or dropping the value, including
+/// during a panic, stops the thread and clears the line. Nothing is drawn when stderr is
+/// not a terminal.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{format_duration, LiveElapsed};
+///
+/// let live = LiveElapsed::start(Duration::from_millis(500));
+/// // ... long-running work ...
+/// let elapsed = live.finish();
+/// println!("done in {}", format_duration(elapsed));
+/// ```
+pub struct LiveElapsed {
+    start: Instant,
+    updater: Option<Updater<Box<dyn Write + Send>>>,
+}
+
+impl LiveElapsed {
+    /// Starts a live display labelled `"elapsed"`, redrawn every `interval`.
+    pub fn start(interval: Duration) -> Self {
+        Self::with_label("elapsed", interval)
+    }
+
+    /// Starts a live display with a custom label, redrawn every `interval`.
+    pub fn with_label(label: &str, interval: Duration) -> Self {
+        let out: Box<dyn Write + Send> = Box::new(io::stderr());
+        Self::start_to(out, io::stderr().is_terminal(), label, interval)
+    }
+
+    fn start_to(out: Box<dyn Write + Send>, animate: bool, label: &str, interval: Duration) -> Self {
+        let label = label.to_string();
+        let updater = animate.then(|| {
+            Updater::spawn(out, interval, move |_, elapsed| {
+                format!("{}: {}", label, live_elapsed(elapsed))
+            })
+        });
+        LiveElapsed {
+            start: Instant::now(),
+            updater,
+        }
+    }
+
+    /// Returns the time since the display was started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Stops the display, clears its line and returns the total elapsed time.
+    pub fn finish(mut self) -> Duration {
+        if let Some(updater) = self.updater.take() {
+            updater.finish();
+        }
+        self.start.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuf {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_spinner_replaced_by_final_line() {
+        let (value, formatted, out) = measure_with_spinner_to(Vec::new(), true, "Working", || {
+            thread::sleep(Duration::from_millis(250));
+            42
+        });
+        assert_eq!(value, 42);
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("⠋ Working 0s"));
+        assert!(text.ends_with(&format!("{}Working {}\n", CLEAR_LINE, formatted)));
+    }
+
+    #[test]
+    fn test_no_animation_when_not_a_terminal() {
+        let (_, formatted, out) = measure_with_spinner_to(Vec::new(), false, "Quiet", || ());
+        assert_eq!(String::from_utf8(out).unwrap(), format!("Quiet {}\n", formatted));
+    }
+
+    #[test]
+    fn test_updater_stops_on_panic() {
+        let result = std::panic::catch_unwind(|| {
+            let _updater = Updater::spawn(io::sink(), Duration::from_millis(10), |_, _| String::new());
+            panic!("work failed");
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_live_elapsed_rewrites_and_clears() {
+        let buf = SharedBuf::default();
+        let live = LiveElapsed::start_to(Box::new(buf.clone()), true, "build", Duration::from_millis(20));
+        thread::sleep(Duration::from_millis(70));
+        let elapsed = live.finish();
+        assert!(elapsed >= Duration::from_millis(70));
+
+        let text = buf.text();
+        assert!(text.matches("build: 0s").count() >= 2);
+        assert!(text.ends_with(CLEAR_LINE));
+    }
+
+    #[test]
+    fn test_live_elapsed_clears_on_drop() {
+        let buf = SharedBuf::default();
+        drop(LiveElapsed::start_to(Box::new(buf.clone()), true, "elapsed", Duration::from_secs(60)));
+        assert_eq!(buf.text(), format!("{}elapsed: 0s{}", CLEAR_LINE, CLEAR_LINE));
+    }
+}
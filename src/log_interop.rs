@@ -0,0 +1,73 @@
+//! Measurements as `log` records.
+//!
+//! Enabled with the `log` feature.
+
+use log::Level;
+
+use crate::clock::measure_call;
+use crate::format_duration;
+
+/// Runs a closure, logs `"label took 1.300s"` at `level` under `target` and returns the
+/// closure's result.
+///
+/// # Arguments
+///
+/// * `level` - The level to log at.
+/// * `target` - The log target, usually `module_path!()`.
+/// * `label` - Name of the operation, used in the message.
+/// * `f` - The closure to run and measure.
+///
+/// # Example
+///
+/// ```
+/// use log::Level;
+/// use elapsed_time::measure_and_log;
+///
+/// let rows = measure_and_log(Level::Info, module_path!(), "load_rows", || vec![1, 2, 3]);
+/// assert_eq!(rows.len(), 3);
+/// ```
+pub fn measure_and_log<F, T>(level: Level, target: &str, label: &str, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let (result, elapsed) = measure_call(f);
+    log::log!(target: target, level, "{} took {}", label, format_duration(elapsed));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::{Log, Metadata, Record};
+    use std::sync::Mutex;
+
+    /// Collects every record as `"LEVEL target: message"`.
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Log for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            let entry = format!("{} {}: {}", record.level(), record.target(), record.args());
+            self.0.lock().unwrap().push(entry);
+        }
+
+        fn flush(&self) {}
+    }
+
+    static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+    #[test]
+    fn test_measure_and_log() {
+        log::set_logger(&RECORDER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let value = measure_and_log(Level::Warn, "app::db", "db_query", || 42);
+        assert_eq!(value, 42);
+        let records = RECORDER.0.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records[0].starts_with("WARN app::db: db_query took "));
+    }
+}
@@ -0,0 +1,338 @@
+//! Measuring jobs you can't instrument by pairing start and end lines in their logs.
+//!
+//! Enabled with the `regex` feature.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+use regex::{Captures, Regex};
+
+use crate::format_duration;
+use crate::timestamp::{leading_timestamp, nanos_between, Timestamp};
+use crate::TimestampFormat;
+
+/// How many starts may wait for their end line by default.
+const DEFAULT_MAX_PENDING: usize = 10_000;
+
+/// A start line waiting for its end line.
+#[derive(Debug, Clone, Copy)]
+struct Start {
+    /// Its position among all starts, to find the oldest when evicting.
+    seq: u64,
+    /// When the line was read.
+    read_at: Instant,
+    /// The timestamp written at the start of the line, if any.
+    logged_at: Option<Timestamp>,
+}
+
+/// A start line and its matching end line, with the time between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSpan {
+    /// The correlation ID captured from both lines, if the patterns capture one.
+    pub id: Option<String>,
+    /// Time from the start line to the end line.
+    pub duration: Duration,
+}
+
+impl fmt::Display for LogSpan {
+    /// Renders `"<id>: <elapsed>"`, or just the elapsed time without an ID.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.id {
+            Some(id) => write!(f, "{}: {}", id, format_duration(self.duration)),
+            None => f.write_str(&format_duration(self.duration)),
+        }
+    }
+}
+
+/// Pairs lines matching a start pattern with later lines matching an end pattern.
+///
+/// If the patterns contain a capture group named `id` (or, failing that, any capture
+/// group), spans are correlated by the captured text, so interleaved jobs are paired
+/// correctly. Without captures, each end line closes the oldest open start.
+///
+/// Spans are timed by the timestamps that start the two lines, in any of the layouts
+/// [`elapsed_between`](crate::elapsed_between) reads, so a log that is already on disk
+/// gives the same durations as a live one. When either line has no timestamp, the times
+/// the lines were read are used instead.
+///
+/// Starts whose end never comes are kept up to [`max_pending`](LogPairer::max_pending);
+/// past that the oldest are dropped, and counted by [`dropped`](LogPairer::dropped).
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use regex::Regex;
+/// use elapsed_time::logtail::LogPairer;
+///
+/// let mut pairer = LogPairer::new(
+///     Regex::new(r"job (?P<id>\d+) started").unwrap(),
+///     Regex::new(r"job (?P<id>\d+) finished").unwrap(),
+/// );
+/// let t0 = Instant::now();
+/// assert_eq!(pairer.feed("job 7 started", t0), None);
+/// assert_eq!(pairer.feed("job 8 started", t0), None);
+/// let span = pairer.feed("job 7 finished", t0 + Duration::from_secs(83)).unwrap();
+/// assert_eq!(span.to_string(), "7: 1m 23s");
+///
+/// // Timestamped lines are timed by their timestamps, whenever they are read.
+/// pairer.feed("12:00:00 job 9 started", t0);
+/// let span = pairer.feed("12:05:00 job 9 finished", t0).unwrap();
+/// assert_eq!(span.to_string(), "9: 5m");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogPairer {
+    start: Regex,
+    end: Regex,
+    format: TimestampFormat,
+    max_pending: usize,
+    /// Open starts by correlation ID, in arrival order for each ID.
+    open: HashMap<Option<String>, VecDeque<Start>>,
+    pending: usize,
+    seq: u64,
+    dropped: u64,
+}
+
+/// Extracts the correlation ID from a match: the `id` group, else the first group.
+fn capture_id(captures: &Captures<'_>) -> Option<String> {
+    captures
+        .name("id")
+        .or_else(|| captures.iter().skip(1).flatten().next())
+        .map(|m| m.as_str().to_string())
+}
+
+impl LogPairer {
+    /// Creates a pairer for lines matching `start` and `end`.
+    pub fn new(start: Regex, end: Regex) -> Self {
+        LogPairer {
+            start,
+            end,
+            format: TimestampFormat::Auto,
+            max_pending: DEFAULT_MAX_PENDING,
+            open: HashMap::new(),
+            pending: 0,
+            seq: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Sets the layout of the timestamps that start each line; detected by default.
+    pub fn timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets how many starts may wait for their end line before the oldest are dropped;
+    /// 10,000 by default.
+    pub fn max_pending(mut self, max: usize) -> Self {
+        self.max_pending = max;
+        self
+    }
+
+    /// Processes one line read at time `at`.
+    ///
+    /// # Returns
+    ///
+    /// The completed span if the line matched the end pattern and a start was waiting.
+    pub fn feed(&mut self, line: &str, at: Instant) -> Option<LogSpan> {
+        let logged_at = leading_timestamp(line, self.format);
+        if let Some(captures) = self.end.captures(line) {
+            let id = capture_id(&captures);
+            if let Some(started) = self.take_start(&id) {
+                let logged = started
+                    .logged_at
+                    .zip(logged_at)
+                    .and_then(|(start, end)| nanos_between(start, end).ok())
+                    .and_then(|nanos| u64::try_from(nanos).ok());
+                let duration = match logged {
+                    Some(nanos) => Duration::from_nanos(nanos),
+                    None => at.saturating_duration_since(started.read_at),
+                };
+                return Some(LogSpan { id, duration });
+            }
+        }
+        if let Some(captures) = self.start.captures(line) {
+            if self.pending >= self.max_pending {
+                self.drop_oldest();
+            }
+            if self.max_pending > 0 {
+                let start = Start {
+                    seq: self.seq,
+                    read_at: at,
+                    logged_at,
+                };
+                self.seq += 1;
+                self.pending += 1;
+                self.open
+                    .entry(capture_id(&captures))
+                    .or_default()
+                    .push_back(start);
+            } else {
+                self.dropped += 1;
+            }
+        }
+        None
+    }
+
+    /// Removes and returns the oldest open start for `id`.
+    fn take_start(&mut self, id: &Option<String>) -> Option<Start> {
+        let starts = self.open.get_mut(id)?;
+        let started = starts.pop_front()?;
+        if starts.is_empty() {
+            self.open.remove(id);
+        }
+        self.pending -= 1;
+        Some(started)
+    }
+
+    /// Forgets the oldest open start of any ID.
+    fn drop_oldest(&mut self) {
+        let oldest = self
+            .open
+            .iter()
+            .filter_map(|(id, starts)| Some((starts.front()?.seq, id)))
+            .min_by_key(|(seq, _)| *seq)
+            .map(|(_, id)| id.clone());
+        if let Some(id) = oldest {
+            self.take_start(&id);
+            self.dropped += 1;
+        }
+    }
+
+    /// Returns the number of start lines still waiting for their end line.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Returns the number of start lines dropped unpaired because too many were waiting.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Reads lines from `reader` as they arrive and calls `on_span` for every completed
+    /// span.
+    ///
+    /// Lines are timed by their leading timestamps, or else by when they were read, so
+    /// this works both on saved logs and on live streams such as `tail -f app.log | ...`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first I/O error from `reader`.
+    pub fn follow<R, F>(&mut self, reader: R, mut on_span: F) -> std::io::Result<()>
+    where
+        R: BufRead,
+        F: FnMut(LogSpan),
+    {
+        for line in reader.lines() {
+            if let Some(span) = self.feed(&line?, Instant::now()) {
+                on_span(span);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(t0: Instant, n: u64) -> Instant {
+        t0 + Duration::from_secs(n)
+    }
+
+    #[test]
+    fn test_correlated_by_id() {
+        let mut pairer = LogPairer::new(
+            Regex::new(r"START (?P<id>\w+)").unwrap(),
+            Regex::new(r"END (?P<id>\w+)").unwrap(),
+        );
+        let t0 = Instant::now();
+        assert_eq!(pairer.feed("START a", t0), None);
+        assert_eq!(pairer.feed("START b", secs(t0, 1)), None);
+        assert_eq!(pairer.feed("noise", secs(t0, 2)), None);
+        assert_eq!(pairer.feed("END c", secs(t0, 3)), None);
+        assert_eq!(pairer.pending(), 2);
+
+        let b = pairer.feed("END b", secs(t0, 5)).unwrap();
+        assert_eq!(b, LogSpan { id: Some("b".into()), duration: Duration::from_secs(4) });
+        let a = pairer.feed("END a", secs(t0, 6)).unwrap();
+        assert_eq!(a.to_string(), "a: 6s");
+        assert_eq!(pairer.pending(), 0);
+    }
+
+    #[test]
+    fn test_uncorrelated_pairs_oldest_first() {
+        let mut pairer =
+            LogPairer::new(Regex::new("begin").unwrap(), Regex::new("done").unwrap());
+        let t0 = Instant::now();
+        pairer.feed("begin", t0);
+        pairer.feed("begin", secs(t0, 2));
+        assert_eq!(pairer.feed("done", secs(t0, 3)).unwrap().to_string(), "3s");
+        assert_eq!(pairer.feed("done", secs(t0, 4)).unwrap().duration, Duration::from_secs(2));
+        assert_eq!(pairer.feed("done", secs(t0, 5)), None);
+    }
+
+    #[test]
+    fn test_timed_by_line_timestamps() {
+        let mut pairer = LogPairer::new(
+            Regex::new(r"start job=(\d+)").unwrap(),
+            Regex::new(r"end job=(\d+)").unwrap(),
+        );
+        let input = "12:00:00 start job=1\n\
+                     12:01:30.250 start job=2\n\
+                     12:05:00 end job=1\n\
+                     23:59:00 start job=3\n\
+                     00:01:00 end job=3\n\
+                     end job=2\n";
+        let mut spans = Vec::new();
+        pairer.follow(input.as_bytes(), |span| spans.push(span.to_string())).unwrap();
+        assert_eq!(spans[..2], ["1: 5m", "3: 2m"]);
+        // The last end line has no timestamp, so its span falls back to read time.
+        assert!(spans[2].starts_with("2: 0"), "{}", spans[2]);
+
+        let mut pairer = LogPairer::new(Regex::new("begin").unwrap(), Regex::new("done").unwrap());
+        let t0 = Instant::now();
+        pairer.feed("2024-03-01T12:00:00Z begin", t0);
+        let span = pairer.feed("[01/Mar/2024:14:00:03 +0200] done", t0).unwrap();
+        assert_eq!(span.duration, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_max_pending_drops_oldest() {
+        let mut pairer = LogPairer::new(
+            Regex::new(r"in (\w)").unwrap(),
+            Regex::new(r"out (\w)").unwrap(),
+        )
+        .max_pending(2);
+        let t0 = Instant::now();
+        pairer.feed("in a", t0);
+        pairer.feed("in b", secs(t0, 1));
+        pairer.feed("in a", secs(t0, 2));
+        assert_eq!((pairer.pending(), pairer.dropped()), (2, 1));
+        // The first "a" was dropped, so the second one pairs.
+        assert_eq!(pairer.feed("out a", secs(t0, 5)).unwrap().duration, Duration::from_secs(3));
+        assert_eq!(pairer.feed("out b", secs(t0, 5)).unwrap().duration, Duration::from_secs(4));
+        assert_eq!(pairer.pending(), 0);
+
+        let mut none = LogPairer::new(Regex::new("in").unwrap(), Regex::new("out").unwrap())
+            .max_pending(0);
+        none.feed("in", t0);
+        assert_eq!((none.pending(), none.dropped()), (0, 1));
+    }
+
+    #[test]
+    fn test_unnamed_group_and_follow() {
+        let mut pairer = LogPairer::new(
+            Regex::new(r"req=(\d+) in").unwrap(),
+            Regex::new(r"req=(\d+) out").unwrap(),
+        );
+        let input = "req=1 in\nreq=2 in\nreq=2 out\nreq=1 out\n";
+        let mut ids = Vec::new();
+        pairer
+            .follow(input.as_bytes(), |span| ids.push(span.id.unwrap()))
+            .unwrap();
+        assert_eq!(ids, ["2", "1"]);
+    }
+}
@@ -0,0 +1,316 @@
+use alloc::format;
+use alloc::string::String;
+use core::time::Duration;
+
+/// Computes the ratio of two durations as a floating-point number.
+///
+/// Useful for "fraction of total time" style numbers, such as time spent in garbage
+/// collection relative to the whole run.
+///
+/// # Arguments
+///
+/// * `part` - The numerator duration.
+/// * `whole` - The denominator duration.
+///
+/// # Returns
+///
+/// `part / whole` as an `f64`, or `0.0` when `whole` is zero, so callers never see
+/// `NaN` or infinity.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::ratio;
+///
+/// assert_eq!(ratio(Duration::from_secs(21), Duration::from_secs(50)), 0.42);
+/// assert_eq!(ratio(Duration::from_secs(1), Duration::ZERO), 0.0);
+/// ```
+pub fn ratio(part: Duration, whole: Duration) -> f64 {
+    if whole.is_zero() {
+        return 0.0;
+    }
+    part.as_secs_f64() / whole.as_secs_f64()
+}
+
+/// Formats the ratio of two durations with two decimal places (e.g., "0.42").
+///
+/// A zero `whole` formats as "0.00", matching [`ratio`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_ratio;
+///
+/// assert_eq!(format_ratio(Duration::from_secs(21), Duration::from_secs(50)), "0.42");
+/// ```
+pub fn format_ratio(part: Duration, whole: Duration) -> String {
+    format!("{:.2}", ratio(part, whole))
+}
+
+/// Formats the ratio of two durations as a whole percentage (e.g., "42%").
+///
+/// A zero `whole` formats as "0%", matching [`ratio`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_percent;
+///
+/// let gc = Duration::from_millis(4_200);
+/// let total = Duration::from_secs(10);
+/// assert_eq!(format_percent(gc, total), "42%");
+/// ```
+pub fn format_percent(part: Duration, whole: Duration) -> String {
+    format!("{:.0}%", ratio(part, whole) * 100.0)
+}
+
+/// Converts a nanosecond count into a `Duration`, saturating at `Duration::MAX`.
+fn duration_from_nanos_saturating(nanos: u128) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+    let secs = nanos / NANOS_PER_SEC;
+    if secs > u64::MAX as u128 {
+        return Duration::MAX;
+    }
+    Duration::new(secs as u64, (nanos % NANOS_PER_SEC) as u32)
+}
+
+/// Multiplies a duration by a floating-point factor, saturating instead of panicking.
+///
+/// `Duration`'s own `mul_f64` panics on negative, non-finite or overflowing results; this
+/// clamps them instead so progress math can't bring a program down.
+///
+/// # Arguments
+///
+/// * `duration` - The duration to scale.
+/// * `factor` - The multiplier, e.g. `1.5` for "50% longer".
+///
+/// # Returns
+///
+/// The scaled duration. Negative factors and `NaN` yield `Duration::ZERO`; results too
+/// large to represent (including positive infinity) yield `Duration::MAX`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::scale;
+///
+/// assert_eq!(scale(Duration::from_secs(10), 1.5), Duration::from_secs(15));
+/// assert_eq!(scale(Duration::from_secs(10), -1.0), Duration::ZERO);
+/// assert_eq!(scale(Duration::MAX, 2.0), Duration::MAX);
+/// ```
+pub fn scale(duration: Duration, factor: f64) -> Duration {
+    if factor.is_nan() || factor <= 0.0 {
+        return Duration::ZERO;
+    }
+    Duration::try_from_secs_f64(duration.as_secs_f64() * factor).unwrap_or(Duration::MAX)
+}
+
+/// Projects the total time a job will take from the time spent on the part already done.
+///
+/// The calculation assumes a constant rate: `elapsed * total / done`. It is done in integer
+/// nanoseconds, so there is no float rounding for realistic inputs.
+///
+/// # Arguments
+///
+/// * `elapsed` - Time spent so far.
+/// * `done` - Units of work completed in that time.
+/// * `total` - Total units of work.
+///
+/// # Returns
+///
+/// The projected total duration, saturating at `Duration::MAX`. Returns `Duration::ZERO`
+/// when `done` is zero, since no rate is known yet.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::extrapolate;
+///
+/// // 25 of 100 items took 30 seconds, so the whole job should take 2 minutes.
+/// assert_eq!(extrapolate(Duration::from_secs(30), 25, 100), Duration::from_secs(120));
+/// ```
+pub fn extrapolate(elapsed: Duration, done: u64, total: u64) -> Duration {
+    if done == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = elapsed.as_nanos().saturating_mul(total as u128) / done as u128;
+    duration_from_nanos_saturating(nanos)
+}
+
+/// Projects how much time is left, the remaining part of [`extrapolate`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::extrapolate_remaining;
+///
+/// assert_eq!(extrapolate_remaining(Duration::from_secs(30), 25, 100), Duration::from_secs(90));
+/// ```
+pub fn extrapolate_remaining(elapsed: Duration, done: u64, total: u64) -> Duration {
+    extrapolate(elapsed, done, total).saturating_sub(elapsed)
+}
+
+/// Formats the projected total from [`extrapolate`] with [`format_duration`](crate::format_duration).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_extrapolated;
+///
+/// assert_eq!(format_extrapolated(Duration::from_secs(30), 25, 100), "2m");
+/// ```
+pub fn format_extrapolated(elapsed: Duration, done: u64, total: u64) -> String {
+    crate::format_duration(extrapolate(elapsed, done, total))
+}
+
+/// Formats how many items were processed per second, with an SI prefix and three
+/// significant digits (e.g., "12.4k items/s").
+///
+/// A zero `duration` formats as "0 items/s", so callers never see infinity.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_rate;
+///
+/// assert_eq!(format_rate(124_000, Duration::from_secs(10)), "12.4k items/s");
+/// assert_eq!(format_rate(3, Duration::from_secs(2)), "1.50 items/s");
+/// ```
+pub fn format_rate(count: u64, duration: Duration) -> String {
+    format!(
+        "{} items/s",
+        scaled(per_second(count, duration), 1000.0, &["", "k", "M", "G", "T"], "")
+    )
+}
+
+/// Formats how many bytes were processed per second, with a binary prefix and three
+/// significant digits (e.g., "356 MiB/s").
+///
+/// A zero `duration` formats as "0 B/s", so callers never see infinity.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_throughput;
+///
+/// assert_eq!(format_throughput(356 * 1024 * 1024, Duration::from_secs(1)), "356 MiB/s");
+/// assert_eq!(format_throughput(1536, Duration::from_secs(1)), "1.50 KiB/s");
+/// ```
+pub fn format_throughput(bytes: u64, duration: Duration) -> String {
+    format!(
+        "{}/s",
+        scaled(
+            per_second(bytes, duration),
+            1024.0,
+            &["B", "KiB", "MiB", "GiB", "TiB", "PiB"],
+            " "
+        )
+    )
+}
+
+/// Returns `count / duration` in units per second, or `0.0` for a zero duration.
+fn per_second(count: u64, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        return 0.0;
+    }
+    count as f64 / duration.as_secs_f64()
+}
+
+/// Formats `value` with three significant digits and the largest prefix that keeps it
+/// below `base`, e.g. `12400.0` with base 1000 as "12.4k".
+fn scaled(mut value: f64, base: f64, prefixes: &[&str], separator: &str) -> String {
+    let mut prefix = 0;
+    // Step up before a value would round to `base`, so 999.7 shows as "1.00k", not "1000".
+    while value >= base - 0.5 && prefix + 1 < prefixes.len() {
+        value /= base;
+        prefix += 1;
+    }
+    let digits = if value == 0.0 || value >= 99.95 {
+        0
+    } else if value >= 9.995 {
+        1
+    } else {
+        2
+    };
+    format!("{:.*}{}{}", digits, value, separator, prefixes[prefix])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratio() {
+        assert_eq!(ratio(Duration::from_secs(1), Duration::from_secs(4)), 0.25);
+        assert_eq!(ratio(Duration::from_secs(3), Duration::from_secs(2)), 1.5);
+        assert_eq!(ratio(Duration::ZERO, Duration::from_secs(2)), 0.0);
+        assert_eq!(ratio(Duration::ZERO, Duration::ZERO), 0.0);
+        assert_eq!(ratio(Duration::from_secs(5), Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_format_ratio_and_percent() {
+        let part = Duration::from_millis(333);
+        let whole = Duration::from_secs(1);
+        assert_eq!(format_ratio(part, whole), "0.33");
+        assert_eq!(format_percent(part, whole), "33%");
+
+        assert_eq!(format_ratio(part, Duration::ZERO), "0.00");
+        assert_eq!(format_percent(part, Duration::ZERO), "0%");
+        assert_eq!(format_percent(whole * 2, whole), "200%");
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(scale(Duration::from_secs(4), 0.5), Duration::from_secs(2));
+        assert_eq!(scale(Duration::from_secs(4), 0.0), Duration::ZERO);
+        assert_eq!(scale(Duration::from_secs(4), f64::NAN), Duration::ZERO);
+        assert_eq!(scale(Duration::from_secs(4), f64::INFINITY), Duration::MAX);
+        assert_eq!(scale(Duration::from_secs(4), f64::NEG_INFINITY), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        assert_eq!(extrapolate(Duration::from_secs(10), 1, 3), Duration::from_secs(30));
+        assert_eq!(extrapolate(Duration::from_secs(10), 3, 3), Duration::from_secs(10));
+        assert_eq!(extrapolate(Duration::from_millis(100), 3, 10), Duration::from_nanos(333_333_333));
+        assert_eq!(extrapolate(Duration::from_secs(10), 0, 3), Duration::ZERO);
+        assert_eq!(extrapolate(Duration::MAX, 1, u64::MAX), Duration::MAX);
+
+        assert_eq!(extrapolate_remaining(Duration::from_secs(10), 1, 3), Duration::from_secs(20));
+        assert_eq!(extrapolate_remaining(Duration::from_secs(10), 5, 3), Duration::ZERO);
+        assert_eq!(format_extrapolated(Duration::from_secs(10), 1, 3), "30s");
+    }
+
+    #[test]
+    fn test_format_rate() {
+        let second = Duration::from_secs(1);
+        assert_eq!(format_rate(356, second), "356 items/s");
+        assert_eq!(format_rate(12, second), "12.0 items/s");
+        assert_eq!(format_rate(1_000, second), "1.00k items/s");
+        assert_eq!(format_rate(999_700, Duration::from_secs(1000)), "1.00k items/s");
+        assert_eq!(format_rate(5_000_000_000, second), "5.00G items/s");
+        assert_eq!(format_rate(0, second), "0 items/s");
+        assert_eq!(format_rate(7, Duration::ZERO), "0 items/s");
+    }
+
+    #[test]
+    fn test_format_throughput() {
+        let second = Duration::from_secs(1);
+        assert_eq!(format_throughput(512, second), "512 B/s");
+        assert_eq!(format_throughput(1023, second), "1023 B/s");
+        assert_eq!(format_throughput(1024, second), "1.00 KiB/s");
+        assert_eq!(format_throughput(356 << 20, second), "356 MiB/s");
+        assert_eq!(format_throughput(10 << 30, Duration::from_secs(4)), "2.50 GiB/s");
+        assert_eq!(format_throughput(0, Duration::ZERO), "0 B/s");
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::{fmt_duration, format_duration};
+
+/// The result of timing something: the raw `Duration`, formatted on demand.
+///
+/// Keep measurements to aggregate or compare them, and print them with `Display` or
+/// [`formatted`](Measurement::formatted) when a human-readable string is needed.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::Measurement;
+///
+/// let a = Measurement::new(Duration::from_millis(1500));
+/// let b = Measurement::new(Duration::from_millis(500));
+/// assert!(a > b);
+/// assert_eq!(a.to_string(), "1.500s");
+///
+/// let total: Duration = a.duration() + Duration::from(b);
+/// assert_eq!(total, Duration::from_secs(2));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Measurement {
+    duration: Duration,
+}
+
+impl Measurement {
+    /// Wraps an already measured duration.
+    pub fn new(duration: Duration) -> Self {
+        Measurement { duration }
+    }
+
+    /// Returns the measured duration.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the measured duration formatted with [`format_duration`].
+    pub fn formatted(&self) -> String {
+        format_duration(self.duration)
+    }
+
+    /// Returns the measured duration in seconds.
+    pub fn as_secs_f64(&self) -> f64 {
+        self.duration.as_secs_f64()
+    }
+
+    /// Returns the measurement as JSON, e.g. `{"nanos":1500000000,"formatted":"1.500s"}`.
+    ///
+    /// Enabled with the `serde_json` feature.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> String {
+        crate::json::duration_value(self.duration).to_string()
+    }
+}
+
+impl From<Duration> for Measurement {
+    fn from(duration: Duration) -> Self {
+        Measurement::new(duration)
+    }
+}
+
+impl From<Measurement> for Duration {
+    fn from(measurement: Measurement) -> Self {
+        measurement.duration
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_duration(f, self.duration)
+    }
+}
+
+/// Measures the elapsed time of a function, keeping the raw duration.
+///
+/// # Arguments
+///
+/// * `f` - A closure that takes no arguments and returns a value of any type.
+///
+/// # Returns
+///
+/// A tuple of the closure's result and a [`Measurement`] of how long it took.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::measure;
+///
+/// let (sum, took) = measure(|| (1..=100u32).sum::<u32>());
+/// assert_eq!(sum, 5050);
+/// assert!(took.duration() < Duration::from_secs(1));
+/// println!("summed in {}", took);
+/// ```
+pub fn measure<F, T>(f: F) -> (T, Measurement)
+where
+    F: FnOnce() -> T,
+{
+    let (result, duration) = crate::clock::measure_call(f);
+    (result, Measurement::new(duration))
+}
+
+/// The outcome of a fallible operation timed with [`measure_result`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedResult<T, E> {
+    /// What the operation returned.
+    pub result: Result<T, E>,
+    /// How long the operation took.
+    pub duration: Duration,
+    /// The duration formatted with [`format_duration`].
+    pub formatted: String,
+}
+
+impl<T, E> TimedResult<T, E> {
+    /// Returns `true` if the operation succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// Returns what the operation returned, discarding the timing.
+    pub fn into_result(self) -> Result<T, E> {
+        self.result
+    }
+}
+
+/// Runs a fallible closure and times it, keeping the outcome next to the elapsed time.
+///
+/// Error paths often take very different times from the normal path, so callers can
+/// aggregate the two apart; [`timers::time_result`](crate::timers::time_result) does that
+/// in the registry.
+///
+/// # Arguments
+///
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// A [`TimedResult`] with the closure's result, the duration and its formatted string.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_result;
+///
+/// let timed = measure_result(|| "42".parse::<u32>());
+/// match &timed.result {
+///     Ok(n) => println!("parsed {} in {}", n, timed.formatted),
+///     Err(e) => eprintln!("failed after {}: {}", timed.formatted, e),
+/// }
+/// assert!(timed.is_ok());
+/// ```
+pub fn measure_result<F, T, E>(f: F) -> TimedResult<T, E>
+where
+    F: FnOnce() -> Result<T, E>,
+{
+    let (result, duration) = crate::clock::measure_call(f);
+    TimedResult {
+        result,
+        duration,
+        formatted: format_duration(duration),
+    }
+}
+
+/// Times closures at the call site: `(|| work()).timed()`.
+///
+/// Implemented for every `FnOnce() -> T`, so a closure can be timed where it is written
+/// instead of being wrapped in [`measure`].
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{timers, Timed};
+///
+/// let (sum, took) = (|| (1..=100u32).sum::<u32>()).timed();
+/// assert_eq!(sum, 5050);
+/// println!("summed in {}", took);
+///
+/// let (rows, _) = (|| vec![1, 2, 3]).timed_as("load rows");
+/// assert_eq!(rows.len(), 3);
+/// assert_eq!(timers::get("load rows").unwrap().count, 1);
+/// ```
+pub trait Timed<T>: FnOnce() -> T + Sized {
+    /// Calls the closure and returns its result with a [`Measurement`] of how long it took.
+    fn timed(self) -> (T, Measurement) {
+        measure(self)
+    }
+
+    /// Like [`timed`](Timed::timed), also recording the time under `label` in the
+    /// [`timers`](crate::timers) registry.
+    fn timed_as(self, label: &str) -> (T, Measurement) {
+        let (result, took) = measure(self);
+        crate::timers::record(label, took.duration());
+        (result, took)
+    }
+}
+
+impl<F: FnOnce() -> T, T> Timed<T> for F {}
+
+/// Times a block, reports it to the global [`sink`](crate::sink) as `"<label>
+/// (<file>:<line>)"` and evaluates to the block's value.
+///
+/// With the default sink this prints `"<label> (<file>:<line>): <elapsed>"` to stderr.
+///
+/// The time is reported however the block is left, including `?` and early `return`s.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::time;
+///
+/// let config = time!("load config", {
+///     std::env::var("CONFIG").unwrap_or_default()
+/// });
+/// // stderr: e.g. "load config (src/main.rs:4): 0s"
+/// # drop(config);
+/// ```
+#[macro_export]
+macro_rules! time {
+    ($label:expr, $body:block) => {{
+        let _time_guard = $crate::__private::BlockGuard::new($label, file!(), line!());
+        $body
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let (value, took) = measure(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            7
+        });
+        assert_eq!(value, 7);
+        assert!(took.duration() >= Duration::from_millis(20));
+        assert_eq!(took.to_string(), took.formatted());
+    }
+
+    #[test]
+    fn test_measure_result() {
+        let timed = measure_result(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            "x".parse::<u32>()
+        });
+        assert!(!timed.is_ok());
+        assert!(timed.duration >= Duration::from_millis(20));
+        assert_eq!(timed.formatted, format_duration(timed.duration));
+        assert_eq!(measure_result(|| Ok::<_, ()>(7)).into_result(), Ok(7));
+    }
+
+    #[test]
+    fn test_timed() {
+        let (value, took) = (|| {
+            std::thread::sleep(Duration::from_millis(20));
+            7
+        })
+        .timed();
+        assert_eq!(value, 7);
+        assert!(took.duration() >= Duration::from_millis(20));
+
+        let (value, took) = (|| "timed").timed_as("measurement::test_timed");
+        assert_eq!(value, "timed");
+        let stats = crate::timers::get("measurement::test_timed").unwrap();
+        assert_eq!((stats.count, stats.total), (1, took.duration()));
+    }
+
+    #[test]
+    fn test_conversions_and_ordering() {
+        let m: Measurement = Duration::from_secs(90).into();
+        assert_eq!(m.formatted(), "1m 30s");
+        assert_eq!(m.as_secs_f64(), 90.0);
+        assert_eq!(Duration::from(m), Duration::from_secs(90));
+
+        let mut all = [Measurement::new(Duration::from_secs(3)), m, Measurement::default()];
+        all.sort();
+        assert_eq!(all.map(|m| m.duration().as_secs()), [0, 3, 90]);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_to_json() {
+        let m = Measurement::new(Duration::from_millis(1500));
+        assert_eq!(m.to_json(), r#"{"nanos":1500000000,"formatted":"1.500s"}"#);
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How often the moving averages take in the events counted since the last tick.
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Averaging windows in minutes, matching the Unix load average.
+const WINDOWS: [f64; 3] = [1.0, 5.0, 15.0];
+
+/// Tracks how often events happen, as 1-, 5- and 15-minute moving averages.
+///
+/// The averages are exponentially weighted and updated every five seconds, in the same way
+/// as the Unix load average, so a burst shows up quickly in the 1-minute rate and fades
+/// slowly from the 15-minute one.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::Meter;
+///
+/// let mut requests = Meter::new();
+/// requests.mark(1);
+/// // ... later, in a status line ...
+/// println!("{}", requests); // e.g. "12.3/s (1m), 9.8/s (5m), 7.1/s (15m)"
+/// ```
+#[derive(Debug, Clone)]
+pub struct Meter {
+    start: Instant,
+    last_tick: Instant,
+    count: u64,
+    uncounted: u64,
+    rates: [Option<f64>; 3],
+}
+
+impl Default for Meter {
+    fn default() -> Self {
+        Meter::new()
+    }
+}
+
+impl Meter {
+    /// Creates a meter with no events.
+    pub fn new() -> Self {
+        Meter::new_at(Instant::now())
+    }
+
+    fn new_at(now: Instant) -> Self {
+        Meter {
+            start: now,
+            last_tick: now,
+            count: 0,
+            uncounted: 0,
+            rates: [None; 3],
+        }
+    }
+
+    /// Records `n` events.
+    pub fn mark(&mut self, n: u64) {
+        self.mark_at(n, Instant::now());
+    }
+
+    fn mark_at(&mut self, n: u64, now: Instant) {
+        if self.ticks_due(now) > 0 {
+            (self.rates, self.last_tick) = self.ticked(now);
+            self.uncounted = 0;
+        }
+        self.uncounted += n;
+        self.count += n;
+    }
+
+    /// Returns the number of whole tick intervals between the last tick and `now`.
+    fn ticks_due(&self, now: Instant) -> u32 {
+        let since = now.saturating_duration_since(self.last_tick);
+        (since.as_nanos() / TICK_INTERVAL.as_nanos()) as u32
+    }
+
+    /// Returns the averages and last tick time after applying any ticks due by `now`.
+    fn ticked(&self, now: Instant) -> ([Option<f64>; 3], Instant) {
+        let ticks = self.ticks_due(now);
+        if ticks == 0 {
+            return (self.rates, self.last_tick);
+        }
+        let instant_rate = self.uncounted as f64 / TICK_INTERVAL.as_secs_f64();
+        let mut rates = self.rates;
+        for (rate, minutes) in rates.iter_mut().zip(WINDOWS) {
+            let alpha = 1.0 - (-TICK_INTERVAL.as_secs_f64() / 60.0 / minutes).exp();
+            let updated = match *rate {
+                Some(rate) => rate + alpha * (instant_rate - rate),
+                None => instant_rate,
+            };
+            // The remaining ticks saw no events, which only decays the average.
+            *rate = Some(updated * (1.0 - alpha).powi(ticks as i32 - 1));
+        }
+        (rates, self.last_tick + TICK_INTERVAL * ticks)
+    }
+
+    fn rate_at(&self, window: usize, now: Instant) -> f64 {
+        let (rates, _) = self.ticked(now);
+        rates[window].unwrap_or(0.0)
+    }
+
+    /// Returns the total number of events recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the events per second averaged over about the last minute.
+    pub fn rate_1m(&self) -> f64 {
+        self.rate_at(0, Instant::now())
+    }
+
+    /// Returns the events per second averaged over about the last five minutes.
+    pub fn rate_5m(&self) -> f64 {
+        self.rate_at(1, Instant::now())
+    }
+
+    /// Returns the events per second averaged over about the last fifteen minutes.
+    pub fn rate_15m(&self) -> f64 {
+        self.rate_at(2, Instant::now())
+    }
+
+    /// Returns the events per second since the meter was created.
+    pub fn mean_rate(&self) -> f64 {
+        self.mean_rate_at(Instant::now())
+    }
+
+    fn mean_rate_at(&self, now: Instant) -> f64 {
+        let secs = now.saturating_duration_since(self.start).as_secs_f64();
+        if secs == 0.0 {
+            return 0.0;
+        }
+        self.count as f64 / secs
+    }
+
+    fn format_at(&self, now: Instant) -> String {
+        let (rates, _) = self.ticked(now);
+        let [one, five, fifteen] = rates.map(|rate| rate.unwrap_or(0.0));
+        format!("{:.1}/s (1m), {:.1}/s (5m), {:.1}/s (15m)", one, five, fifteen)
+    }
+}
+
+impl fmt::Display for Meter {
+    /// Renders `"12.3/s (1m), 9.8/s (5m), 7.1/s (15m)"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_at(Instant::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(t0: Instant, n: u64) -> Instant {
+        t0 + Duration::from_secs(n)
+    }
+
+    #[test]
+    fn test_first_tick_sets_rates() {
+        let t0 = Instant::now();
+        let mut meter = Meter::new_at(t0);
+        assert_eq!(meter.format_at(t0), "0.0/s (1m), 0.0/s (5m), 0.0/s (15m)");
+        meter.mark_at(50, secs(t0, 1));
+        // Nothing is averaged until the first tick.
+        assert_eq!(meter.rate_at(0, secs(t0, 4)), 0.0);
+        assert_eq!(meter.rate_at(0, secs(t0, 5)), 10.0);
+        assert_eq!(meter.format_at(secs(t0, 5)), "10.0/s (1m), 10.0/s (5m), 10.0/s (15m)");
+        assert_eq!(meter.count(), 50);
+        assert_eq!(meter.mean_rate_at(secs(t0, 10)), 5.0);
+    }
+
+    #[test]
+    fn test_idle_decay_matches_ticking() {
+        let t0 = Instant::now();
+        let mut meter = Meter::new_at(t0);
+        meter.mark_at(50, t0);
+        // Computing a minute of idle ticks at once matches marking zero events each tick.
+        let mut stepped = meter.clone();
+        for tick in 1..=12 {
+            stepped.mark_at(0, secs(t0, tick * 5));
+        }
+        let at_once = meter.rate_at(0, secs(t0, 60));
+        assert!((stepped.rate_at(0, secs(t0, 60)) - at_once).abs() < 1e-9);
+        // A minute of silence decays the 1-minute rate by about a factor of e.
+        assert!((at_once - 10.0 * (-55.0f64 / 60.0).exp()).abs() < 1e-9);
+        assert!(meter.rate_at(2, secs(t0, 60)) > at_once);
+    }
+
+    #[test]
+    fn test_steady_rate_converges() {
+        let t0 = Instant::now();
+        let mut meter = Meter::new_at(t0);
+        for s in 0..600 {
+            meter.mark_at(if s < 300 { 2 } else { 8 }, secs(t0, s));
+        }
+        let now = secs(t0, 600);
+        assert!((meter.rate_at(0, now) - 8.0).abs() < 0.1);
+        assert!(meter.rate_at(1, now) < meter.rate_at(0, now));
+        assert!(meter.rate_at(2, now) < meter.rate_at(1, now));
+    }
+}
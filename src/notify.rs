@@ -0,0 +1,87 @@
+//! Desktop notifications when long-running operations finish.
+//!
+//! Enabled with the `notify-rust` feature.
+
+use std::time::{Duration, Instant};
+
+use notify_rust::Notification;
+
+use crate::format_duration;
+
+/// Builds the notification summary, e.g. `"Build finished in 14m 3s"`.
+fn completion_message(label: &str, elapsed: Duration) -> String {
+    format!("{} finished in {}", label, format_duration(elapsed))
+}
+
+/// Shows a desktop notification announcing that `label` finished after `elapsed`.
+///
+/// # Errors
+///
+/// Returns the notification backend's error if the notification could not be shown,
+/// for example when no notification daemon is running.
+pub fn notify_completion(label: &str, elapsed: Duration) -> Result<(), notify_rust::error::Error> {
+    Notification::new()
+        .appname("elapsed_time")
+        .summary(&completion_message(label, elapsed))
+        .show()
+        .map(|_| ())
+}
+
+/// Runs a closure and shows a desktop notification if it took longer than `threshold`.
+///
+/// Quick operations finish silently, so this can wrap every step of a build script and
+/// only the slow ones will interrupt you. Failures to show the notification are ignored;
+/// they never affect the measured work.
+///
+/// # Arguments
+///
+/// * `label` - Name of the operation, used in the notification text.
+/// * `threshold` - The elapsed time a run must exceed to trigger a notification.
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// The closure's return value and the formatted elapsed time.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use elapsed_time::notify::measure_and_notify;
+///
+/// // Pops up "Build finished in 14m 3s" if the build takes more than a minute.
+/// let (status, elapsed) = measure_and_notify("Build", Duration::from_secs(60), || {
+///     std::process::Command::new("cargo").arg("build").status()
+/// });
+/// ```
+pub fn measure_and_notify<F, T>(label: &str, threshold: Duration, f: F) -> (T, String)
+where
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+    let value = f();
+    let elapsed = start.elapsed();
+    if elapsed > threshold {
+        let _ = notify_completion(label, elapsed);
+    }
+    (value, format_duration(elapsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_message() {
+        assert_eq!(
+            completion_message("Build", Duration::from_secs(843)),
+            "Build finished in 14m 3s"
+        );
+    }
+
+    #[test]
+    fn test_fast_operation_is_silent() {
+        let (value, _) = measure_and_notify("quick", Duration::from_secs(3600), || 7);
+        assert_eq!(value, 7);
+    }
+}
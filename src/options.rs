@@ -0,0 +1,1193 @@
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::time::Duration;
+
+use crate::i18n::{Locale, UnitNames};
+use crate::DisplayDuration;
+
+/// Durations formatted per buffer by [`format_durations`], and per task with `rayon`.
+const BATCH_CHUNK: usize = 4096;
+
+/// Room for the longest default output, that of `Duration::MAX`.
+const OUTPUT_CAPACITY: usize = 40;
+
+/// A unit of time that a [`DurationFormatter`] can show.
+///
+/// Units are ordered by size, so `TimeUnit::Seconds < TimeUnit::Weeks`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeUnit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    /// An approximate month: a twelfth of a year, 30 days and 10 hours.
+    Months,
+    /// A 365-day year.
+    Years,
+}
+
+impl TimeUnit {
+    /// Every unit, largest first.
+    pub(crate) const DESCENDING: [TimeUnit; 7] = [
+        TimeUnit::Years,
+        TimeUnit::Months,
+        TimeUnit::Weeks,
+        TimeUnit::Days,
+        TimeUnit::Hours,
+        TimeUnit::Minutes,
+        TimeUnit::Seconds,
+    ];
+
+    /// Returns the length of one unit in seconds.
+    pub(crate) const fn secs(self) -> u64 {
+        match self {
+            TimeUnit::Seconds => 1,
+            TimeUnit::Minutes => 60,
+            TimeUnit::Hours => 60 * 60,
+            TimeUnit::Days => 24 * 60 * 60,
+            TimeUnit::Weeks => 7 * 24 * 60 * 60,
+            TimeUnit::Months => 365 * 24 * 60 * 60 / 12,
+            TimeUnit::Years => 365 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// How a [`DurationFormatter`] writes units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnitStyle {
+    /// Abbreviated: `"1h 30m 5s"`.
+    #[default]
+    Short,
+    /// Spelled out, pluralized and spaced from the value: `"1 hour 30 minutes 5 seconds"`.
+    Long,
+    /// An ISO 8601 duration: `"PT1H30M5S"`, or `"P2W"` for whole weeks.
+    ///
+    /// Zero units are always left out and the fraction of a second loses its trailing
+    /// zeros. The separator, unit names and unit limit do not apply.
+    Iso8601,
+    /// A fixed-width digital clock, `"01:30:45.250"`, for aligned columns in logs and
+    /// progress bars.
+    ///
+    /// Hours grow past two digits when they must. The fraction always has the configured
+    /// number of digits; the unit range, separator, unit names and unit limit do not apply.
+    Clock,
+    /// Like [`Clock`](UnitStyle::Clock) with a leading day field: `"02:01:30:45.250"`.
+    DayClock,
+    /// Abbreviated with nothing in between, in the style of Go: `"1h30m45.5s"`, for URLs,
+    /// filenames and metric labels.
+    ///
+    /// The separator is ignored and the fraction of a second loses its trailing zeros.
+    Compact,
+}
+
+/// How a [`DurationFormatter`] treats the time below the last unit it shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rounding {
+    /// Drop it, as `format_duration` does: 59.9 s without milliseconds is `"59s"`.
+    #[default]
+    Truncate,
+    /// Round to the nearest unit, halves up: 59.9 s is `"1m"`.
+    HalfUp,
+    /// Round up whenever anything is left over: 59.1 s is `"1m"`.
+    Ceil,
+}
+
+/// The unit names a [`DurationFormatter`] uses.
+#[derive(Clone)]
+enum Names {
+    Locale(Locale),
+    Custom(Arc<dyn UnitNames>),
+}
+
+impl Names {
+    fn get(&self) -> &dyn UnitNames {
+        match self {
+            Names::Locale(locale) => locale,
+            Names::Custom(names) => names.as_ref(),
+        }
+    }
+}
+
+impl PartialEq for Names {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Names::Locale(a), Names::Locale(b)) => a == b,
+            (Names::Custom(a), Names::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Names {}
+
+impl fmt::Debug for Names {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Names::Locale(locale) => locale.fmt(f),
+            Names::Custom(names) => names.fmt(f),
+        }
+    }
+}
+
+/// A configurable duration formatter.
+///
+/// The default formatter produces exactly the output of
+/// [`format_duration`](crate::format_duration); the builder methods adjust the precision,
+/// the range of units shown, the separator and the unit style, so logs and user-facing
+/// screens can each get the output they need.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{DurationFormatter, TimeUnit, UnitStyle};
+///
+/// let formatter = DurationFormatter::new().separator("").precision(0);
+/// assert_eq!(formatter.format(Duration::from_millis(90_500)), "1m30s");
+///
+/// let ui = DurationFormatter::new()
+///     .smallest_unit(TimeUnit::Minutes)
+///     .unit_style(UnitStyle::Long);
+/// assert_eq!(ui.format(Duration::from_secs(5430)), "1 hour 30 minutes");
+///
+/// let hours = DurationFormatter::new().largest_unit(TimeUnit::Hours);
+/// assert_eq!(hours.format(Duration::from_secs(2 * 24 * 60 * 60)), "48h 0m 0s");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurationFormatter {
+    separator: String,
+    precision: u8,
+    largest_unit: TimeUnit,
+    smallest_unit: TimeUnit,
+    unit_style: UnitStyle,
+    small_units: bool,
+    max_units: Option<usize>,
+    rounding: Rounding,
+    hide_zero_units: bool,
+    names: Names,
+    suffixes: Vec<(TimeUnit, String)>,
+}
+
+/// The settings [`format_durations`] takes; another name for [`DurationFormatter`].
+pub type FormatOptions = DurationFormatter;
+
+impl Default for DurationFormatter {
+    fn default() -> Self {
+        DurationFormatter {
+            separator: " ".to_string(),
+            precision: 3,
+            largest_unit: TimeUnit::Weeks,
+            smallest_unit: TimeUnit::Seconds,
+            unit_style: UnitStyle::Short,
+            small_units: false,
+            max_units: None,
+            rounding: Rounding::Truncate,
+            hide_zero_units: false,
+            names: Names::Locale(Locale::English),
+            suffixes: Vec::new(),
+        }
+    }
+}
+
+impl DurationFormatter {
+    /// Creates a formatter with the default settings, matching `format_duration`.
+    pub fn new() -> Self {
+        DurationFormatter::default()
+    }
+
+    /// Sets the text placed between units, `" "` by default.
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Sets how many fractional digits of a second are shown, from 0 to 9 (3 by default).
+    ///
+    /// Digits beyond the precision are truncated, and a fraction that is zero at this
+    /// precision is left out: with the default, 1.5 s is `"1.500s"` and 2 s is `"2s"`.
+    /// Values above 9 are treated as 9.
+    pub fn precision(mut self, digits: u8) -> Self {
+        self.precision = digits.min(9);
+        self
+    }
+
+    /// Chooses whether seconds show milliseconds, as in `"1.500s"` (the default), or are
+    /// truncated to whole seconds. Shorthand for a precision of 3 or 0.
+    pub fn millis(self, millis: bool) -> Self {
+        self.precision(if millis { 3 } else { 0 })
+    }
+
+    /// Sets the largest unit shown, `TimeUnit::Weeks` by default. Time beyond it is
+    /// counted in it, as in `"48h 0m 0s"`.
+    ///
+    /// Long durations pile up as large week counts by default; use `TimeUnit::Years` or
+    /// `TimeUnit::Months` here to show 365-day years and approximate months instead, as in
+    /// `"1y 2mo 1w 1d 0h 0m 0s"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::{DurationFormatter, TimeUnit};
+    ///
+    /// // Dashboards that speak in hours.
+    /// let hours = DurationFormatter::new()
+    ///     .largest_unit(TimeUnit::Hours)
+    ///     .smallest_unit(TimeUnit::Minutes);
+    /// assert_eq!(hours.format(Duration::from_secs(3 * 86_400 + 15 * 60)), "72h 15m");
+    /// ```
+    pub fn largest_unit(mut self, unit: TimeUnit) -> Self {
+        self.largest_unit = unit;
+        self.smallest_unit = self.smallest_unit.min(unit);
+        self
+    }
+
+    /// Sets the smallest unit shown, `TimeUnit::Seconds` by default. Time below it is
+    /// dropped, as in `"1h 30m"`, or rounded into it with [`rounding`](Self::rounding).
+    /// Within seconds, [`precision`](Self::precision) sets how many fractional digits
+    /// remain.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::{DurationFormatter, Rounding, TimeUnit};
+    ///
+    /// // An uptime banner.
+    /// let uptime = DurationFormatter::new()
+    ///     .smallest_unit(TimeUnit::Hours)
+    ///     .rounding(Rounding::HalfUp);
+    /// assert_eq!(uptime.format(Duration::from_secs(2 * 86_400 + 3 * 3600 + 40 * 60)), "2d 4h");
+    ///
+    /// // A benchmark result.
+    /// let bench = DurationFormatter::new().largest_unit(TimeUnit::Seconds).precision(6);
+    /// assert_eq!(bench.format(Duration::from_nanos(45_123_456_789)), "45.123456s");
+    /// ```
+    pub fn smallest_unit(mut self, unit: TimeUnit) -> Self {
+        self.smallest_unit = unit;
+        self.largest_unit = self.largest_unit.max(unit);
+        self
+    }
+
+    /// Sets how unit names are written, `UnitStyle::Short` by default.
+    pub fn unit_style(mut self, style: UnitStyle) -> Self {
+        self.unit_style = style;
+        self
+    }
+
+    /// Chooses whether durations under a millisecond are shown in microseconds or
+    /// nanoseconds, as in `"153µs"` and `"420ns"`, instead of as `"0s"`. Off by default.
+    ///
+    /// Takes effect only while seconds are the smallest unit shown.
+    pub fn small_units(mut self, small_units: bool) -> Self {
+        self.small_units = small_units;
+        self
+    }
+
+    /// Shows at most `n` units, starting from the largest non-zero one, as in `"1h 30m"`
+    /// for `n = 2`. Trailing zero units inside the limit are dropped as well, so one hour
+    /// and 45 seconds is `"1h"`. Unlimited by default; `0` is treated as 1.
+    pub fn max_units(mut self, n: usize) -> Self {
+        self.max_units = Some(n.max(1));
+        self
+    }
+
+    /// Sets how the time below the last unit shown is treated, `Rounding::Truncate` by
+    /// default.
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Chooses whether the last unit shown is rounded half up instead of truncated (the
+    /// default), as in `"1h 31m"` rather than `"1h 30m"` for 1h 30m 45s. Shorthand for
+    /// `Rounding::HalfUp` or `Rounding::Truncate`.
+    pub fn round(self, round: bool) -> Self {
+        self.rounding(if round {
+            Rounding::HalfUp
+        } else {
+            Rounding::Truncate
+        })
+    }
+
+    /// Writes unit names from one of the built-in [`Locale`] tables, English by default.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.names = Names::Locale(locale);
+        self
+    }
+
+    /// Writes `suffix` after values of `unit` in the abbreviated styles, instead of the
+    /// unit names' abbreviation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::{DurationFormatter, TimeUnit};
+    ///
+    /// // "min" can't be mistaken for meters.
+    /// let formatter = DurationFormatter::new()
+    ///     .suffix(TimeUnit::Minutes, "min")
+    ///     .separator(", ");
+    /// assert_eq!(formatter.format(Duration::from_secs(125)), "2min, 5s");
+    /// ```
+    pub fn suffix(mut self, unit: TimeUnit, suffix: impl Into<String>) -> Self {
+        let suffix = suffix.into();
+        match self.suffixes.iter_mut().find(|(u, _)| *u == unit) {
+            Some((_, existing)) => *existing = suffix,
+            None => self.suffixes.push((unit, suffix)),
+        }
+        self
+    }
+
+    /// Writes unit names from a custom [`UnitNames`] implementation.
+    pub fn unit_names(mut self, names: impl UnitNames + 'static) -> Self {
+        self.names = Names::Custom(Arc::new(names));
+        self
+    }
+
+    /// Chooses whether units that are zero are left out, as in `"1h 5s"` rather than
+    /// `"1h 0m 5s"`. Off by default.
+    pub fn hide_zero_units(mut self, hide: bool) -> Self {
+        self.hide_zero_units = hide;
+        self
+    }
+
+    /// Returns a formatter for end-user messages: spelled-out, pluralized unit names with
+    /// zero units left out, as in `"2 minutes 5 seconds"`. This is what
+    /// [`format_duration_verbose`] uses.
+    pub fn verbose() -> Self {
+        DurationFormatter::new()
+            .unit_style(UnitStyle::Long)
+            .hide_zero_units(true)
+    }
+
+    /// Returns a formatter suited to micro-benchmarks: sub-millisecond durations in
+    /// microseconds or nanoseconds and everything else with nanosecond precision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::DurationFormatter;
+    ///
+    /// let precise = DurationFormatter::precise();
+    /// assert_eq!(precise.format(Duration::from_nanos(420)), "420ns");
+    /// assert_eq!(precise.format(Duration::from_nanos(153_250)), "153µs");
+    /// assert_eq!(precise.format(Duration::from_nanos(1_234_567_891)), "1.234567891s");
+    /// ```
+    pub fn precise() -> Self {
+        DurationFormatter::new().precision(9).small_units(true)
+    }
+
+    /// Formats one duration with these settings.
+    pub fn format(&self, duration: Duration) -> String {
+        let mut out = String::with_capacity(OUTPUT_CAPACITY);
+        self.write_to(&mut out, duration);
+        out
+    }
+
+    /// Returns a value that formats `duration` with these settings only when it is displayed.
+    ///
+    /// Prefer it to [`format`](DurationFormatter::format) for log messages that may be
+    /// filtered out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::DurationFormatter;
+    ///
+    /// let formatter = DurationFormatter::new().precision(1);
+    /// let took = formatter.display(Duration::from_millis(1_250));
+    /// assert_eq!(format!("took {}", took), "took 1.2s");
+    /// ```
+    pub fn display(&self, duration: Duration) -> DisplayDuration<'_> {
+        DisplayDuration::with_formatter(duration, self)
+    }
+
+    /// Formats `duration` only if it is longer than `threshold`, for reports that should
+    /// only mention slow outliers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::DurationFormatter;
+    ///
+    /// let formatter = DurationFormatter::new();
+    /// let threshold = Duration::from_secs(1);
+    /// assert_eq!(formatter.format_if_slower_than(Duration::from_millis(200), threshold), None);
+    /// assert_eq!(
+    ///     formatter.format_if_slower_than(Duration::from_millis(1300), threshold),
+    ///     Some("1.300s".to_string())
+    /// );
+    /// ```
+    pub fn format_if_slower_than(
+        &self,
+        duration: Duration,
+        threshold: Duration,
+    ) -> Option<String> {
+        (duration > threshold).then(|| self.format(duration))
+    }
+
+    /// Appends the formatted `duration` to `out` without intermediate allocations.
+    fn write_to(&self, out: &mut String, duration: Duration) {
+        let names = self.names.get();
+        let duration = self.rounded(duration);
+        match self.unit_style {
+            UnitStyle::Iso8601 => return self.write_iso8601(out, duration),
+            UnitStyle::Clock => return self.write_clock(out, duration, false),
+            UnitStyle::DayClock => return self.write_clock(out, duration, true),
+            UnitStyle::Short | UnitStyle::Long | UnitStyle::Compact => {}
+        }
+        if self.uses_small_units(duration) {
+            let (value, short, nanos) = match duration.as_nanos() {
+                nanos @ 0..=999 => (nanos as u64, "ns", true),
+                nanos => ((nanos / 1_000) as u64, "µs", false),
+            };
+            let _ = write!(out, "{}", value);
+            if self.unit_style == UnitStyle::Long {
+                let plural = names.is_plural(value, false);
+                out.push(' ');
+                out.push_str(if nanos {
+                    names.long_nanos(plural)
+                } else {
+                    names.long_micros(plural)
+                });
+            } else {
+                self.push_short(out, short);
+            }
+            return;
+        }
+
+        let (values, len, fraction) = self.split(duration);
+        let (lead, _, end) = self.shown(&values[..len], fraction);
+        let shown = &values[lead..end];
+
+        let compact = self.unit_style == UnitStyle::Compact;
+        let start = out.len();
+        for (unit, value) in shown {
+            let has_fraction = *unit == TimeUnit::Seconds && fraction > 0;
+            if self.hide_zero_units && *value == 0 && !has_fraction && shown.len() > 1 {
+                continue;
+            }
+            if out.len() > start && !compact {
+                out.push_str(&self.separator);
+            }
+            // Writing to a `String` cannot fail.
+            let _ = write!(out, "{}", value);
+            if has_fraction {
+                out.push(names.decimal_mark());
+                let digits = out.len();
+                let _ = write!(
+                    out,
+                    "{:0width$}",
+                    fraction,
+                    width = usize::from(self.precision)
+                );
+                if compact {
+                    let trimmed = out[digits..].trim_end_matches('0').len();
+                    out.truncate(digits + trimmed);
+                }
+            }
+            if self.unit_style == UnitStyle::Long {
+                out.push(' ');
+                out.push_str(names.long(*unit, names.is_plural(*value, has_fraction)));
+            } else {
+                let suffix = self
+                    .suffixes
+                    .iter()
+                    .find(|(u, _)| u == unit)
+                    .map_or_else(|| names.short(*unit), |(_, suffix)| suffix.as_str());
+                self.push_short(out, suffix);
+            }
+        }
+    }
+
+    /// Appends `duration` as an ISO 8601 duration such as `"P1DT2H30M0.5S"`.
+    fn write_iso8601(&self, out: &mut String, duration: Duration) {
+        let (values, len, fraction) = self.split(duration);
+        let value_of = |unit| {
+            values[..len]
+                .iter()
+                .find(|(u, _)| *u == unit)
+                .map_or(0, |(_, value)| *value)
+        };
+
+        out.push('P');
+        let [years, months, weeks, days] = [
+            TimeUnit::Years,
+            TimeUnit::Months,
+            TimeUnit::Weeks,
+            TimeUnit::Days,
+        ]
+        .map(value_of);
+        let time = [TimeUnit::Hours, TimeUnit::Minutes, TimeUnit::Seconds].map(value_of);
+        // Weeks can't be combined with other designators, so only whole weeks use them.
+        let rest_is_zero = time == [0; 3] && fraction == 0;
+        if weeks > 0 && years == 0 && months == 0 && days == 0 && rest_is_zero {
+            let _ = write!(out, "{}W", weeks);
+            return;
+        }
+        let days = weeks.saturating_mul(7).saturating_add(days);
+        for (value, designator) in [(years, 'Y'), (months, 'M'), (days, 'D')] {
+            if value > 0 {
+                let _ = write!(out, "{}{}", value, designator);
+            }
+        }
+        if rest_is_zero && (years > 0 || months > 0 || days > 0) {
+            return;
+        }
+
+        out.push('T');
+        let [hours, minutes, seconds] = time;
+        if hours > 0 {
+            let _ = write!(out, "{}H", hours);
+        }
+        if minutes > 0 {
+            let _ = write!(out, "{}M", minutes);
+        }
+        if fraction > 0 {
+            let digits = format!("{:0width$}", fraction, width = usize::from(self.precision));
+            let _ = write!(out, "{}.{}S", seconds, digits.trim_end_matches('0'));
+        } else if seconds > 0 || (hours == 0 && minutes == 0) {
+            let _ = write!(out, "{}S", seconds);
+        }
+    }
+
+    /// Appends `duration` as `HH:MM:SS.fff`, with a leading `DD:` field if `days`.
+    fn write_clock(&self, out: &mut String, duration: Duration, days: bool) {
+        let secs = duration.as_secs();
+        let hours = if days {
+            let _ = write!(out, "{:02}:", secs / 86_400);
+            secs % 86_400 / 3_600
+        } else {
+            secs / 3_600
+        };
+        let _ = write!(
+            out,
+            "{:02}:{:02}:{:02}",
+            hours,
+            secs % 3_600 / 60,
+            secs % 60
+        );
+        if self.precision > 0 {
+            let fraction = duration.subsec_nanos() / self.fraction_step();
+            let _ = write!(
+                out,
+                ".{:0width$}",
+                fraction,
+                width = usize::from(self.precision)
+            );
+        }
+    }
+
+    /// Appends an abbreviation after its value, spaced as the unit names require.
+    fn push_short(&self, out: &mut String, short: &str) {
+        if self.unit_style != UnitStyle::Compact && self.names.get().space_before_short() {
+            out.push(' ');
+        }
+        out.push_str(short);
+    }
+
+    /// Returns `true` if `duration` is shown in microseconds or nanoseconds.
+    fn uses_small_units(&self, duration: Duration) -> bool {
+        self.small_units
+            && self.smallest_unit == TimeUnit::Seconds
+            && !duration.is_zero()
+            && duration < Duration::from_millis(1)
+    }
+
+    /// Splits `duration` into the value of each unit in range, largest first, and the
+    /// fraction of a second at the configured precision.
+    fn split(&self, duration: Duration) -> ([(TimeUnit, u64); 7], usize, u32) {
+        let mut values = [(TimeUnit::Seconds, 0u64); 7];
+        let mut len = 0;
+        let mut rest = duration.as_secs();
+        for unit in TimeUnit::DESCENDING {
+            if unit <= self.largest_unit && unit >= self.smallest_unit {
+                values[len] = (unit, rest / unit.secs());
+                rest %= unit.secs();
+                len += 1;
+            }
+        }
+        let fraction = if self.smallest_unit == TimeUnit::Seconds {
+            duration.subsec_nanos() / self.fraction_step()
+        } else {
+            0
+        };
+        (values, len, fraction)
+    }
+
+    /// Returns the nanoseconds in one step of the shown fraction of a second.
+    fn fraction_step(&self) -> u32 {
+        10u32.pow(9 - u32::from(self.precision))
+    }
+
+    /// Picks the units to show from `values`.
+    ///
+    /// Returns the index of the leading unit, the end of the window of units that may be
+    /// shown, and the end of the units actually shown.
+    fn shown(&self, values: &[(TimeUnit, u64)], fraction: u32) -> (usize, usize, usize) {
+        let len = values.len();
+        // Leading zero units are skipped.
+        let lead = match values.iter().position(|(_, value)| *value > 0) {
+            Some(lead) => lead,
+            None => len - 1,
+        };
+        let window = match self.max_units {
+            Some(n) => (lead + n).min(len),
+            None => len,
+        };
+        let has_fraction = window == len && fraction > 0;
+        let end = match self.max_units {
+            // A limited window drops its trailing zero units: "1h", not "1h 0m".
+            Some(_) => {
+                let mut end = window;
+                while end > lead + 1 && values[end - 1].1 == 0 && !(end == len && has_fraction) {
+                    end -= 1;
+                }
+                end
+            }
+            // Otherwise every unit below the leading one is shown, except that whole
+            // minutes stand alone ("2m" rather than "2m 0s").
+            None => {
+                let rest_is_zero = values[lead + 1..].iter().all(|(_, value)| *value == 0);
+                if values[lead].0 == TimeUnit::Minutes && rest_is_zero && fraction == 0 {
+                    lead + 1
+                } else {
+                    len
+                }
+            }
+        };
+        (lead, window, end)
+    }
+
+    /// Returns `duration` adjusted so that truncating it to the finest unit that would be
+    /// shown applies the rounding mode.
+    fn rounded(&self, duration: Duration) -> Duration {
+        if self.rounding == Rounding::Truncate {
+            return duration;
+        }
+        let step = if self.uses_small_units(duration) {
+            if duration < Duration::from_micros(1) {
+                return duration;
+            }
+            Duration::from_micros(1)
+        } else {
+            let (values, len, fraction) = self.split(duration);
+            let (_, window, _) = self.shown(&values[..len], fraction);
+            match values[window - 1].0 {
+                TimeUnit::Seconds => Duration::from_nanos(u64::from(self.fraction_step())),
+                unit => Duration::from_secs(unit.secs()),
+            }
+        };
+        let nudge = match self.rounding {
+            Rounding::Truncate => Duration::ZERO,
+            Rounding::HalfUp => step / 2,
+            Rounding::Ceil => step - Duration::from_nanos(1),
+        };
+        duration.saturating_add(nudge)
+    }
+
+    /// Formats a run of durations, reusing one scratch buffer.
+    fn format_chunk(&self, durations: &[Duration]) -> Vec<String> {
+        let mut scratch = String::with_capacity(OUTPUT_CAPACITY);
+        durations
+            .iter()
+            .map(|duration| {
+                scratch.clear();
+                self.write_to(&mut scratch, *duration);
+                scratch.as_str().to_owned()
+            })
+            .collect()
+    }
+}
+
+/// Formats a duration with at most `max_units` units, for compact displays.
+///
+/// This is `format_duration` limited to the `max_units` largest units starting from the
+/// first non-zero one, with the rest truncated; see [`DurationFormatter::max_units`] and
+/// [`DurationFormatter::round`] for more control.
+///
+/// # Arguments
+///
+/// * `duration` - The duration to format.
+/// * `max_units` - How many units to keep, at least 1.
+///
+/// # Returns
+///
+/// A `String` such as `"1h 30m"`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_duration_compact;
+///
+/// let d = Duration::from_millis(5_445_123); // 1h 30m 45.123s
+/// assert_eq!(format_duration_compact(d, 2), "1h 30m");
+/// assert_eq!(format_duration_compact(d, 1), "1h");
+/// ```
+pub fn format_duration_compact(duration: Duration, max_units: usize) -> String {
+    DurationFormatter::new()
+        .max_units(max_units)
+        .format(duration)
+}
+
+/// Formats a duration in words with pluralized unit names, for end-user messages.
+///
+/// Zero units are left out, so this reads `"1 hour 1 minute"` where `format_duration`
+/// reads `"1h 1m 0s"`; see [`DurationFormatter::verbose`] to adjust it further.
+///
+/// # Arguments
+///
+/// * `duration` - The duration to format.
+///
+/// # Returns
+///
+/// A `String` such as `"2 minutes 5 seconds"`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_duration_verbose;
+///
+/// assert_eq!(format_duration_verbose(Duration::from_secs(125)), "2 minutes 5 seconds");
+/// assert_eq!(format_duration_verbose(Duration::from_secs(3660)), "1 hour 1 minute");
+/// assert_eq!(format_duration_verbose(Duration::from_millis(5006)), "5.006 seconds");
+/// ```
+pub fn format_duration_verbose(duration: Duration) -> String {
+    DurationFormatter::verbose().format(duration)
+}
+
+/// Formats a duration as an ISO 8601 duration, for APIs and payloads that require it.
+///
+/// Whole weeks are written in weeks (`"P2W"`); anything else uses days and time
+/// (`"P1DT2H"`), since ISO 8601 doesn't mix weeks with other units. Fractions of a second
+/// are kept to the nanosecond without trailing zeros.
+///
+/// # Arguments
+///
+/// * `duration` - The duration to format.
+///
+/// # Returns
+///
+/// A `String` such as `"PT1H30M45.5S"`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_duration_iso8601;
+///
+/// assert_eq!(format_duration_iso8601(Duration::from_millis(5_445_500)), "PT1H30M45.5S");
+/// assert_eq!(format_duration_iso8601(Duration::from_secs(14 * 24 * 60 * 60)), "P2W");
+/// assert_eq!(format_duration_iso8601(Duration::ZERO), "PT0S");
+/// ```
+pub fn format_duration_iso8601(duration: Duration) -> String {
+    DurationFormatter::new()
+        .unit_style(UnitStyle::Iso8601)
+        .precision(9)
+        .format(duration)
+}
+
+/// Formats many durations at once, for log-processing jobs that humanize millions of values.
+///
+/// Each output is written straight into a reused buffer instead of being assembled from
+/// temporary strings. With the `rayon` feature, large inputs are formatted in parallel.
+///
+/// # Arguments
+///
+/// * `durations` - The durations to format.
+/// * `formatter` - How to format them; `DurationFormatter::default()` matches `format_duration`.
+///
+/// # Returns
+///
+/// One string per duration, in the same order.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::{format_durations, DurationFormatter};
+///
+/// let durations = [Duration::from_millis(500), Duration::from_secs(125)];
+/// assert_eq!(format_durations(&durations, &DurationFormatter::new()), ["0.500s", "2m 5s"]);
+/// ```
+pub fn format_durations(durations: &[Duration], formatter: &DurationFormatter) -> Vec<String> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        if durations.len() > BATCH_CHUNK {
+            return durations
+                .par_chunks(BATCH_CHUNK)
+                .flat_map_iter(|chunk| formatter.format_chunk(chunk))
+                .collect();
+        }
+    }
+    durations
+        .chunks(BATCH_CHUNK)
+        .flat_map(|chunk| formatter.format_chunk(chunk))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_duration;
+
+    fn samples() -> Vec<Duration> {
+        let mut samples: Vec<Duration> = [0, 1, 999, 1000, 1500, 60_000, 61_001, 3_600_000]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect();
+        samples.extend((0..10_000u64).map(|i| Duration::from_millis(i * i * 7919)));
+        samples.push(Duration::MAX);
+        samples
+    }
+
+    #[test]
+    fn test_default_matches_format_duration() {
+        let samples = samples();
+        let batch = format_durations(&samples, &FormatOptions::default());
+        assert_eq!(batch.len(), samples.len());
+        for (duration, formatted) in samples.iter().zip(&batch) {
+            assert_eq!(*formatted, format_duration(*duration));
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let compact = FormatOptions::default().separator("");
+        assert_eq!(compact.format(Duration::from_secs(3665)), "1h1m5s");
+        let whole = FormatOptions::default().millis(false);
+        assert_eq!(whole.format(Duration::from_millis(1500)), "1s");
+        assert_eq!(whole.format(Duration::from_millis(120_250)), "2m");
+        assert_eq!(whole.format(Duration::from_millis(400)), "0s");
+    }
+
+    #[test]
+    fn test_format_if_slower_than() {
+        let formatter = FormatOptions::default();
+        let threshold = Duration::from_secs(1);
+        assert_eq!(formatter.format_if_slower_than(threshold, threshold), None);
+        assert_eq!(
+            formatter.format_if_slower_than(Duration::from_secs(90), threshold).as_deref(),
+            Some("1m 30s")
+        );
+    }
+
+    #[test]
+    fn test_precision() {
+        let d = Duration::new(3, 141_592_653);
+        assert_eq!(DurationFormatter::new().precision(0).format(d), "3s");
+        assert_eq!(DurationFormatter::new().precision(1).format(d), "3.1s");
+        assert_eq!(
+            DurationFormatter::new().precision(9).format(d),
+            "3.141592653s"
+        );
+        assert_eq!(
+            DurationFormatter::new()
+                .precision(6)
+                .format(Duration::from_secs(3)),
+            "3s"
+        );
+        assert_eq!(
+            DurationFormatter::new()
+                .precision(6)
+                .format(Duration::from_micros(5)),
+            "0.000005s"
+        );
+    }
+
+    #[test]
+    fn test_unit_range() {
+        let d = Duration::new(2 * 24 * 60 * 60 + 5430, 250_000_000);
+        let f = DurationFormatter::new();
+        assert_eq!(f.format(d), "2d 1h 30m 30.250s");
+        assert_eq!(
+            f.clone().largest_unit(TimeUnit::Hours).format(d),
+            "49h 30m 30.250s"
+        );
+        assert_eq!(
+            f.clone().largest_unit(TimeUnit::Seconds).format(d),
+            "178230.250s"
+        );
+        assert_eq!(
+            f.clone().smallest_unit(TimeUnit::Minutes).format(d),
+            "2d 1h 30m"
+        );
+        assert_eq!(
+            f.clone()
+                .smallest_unit(TimeUnit::Hours)
+                .format(Duration::from_secs(59)),
+            "0h"
+        );
+        assert_eq!(
+            f.clone()
+                .smallest_unit(TimeUnit::Minutes)
+                .format(Duration::from_secs(600)),
+            "10m"
+        );
+
+        // The range always contains at least one unit.
+        let f = f
+            .smallest_unit(TimeUnit::Days)
+            .largest_unit(TimeUnit::Hours);
+        assert_eq!(f.format(d), "49h");
+    }
+
+    #[test]
+    fn test_largest_unit_clamps_every_unit() {
+        let d = Duration::from_secs(36 * 3600 + 61);
+        let top = |unit| DurationFormatter::new().largest_unit(unit).format(d);
+        assert_eq!(top(TimeUnit::Years), "1d 12h 1m 1s");
+        assert_eq!(top(TimeUnit::Weeks), "1d 12h 1m 1s");
+        assert_eq!(top(TimeUnit::Days), "1d 12h 1m 1s");
+        assert_eq!(top(TimeUnit::Hours), "36h 1m 1s");
+        assert_eq!(top(TimeUnit::Minutes), "2161m 1s");
+        assert_eq!(top(TimeUnit::Seconds), "129661s");
+        // Clamping never shows units above the cap, however long the duration.
+        let max = DurationFormatter::new()
+            .largest_unit(TimeUnit::Hours)
+            .format(Duration::MAX);
+        assert!(max.starts_with("5124095576030431h "), "{}", max);
+    }
+
+    #[test]
+    fn test_smallest_unit_rounds_cut_off_unit() {
+        let d = Duration::from_secs(2 * 86_400 + 3 * 3600 + 29 * 60 + 59);
+        let hours = DurationFormatter::new().smallest_unit(TimeUnit::Hours);
+        assert_eq!(hours.format(d), "2d 3h");
+        assert_eq!(hours.clone().rounding(Rounding::HalfUp).format(d), "2d 3h");
+        assert_eq!(hours.rounding(Rounding::Ceil).format(d), "2d 4h");
+
+        let days = DurationFormatter::new()
+            .smallest_unit(TimeUnit::Days)
+            .rounding(Rounding::HalfUp);
+        assert_eq!(
+            days.format(Duration::from_secs(6 * 86_400 + 13 * 3600)),
+            "1w 0d"
+        );
+    }
+
+    #[test]
+    fn test_long_style() {
+        let long = DurationFormatter::new().unit_style(UnitStyle::Long);
+        assert_eq!(
+            long.format(Duration::from_secs(3661)),
+            "1 hour 1 minute 1 second"
+        );
+        assert_eq!(long.format(Duration::from_secs(120)), "2 minutes");
+        assert_eq!(long.format(Duration::from_millis(1500)), "1.500 seconds");
+        assert_eq!(long.format(Duration::ZERO), "0 seconds");
+        let listed = long.separator(", ");
+        assert_eq!(
+            listed.format(Duration::from_secs(7 * 24 * 60 * 60)),
+            "1 week, 0 days, 0 hours, 0 minutes, 0 seconds"
+        );
+    }
+
+    #[test]
+    fn test_small_units() {
+        let f = DurationFormatter::new().small_units(true);
+        assert_eq!(f.format(Duration::from_nanos(1)), "1ns");
+        assert_eq!(f.format(Duration::from_nanos(999)), "999ns");
+        assert_eq!(f.format(Duration::from_nanos(1_000)), "1µs");
+        assert_eq!(f.format(Duration::from_nanos(999_999)), "999µs");
+        assert_eq!(f.format(Duration::from_millis(1)), "0.001s");
+        assert_eq!(f.format(Duration::ZERO), "0s");
+        assert_eq!(
+            DurationFormatter::new().format(Duration::from_micros(153)),
+            "0s"
+        );
+
+        let long = f.clone().unit_style(UnitStyle::Long);
+        assert_eq!(long.format(Duration::from_nanos(1)), "1 nanosecond");
+        assert_eq!(long.format(Duration::from_micros(153)), "153 microseconds");
+
+        let minutes = f.smallest_unit(TimeUnit::Minutes);
+        assert_eq!(minutes.format(Duration::from_micros(153)), "0m");
+    }
+
+    #[test]
+    fn test_max_units() {
+        let d = Duration::from_millis(5_445_123);
+        assert_eq!(format_duration_compact(d, 3), "1h 30m 45.123s");
+        assert_eq!(format_duration_compact(d, 2), "1h 30m");
+        assert_eq!(format_duration_compact(d, 0), "1h");
+        assert_eq!(format_duration_compact(Duration::from_secs(3645), 2), "1h");
+        assert_eq!(
+            format_duration_compact(Duration::from_secs(3645), 3),
+            "1h 0m 45s"
+        );
+        assert_eq!(
+            format_duration_compact(Duration::from_millis(3_600_500), 3),
+            "1h 0m 0.500s"
+        );
+        assert_eq!(
+            format_duration_compact(Duration::from_millis(1500), 1),
+            "1.500s"
+        );
+        assert_eq!(format_duration_compact(Duration::ZERO, 2), "0s");
+    }
+
+    #[test]
+    fn test_round() {
+        let two = DurationFormatter::new().max_units(2).round(true);
+        assert_eq!(two.format(Duration::from_secs(5445)), "1h 31m");
+        assert_eq!(two.format(Duration::from_secs(5415)), "1h 30m");
+        // Rounding carries into larger units.
+        assert_eq!(two.format(Duration::from_secs(7170)), "2h");
+        let one = DurationFormatter::new().max_units(1).round(true);
+        assert_eq!(one.format(Duration::from_secs(59 * 60 + 45)), "1h");
+
+        let whole = DurationFormatter::new().precision(0).round(true);
+        assert_eq!(whole.format(Duration::from_millis(1500)), "2s");
+        assert_eq!(whole.format(Duration::from_millis(119_600)), "2m");
+        let precise = DurationFormatter::precise().precision(0).round(true);
+        assert_eq!(precise.format(Duration::from_nanos(153_500)), "154µs");
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let whole = DurationFormatter::new().precision(0);
+        let d = Duration::from_millis(119_900);
+        assert_eq!(whole.format(d), "1m 59s");
+        assert_eq!(whole.clone().rounding(Rounding::HalfUp).format(d), "2m");
+        assert_eq!(whole.clone().rounding(Rounding::Ceil).format(d), "2m");
+
+        let d = Duration::from_millis(59_100);
+        assert_eq!(whole.clone().rounding(Rounding::HalfUp).format(d), "59s");
+        assert_eq!(whole.clone().rounding(Rounding::Ceil).format(d), "1m");
+        let ceil = whole.rounding(Rounding::Ceil);
+        assert_eq!(ceil.format(Duration::from_secs(60)), "1m");
+        assert_eq!(ceil.format(Duration::ZERO), "0s");
+
+        let minutes = DurationFormatter::new()
+            .smallest_unit(TimeUnit::Minutes)
+            .rounding(Rounding::Ceil);
+        assert_eq!(minutes.format(Duration::from_secs(3601)), "1h 1m");
+        assert_eq!(minutes.format(Duration::from_secs(3600)), "1h 0m");
+    }
+
+    #[test]
+    fn test_verbose() {
+        let d = Duration::from_secs(7 * 24 * 60 * 60 + 3600 + 1);
+        assert_eq!(format_duration_verbose(d), "1 week 1 hour 1 second");
+        assert_eq!(format_duration_verbose(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_duration_verbose(Duration::ZERO), "0 seconds");
+        assert_eq!(
+            format_duration_verbose(Duration::from_millis(60_500)),
+            "1 minute 0.500 seconds"
+        );
+
+        let short = DurationFormatter::new().hide_zero_units(true);
+        assert_eq!(short.format(Duration::from_secs(3605)), "1h 5s");
+        assert_eq!(short.format(Duration::from_secs(7200)), "2h");
+    }
+
+    #[test]
+    fn test_iso8601() {
+        let iso = |ms: u64| format_duration_iso8601(Duration::from_millis(ms));
+        assert_eq!(iso(45_500), "PT45.5S");
+        assert_eq!(iso(120_000), "PT2M");
+        assert_eq!(iso(3_600_250), "PT1H0.25S");
+        assert_eq!(iso(24 * 3_600_000), "P1D");
+        assert_eq!(iso(8 * 24 * 3_600_000 + 60_000), "P8DT1M");
+        assert_eq!(iso(7 * 24 * 3_600_000 + 1), "P7DT0.001S");
+        assert_eq!(
+            format_duration_iso8601(Duration::from_nanos(5)),
+            "PT0.000000005S"
+        );
+
+        let hours = DurationFormatter::new()
+            .unit_style(UnitStyle::Iso8601)
+            .largest_unit(TimeUnit::Hours)
+            .precision(0);
+        assert_eq!(
+            hours.format(Duration::from_millis(2 * 24 * 3_600_000 + 500)),
+            "PT48H"
+        );
+    }
+
+    #[test]
+    fn test_years_and_months() {
+        let year = 365 * 24 * 60 * 60;
+        let d = Duration::from_secs(year + 2 * (year / 12) + 8 * 24 * 60 * 60);
+        assert_eq!(DurationFormatter::new().format(d), format_duration(d));
+        let years = DurationFormatter::new().largest_unit(TimeUnit::Years);
+        assert_eq!(years.format(d), "1y 2mo 1w 1d 0h 0m 0s");
+        assert_eq!(years.clone().max_units(2).format(d), "1y 2mo");
+        assert_eq!(years.format(Duration::from_secs(3600)), "1h 0m 0s");
+        let months = DurationFormatter::verbose().largest_unit(TimeUnit::Months);
+        assert_eq!(months.format(Duration::from_secs(year)), "12 months");
+
+        let iso = DurationFormatter::new()
+            .unit_style(UnitStyle::Iso8601)
+            .largest_unit(TimeUnit::Years);
+        assert_eq!(iso.format(Duration::from_secs(year + year / 12)), "P1Y1M");
+        assert_eq!(iso.format(Duration::from_secs(year + 3600)), "P1YT1H");
+    }
+
+    #[test]
+    fn test_compact() {
+        let compact = DurationFormatter::new().unit_style(UnitStyle::Compact);
+        assert_eq!(
+            compact.format(Duration::from_millis(5_445_500)),
+            "1h30m45.5s"
+        );
+        assert_eq!(compact.format(Duration::from_millis(61_250)), "1m1.25s");
+        assert_eq!(compact.format(Duration::from_secs(120)), "2m");
+        assert_eq!(compact.format(Duration::ZERO), "0s");
+        let separated = compact.clone().separator(" ").locale(Locale::French);
+        assert_eq!(separated.format(Duration::from_secs(3_605)), "1h0min5s");
+        let precise = compact.small_units(true).precision(9);
+        assert_eq!(precise.format(Duration::from_micros(1500)), "0.0015s");
+        assert_eq!(precise.format(Duration::from_nanos(420)), "420ns");
+    }
+
+    #[test]
+    fn test_suffixes() {
+        let f = DurationFormatter::new()
+            .suffix(TimeUnit::Minutes, "x")
+            .suffix(TimeUnit::Minutes, "min")
+            .suffix(TimeUnit::Hours, " hrs")
+            .separator(" / ");
+        assert_eq!(f.format(Duration::from_secs(3725)), "1 hrs / 2min / 5s");
+        let compact = f.clone().unit_style(UnitStyle::Compact);
+        assert_eq!(compact.format(Duration::from_secs(120)), "2min");
+        // Spelled-out names are unaffected.
+        let long = f.unit_style(UnitStyle::Long);
+        assert_eq!(long.format(Duration::from_secs(120)), "2 minutes");
+    }
+
+    #[test]
+    fn test_clock() {
+        let clock = DurationFormatter::new().unit_style(UnitStyle::Clock);
+        assert_eq!(
+            clock.format(Duration::from_millis(5_445_250)),
+            "01:30:45.250"
+        );
+        assert_eq!(clock.format(Duration::ZERO), "00:00:00.000");
+        assert_eq!(
+            clock.format(Duration::from_secs(100 * 3600)),
+            "100:00:00.000"
+        );
+        let whole = clock.precision(0).round(true);
+        assert_eq!(whole.format(Duration::from_millis(59_500)), "00:01:00");
+
+        let days = DurationFormatter::new().unit_style(UnitStyle::DayClock);
+        let d = Duration::from_millis(2 * 86_400_000 + 5_445_250);
+        assert_eq!(days.format(d), "02:01:30:45.250");
+    }
+}
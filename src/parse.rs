@@ -0,0 +1,326 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::time::Duration;
+
+/// An error returned when a human-readable duration string cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input contained no duration components.
+    Empty,
+    /// A component's numeric part could not be read, e.g. `"x5s"` or `"1.2.3s"`.
+    InvalidNumber(String),
+    /// A component used a unit suffix that is not recognized, e.g. `"5q"`.
+    UnknownUnit(String),
+    /// A component had a number but no unit, e.g. `"42"`.
+    MissingUnit(String),
+    /// A unit appeared twice or after a smaller unit, e.g. `"5s 2m"`.
+    UnitOutOfOrder(String),
+    /// The value does not fit in a `Duration`.
+    Overflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty duration string"),
+            ParseError::InvalidNumber(token) => write!(f, "invalid number in \"{}\"", token),
+            ParseError::UnknownUnit(token) => write!(f, "unknown unit in \"{}\"", token),
+            ParseError::MissingUnit(token) => write!(f, "missing unit in \"{}\"", token),
+            ParseError::UnitOutOfOrder(token) => {
+                write!(f, "unit in \"{}\" is repeated or out of order", token)
+            }
+            ParseError::Overflow => write!(f, "duration is too large"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Units accepted by the parser, largest first, with their spellings and length in
+/// nanoseconds.
+const UNITS: [(&[&str], u64); 10] = [
+    (
+        &["y", "yr", "yrs", "year", "years"],
+        365 * 24 * 60 * 60 * NANOS_PER_SEC,
+    ),
+    (
+        &["mo", "mos", "month", "months"],
+        365 * 24 * 60 * 60 / 12 * NANOS_PER_SEC,
+    ),
+    (
+        &["w", "wk", "wks", "week", "weeks"],
+        7 * 24 * 60 * 60 * NANOS_PER_SEC,
+    ),
+    (&["d", "day", "days"], 24 * 60 * 60 * NANOS_PER_SEC),
+    (
+        &["h", "hr", "hrs", "hour", "hours"],
+        60 * 60 * NANOS_PER_SEC,
+    ),
+    (
+        &["m", "min", "mins", "minute", "minutes"],
+        60 * NANOS_PER_SEC,
+    ),
+    (&["s", "sec", "secs", "second", "seconds"], NANOS_PER_SEC),
+    (&["ms", "msec", "millisecond", "milliseconds"], 1_000_000),
+    (&["us", "µs", "usec", "microsecond", "microseconds"], 1_000),
+    (&["ns", "nsec", "nanosecond", "nanoseconds"], 1),
+];
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Parses a `<number><unit>` component into a duration and the index of its unit.
+fn parse_component(number: &str, unit: &str) -> Result<(Duration, usize), ParseError> {
+    let token = || format!("{}{}", number, unit);
+    let invalid = || ParseError::InvalidNumber(token());
+
+    let unit_index = UNITS
+        .iter()
+        .position(|(spellings, _)| spellings.contains(&unit))
+        .ok_or_else(|| ParseError::UnknownUnit(token()))?;
+    let unit_nanos = u128::from(UNITS[unit_index].1);
+
+    let (whole, fraction) = match number.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (number, ""),
+    };
+    if whole.is_empty() || fraction.contains('.') || (number.contains('.') && fraction.is_empty()) {
+        return Err(invalid());
+    }
+    if fraction.len() > 9 {
+        return Err(invalid());
+    }
+    let whole: u128 = whole.parse().map_err(|_| ParseError::Overflow)?;
+    let fraction_value: u128 = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse().map_err(|_| invalid())?
+    };
+
+    let nanos = whole
+        .checked_mul(unit_nanos)
+        .ok_or(ParseError::Overflow)?
+        .checked_add(fraction_value * unit_nanos / 10u128.pow(fraction.len() as u32))
+        .ok_or(ParseError::Overflow)?;
+    let secs =
+        u64::try_from(nanos / u128::from(NANOS_PER_SEC)).map_err(|_| ParseError::Overflow)?;
+    let subsec = (nanos % u128::from(NANOS_PER_SEC)) as u32;
+    Ok((Duration::new(secs, subsec), unit_index))
+}
+
+/// Parses a human-readable duration string into a `Duration`.
+///
+/// Accepts everything this crate's formatters write in the default short and long styles,
+/// plus common variants: components may be run together (`"2d4h"`), separated by spaces or
+/// commas, written with a space before the unit (`"2 hours"`) and carry a fraction in any
+/// unit (`"1.5h"`). Units run from 365-day years and approximate months to nanoseconds,
+/// each under its abbreviation (`y`, `mo`, `w`, `d`, `h`, `m`, `s`, `ms`, `us`/`µs`, `ns`) or
+/// its English name, and must appear largest first.
+///
+/// # Arguments
+///
+/// * `input` - A string such as `"1h 30m 45.5s"`, `"90s"`, `"1.5h"` or `"2d4h"`.
+///
+/// # Returns
+///
+/// The parsed `Duration`, or a `ParseError` describing the first offending component.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] if the input is empty, a component is malformed, units repeat
+/// or come out of order, or the total doesn't fit in a `Duration`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::parse_duration;
+///
+/// assert_eq!(parse_duration("1h 30m 45.5s").unwrap(), Duration::from_millis(5_445_500));
+/// assert_eq!(parse_duration("1.5h").unwrap(), Duration::from_secs(5400));
+/// assert_eq!(parse_duration("2d4h").unwrap(), Duration::from_secs(2 * 86_400 + 4 * 3600));
+/// assert_eq!(parse_duration("2 minutes 5 seconds").unwrap(), Duration::from_secs(125));
+/// assert!(parse_duration("soon").is_err());
+/// ```
+pub fn parse_duration(input: &str) -> Result<Duration, ParseError> {
+    let mut total = Duration::ZERO;
+    let mut next_unit = 0;
+    let mut rest = input.trim_start_matches(is_separator);
+
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, after) = rest.split_at(number_len);
+        if number.is_empty() {
+            let token = rest.split(is_separator).next().unwrap_or(rest);
+            return Err(ParseError::InvalidNumber(token.to_string()));
+        }
+        let after = after.trim_start_matches(char::is_whitespace);
+        let unit_len = after
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(after.len());
+        if unit_len == 0 {
+            let token = rest.split(is_separator).next().unwrap_or(rest);
+            return Err(ParseError::MissingUnit(token.to_string()));
+        }
+        let (unit, after) = after.split_at(unit_len);
+
+        let (value, unit_index) = parse_component(number, unit)?;
+        if unit_index < next_unit {
+            return Err(ParseError::UnitOutOfOrder(format!("{}{}", number, unit)));
+        }
+        next_unit = unit_index + 1;
+        total = total.checked_add(value).ok_or(ParseError::Overflow)?;
+        rest = after.trim_start_matches(is_separator);
+    }
+
+    if next_unit == 0 {
+        return Err(ParseError::Empty);
+    }
+    Ok(total)
+}
+
+/// Returns `true` for characters allowed between components.
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == ','
+}
+
+/// Converts a human-readable duration string into a `std::time::Duration`.
+///
+/// This is the recommended one-call way to read durations from configuration files,
+/// environment variables or command-line flags. It understands everything
+/// [`format_duration`](crate::format_duration) produces, so formatted values round-trip.
+/// It is another name for [`parse_duration`].
+///
+/// # Arguments
+///
+/// * `input` - A string such as `"2h 30m"`, `"90s"` or `"1w 2d 3h 4m 5.006s"`.
+///
+/// # Returns
+///
+/// The parsed `Duration`, or a `ParseError` describing the first offending component.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::duration_from_human;
+///
+/// assert_eq!(duration_from_human("2h 30m").unwrap(), Duration::from_secs(9000));
+/// assert_eq!(duration_from_human("1.500s").unwrap(), Duration::from_millis(1500));
+/// assert!(duration_from_human("soon").is_err());
+/// ```
+pub fn duration_from_human(input: &str) -> Result<Duration, ParseError> {
+    parse_duration(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format_duration, format_duration_verbose, DurationFormatter};
+
+    #[test]
+    fn test_parse_duration() {
+        let test_cases = [
+            ("5s", Duration::from_secs(5)),
+            ("0.500s", Duration::from_millis(500)),
+            ("2h 30m", Duration::from_secs(9000)),
+            ("  1m   30.5s ", Duration::from_millis(90500)),
+            ("1w 2d 3h 4m 5.006s", Duration::from_millis(788_645_006)),
+            ("0.000000001s", Duration::from_nanos(1)),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(parse_duration(input), Ok(expected), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for secs in [0, 1, 59, 60, 61, 3599, 3600, 90061, 1_209_600] {
+            let duration = Duration::from_millis(secs * 1000 + 6);
+            assert_eq!(parse_duration(&format_duration(duration)), Ok(duration));
+            assert_eq!(
+                parse_duration(&format_duration_verbose(duration)),
+                Ok(duration)
+            );
+        }
+        let precise = DurationFormatter::precise();
+        for nanos in [1, 999, 153_000, 1_234_567_891] {
+            let duration = Duration::from_nanos(nanos);
+            assert_eq!(parse_duration(&precise.format(duration)), Ok(duration));
+        }
+    }
+
+    #[test]
+    fn test_variants() {
+        let test_cases = [
+            ("90s", Duration::from_secs(90)),
+            ("1.5h", Duration::from_secs(5400)),
+            ("2d4h", Duration::from_secs(2 * 86_400 + 4 * 3600)),
+            ("1h30m", Duration::from_secs(5400)),
+            ("0.25d", Duration::from_secs(6 * 3600)),
+            ("2 hours, 5 mins", Duration::from_secs(7500)),
+            ("1 hour 1 minute 1 second", Duration::from_secs(3661)),
+            ("1s 500ms", Duration::from_millis(1500)),
+            ("153µs", Duration::from_micros(153)),
+            ("1.5us 420ns", Duration::from_nanos(1920)),
+            (
+                "1y 2mo",
+                Duration::from_secs(365 * 86_400 + 365 * 86_400 / 6),
+            ),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(parse_duration(input), Ok(expected), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_duration(""), Err(ParseError::Empty));
+        assert_eq!(parse_duration("   "), Err(ParseError::Empty));
+        assert_eq!(
+            parse_duration("42"),
+            Err(ParseError::MissingUnit("42".into()))
+        );
+        assert_eq!(
+            parse_duration("5q"),
+            Err(ParseError::UnknownUnit("5q".into()))
+        );
+        assert_eq!(
+            parse_duration("s"),
+            Err(ParseError::InvalidNumber("s".into()))
+        );
+        assert_eq!(
+            parse_duration("1.5"),
+            Err(ParseError::MissingUnit("1.5".into()))
+        );
+        assert_eq!(
+            parse_duration("1.s"),
+            Err(ParseError::InvalidNumber("1.s".into()))
+        );
+        assert_eq!(
+            parse_duration("x5s"),
+            Err(ParseError::InvalidNumber("x5s".into()))
+        );
+        assert_eq!(
+            parse_duration("1.2.3s"),
+            Err(ParseError::InvalidNumber("1.2.3s".into()))
+        );
+        assert_eq!(
+            parse_duration("5s 2m"),
+            Err(ParseError::UnitOutOfOrder("2m".into()))
+        );
+        assert_eq!(
+            parse_duration("1m 1m"),
+            Err(ParseError::UnitOutOfOrder("1m".into()))
+        );
+        assert_eq!(
+            parse_duration("99999999999999999999s"),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(parse_duration("30500568904944w"), Err(ParseError::Overflow));
+    }
+}
@@ -0,0 +1,132 @@
+//! Support code for this crate's macros. Not a public API.
+
+use std::time::Instant;
+
+use crate::sink;
+
+/// Reports its label and elapsed time to the global [`sink`] when dropped; the expansion of
+/// `#[timed]`.
+pub struct TimedGuard {
+    label: &'static str,
+    start: Instant,
+}
+
+impl TimedGuard {
+    pub fn new(label: &'static str) -> Self {
+        TimedGuard {
+            label,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for TimedGuard {
+    fn drop(&mut self) {
+        sink::record(self.label, self.start.elapsed());
+    }
+}
+
+/// Reports a block's elapsed time with its source location to the global [`sink`] when
+/// dropped; the expansion of [`time!`](crate::time).
+pub struct BlockGuard<'a> {
+    label: &'a str,
+    file: &'static str,
+    line: u32,
+    start: Instant,
+}
+
+impl<'a> BlockGuard<'a> {
+    pub fn new(label: &'a str, file: &'static str, line: u32) -> Self {
+        BlockGuard {
+            label,
+            file,
+            line,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for BlockGuard<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        sink::record(&block_label(self.label, self.file, self.line), elapsed);
+    }
+}
+
+/// Formats `"<label> (<file>:<line>)"`, the label a block is reported under.
+fn block_label(label: &str, file: &str, line: u32) -> String {
+    format!("{} ({}:{})", label, file, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[cfg(feature = "macros")]
+    use crate::timed;
+
+    #[test]
+    fn test_time_evaluates_to_block() {
+        let label = String::from("sum");
+        let sum = crate::time!(&label, { (1..=10u32).sum::<u32>() });
+        assert_eq!(sum, 55);
+    }
+
+    #[test]
+    fn test_time_reports_on_early_exit() {
+        fn parse(s: &str) -> Result<u32, std::num::ParseIntError> {
+            let n = crate::time!("parse", { s.parse::<u32>()? });
+            Ok(n + 1)
+        }
+        assert_eq!(parse("41"), Ok(42));
+        assert!(parse("x").is_err());
+    }
+
+    #[test]
+    fn test_block_report() {
+        let sink = crate::sink::WriterSink::new(Vec::new());
+        let label = block_label("load config", "src/main.rs", 42);
+        crate::sink::MeasurementSink::record(&sink, &label, Duration::from_millis(1300));
+        assert_eq!(
+            String::from_utf8(sink.into_inner()).unwrap(),
+            "load config (src/main.rs:42): 1.300s\n"
+        );
+    }
+
+    #[cfg(feature = "macros")]
+    #[timed]
+    fn parse_number(s: &str) -> Result<u32, std::num::ParseIntError> {
+        if s.is_empty() {
+            return Ok(0);
+        }
+        let n: u32 = s.parse()?;
+        Ok(n * 2)
+    }
+
+    #[cfg(feature = "macros")]
+    #[timed("async lookup")]
+    async fn lookup(key: u32) -> u32 {
+        key + 1
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn test_timed_keeps_behavior() {
+        assert_eq!(parse_number("21"), Ok(42));
+        assert_eq!(parse_number(""), Ok(0));
+        assert!(parse_number("x").is_err());
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn test_timed_async() {
+        use std::future::Future;
+        use std::pin::pin;
+        use std::task::{Context, Poll, Waker};
+
+        let fut = pin!(lookup(1));
+        let poll = fut.poll(&mut Context::from_waker(Waker::noop()));
+        assert_eq!(poll, Poll::Ready(2));
+    }
+}
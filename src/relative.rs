@@ -0,0 +1,429 @@
+use alloc::format;
+use alloc::string::String;
+use core::time::Duration;
+
+use crate::i18n::{Locale, UnitNames};
+use crate::TimeUnit;
+
+/// Phrases durations relative to now, such as `"5 minutes ago"` or `"in 2 weeks"`, or
+/// approximately, such as `"about 2 hours"`, for user interfaces.
+///
+/// Only the largest whole unit is shown, truncated: 3 days and 20 hours is `"3 days
+/// ago"`. Anything shorter than the [`just_now`](RelativeFormatter::just_now) threshold,
+/// or than one unit of the [`granularity`](RelativeFormatter::granularity), reads
+/// `"just now"`, or `"in a moment"` for the future.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::i18n::Locale;
+/// use elapsed_time::{RelativeFormatter, TimeUnit};
+///
+/// let relative = RelativeFormatter::new();
+/// assert_eq!(relative.ago(Duration::from_secs(3)), "just now");
+/// assert_eq!(relative.ago(Duration::from_secs(5 * 60 + 20)), "5 minutes ago");
+/// assert_eq!(relative.from_now(Duration::from_secs(45)), "in 45 seconds");
+/// assert_eq!(relative.approximate(Duration::from_secs(100 * 60)), "about 2 hours");
+///
+/// let coarse = RelativeFormatter::new().granularity(TimeUnit::Hours);
+/// assert_eq!(coarse.ago(Duration::from_secs(50 * 60)), "just now");
+/// assert_eq!(coarse.ago(Duration::from_secs(3 * 24 * 3600)), "3 days ago");
+///
+/// let german = RelativeFormatter::new().locale(Locale::German);
+/// assert_eq!(german.ago(Duration::from_secs(2 * 24 * 3600)), "vor 2 Tagen");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelativeFormatter {
+    granularity: TimeUnit,
+    largest_unit: TimeUnit,
+    just_now: Duration,
+    locale: Locale,
+}
+
+impl Default for RelativeFormatter {
+    fn default() -> Self {
+        RelativeFormatter {
+            granularity: TimeUnit::Seconds,
+            largest_unit: TimeUnit::Years,
+            just_now: Duration::from_secs(10),
+            locale: Locale::English,
+        }
+    }
+}
+
+impl RelativeFormatter {
+    /// Creates a formatter with the default settings: seconds up to years, and `"just
+    /// now"` below 10 seconds.
+    pub fn new() -> Self {
+        RelativeFormatter::default()
+    }
+
+    /// Sets the smallest unit shown. Durations shorter than one of it read `"just now"`.
+    ///
+    /// Clamped to the largest unit.
+    pub fn granularity(mut self, unit: TimeUnit) -> Self {
+        self.granularity = unit;
+        self
+    }
+
+    /// Sets the largest unit shown, so that with [`TimeUnit::Days`], 90 days is `"90 days
+    /// ago"` rather than `"2 months ago"`.
+    pub fn largest_unit(mut self, unit: TimeUnit) -> Self {
+        self.largest_unit = unit;
+        self
+    }
+
+    /// Sets how close something must be to read `"just now"` or `"in a moment"`, and how
+    /// short it must be to read `"a few seconds"`; 10 seconds by default.
+    pub fn just_now(mut self, threshold: Duration) -> Self {
+        self.just_now = threshold;
+        self
+    }
+
+    /// Sets the language of the phrases and unit names.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Phrases `duration` as time in the past, such as `"3 days ago"`.
+    pub fn ago(&self, duration: Duration) -> String {
+        match self.amount(duration) {
+            Some(amount) => match self.locale {
+                Locale::English => format!("{} ago", amount),
+                Locale::German => format!("vor {}", amount),
+                Locale::French => format!("il y a {}", amount),
+                Locale::Spanish => format!("hace {}", amount),
+            },
+            None => String::from(match self.locale {
+                Locale::English => "just now",
+                Locale::German => "gerade eben",
+                Locale::French => "à l'instant",
+                Locale::Spanish => "ahora mismo",
+            }),
+        }
+    }
+
+    /// Phrases `duration` as time in the future, such as `"in 2 weeks"`, for countdowns
+    /// and retry notices.
+    pub fn from_now(&self, duration: Duration) -> String {
+        match self.amount(duration) {
+            Some(amount) => match self.locale {
+                Locale::English | Locale::German => format!("in {}", amount),
+                Locale::French => format!("dans {}", amount),
+                Locale::Spanish => format!("en {}", amount),
+            },
+            None => String::from(match self.locale {
+                Locale::English => "in a moment",
+                Locale::German => "gleich",
+                Locale::French => "dans un instant",
+                Locale::Spanish => "en un momento",
+            }),
+        }
+    }
+
+    /// Phrases `duration` approximately, rounded to the nearest whole unit: `"about 2
+    /// hours"`, `"under a minute"` or `"a few seconds"`.
+    ///
+    /// Where precise output is overkill, as in user-facing summaries. Durations shorter
+    /// than the [`just_now`](RelativeFormatter::just_now) threshold are `"a few seconds"`
+    /// and those shorter than one unit of the
+    /// [`granularity`](RelativeFormatter::granularity), but at least a minute, read `"under
+    /// a minute"`, `"under an hour"` and so on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::{RelativeFormatter, TimeUnit};
+    ///
+    /// let fuzzy = RelativeFormatter::new();
+    /// assert_eq!(fuzzy.approximate(Duration::from_secs(3)), "a few seconds");
+    /// assert_eq!(fuzzy.approximate(Duration::from_secs(40)), "under a minute");
+    /// assert_eq!(fuzzy.approximate(Duration::from_secs(59 * 60 + 40)), "about 1 hour");
+    ///
+    /// let days = RelativeFormatter::new().granularity(TimeUnit::Days);
+    /// assert_eq!(days.approximate(Duration::from_secs(5 * 3600)), "under a day");
+    /// ```
+    pub fn approximate(&self, duration: Duration) -> String {
+        let smallest = self
+            .granularity
+            .max(TimeUnit::Minutes)
+            .min(self.largest_unit);
+        if duration < self.just_now {
+            return String::from(match self.locale {
+                Locale::English => "a few seconds",
+                Locale::German => "ein paar Sekunden",
+                Locale::French => "quelques secondes",
+                Locale::Spanish => "unos segundos",
+            });
+        }
+        let secs = duration.as_secs();
+        if secs < smallest.secs() {
+            return self.under(smallest);
+        }
+        let in_range = |unit: &TimeUnit| *unit <= self.largest_unit && *unit >= smallest;
+        let rounded = |unit: TimeUnit| secs.saturating_add(unit.secs() / 2) / unit.secs();
+        let mut unit = TimeUnit::DESCENDING
+            .into_iter()
+            .filter(in_range)
+            .find(|unit| secs >= unit.secs())
+            .unwrap_or(smallest);
+        // Rounding up may reach the next unit: 59m 40s is about 1 hour, not 60 minutes.
+        let larger = TimeUnit::DESCENDING
+            .into_iter()
+            .rev()
+            .filter(in_range)
+            .find(|larger| *larger > unit);
+        if let Some(larger) = larger {
+            if rounded(unit).saturating_mul(unit.secs()) >= larger.secs() {
+                unit = larger;
+            }
+        }
+        let value = rounded(unit);
+        let amount = format!(
+            "{} {}",
+            value,
+            self.locale.long(unit, self.locale.is_plural(value, false))
+        );
+        match self.locale {
+            Locale::English => format!("about {}", amount),
+            Locale::German => format!("etwa {}", amount),
+            Locale::French => format!("environ {}", amount),
+            Locale::Spanish => format!("alrededor de {}", amount),
+        }
+    }
+
+    /// Returns the phrase for less than one `unit`, such as `"under an hour"`.
+    fn under(&self, unit: TimeUnit) -> String {
+        use TimeUnit::*;
+        let name = self.locale.long(unit, false);
+        let feminine = match self.locale {
+            Locale::English => false,
+            Locale::German | Locale::French => matches!(unit, Seconds | Minutes | Hours | Weeks),
+            Locale::Spanish => matches!(unit, Hours | Weeks),
+        };
+        match (self.locale, feminine) {
+            (Locale::English, _) if unit == Hours => format!("under an {}", name),
+            (Locale::English, _) => format!("under a {}", name),
+            (Locale::German, true) => format!("unter einer {}", name),
+            (Locale::German, false) => format!("unter einem {}", name),
+            (Locale::French, true) => format!("moins d'une {}", name),
+            (Locale::French, false) => format!("moins d'un {}", name),
+            (Locale::Spanish, true) => format!("menos de una {}", name),
+            (Locale::Spanish, false) => format!("menos de un {}", name),
+        }
+    }
+
+    /// Returns the count and name of the unit that `duration` is phrased in, such as `"5
+    /// minutes"`, or `None` if it is too short to count.
+    fn amount(&self, duration: Duration) -> Option<String> {
+        let smallest = self.granularity.min(self.largest_unit);
+        if duration < self.just_now || duration.as_secs() < smallest.secs() {
+            return None;
+        }
+        let secs = duration.as_secs();
+        let unit = TimeUnit::DESCENDING
+            .into_iter()
+            .filter(|unit| *unit <= self.largest_unit && *unit >= smallest)
+            .find(|unit| secs >= unit.secs())
+            .unwrap_or(smallest);
+        let value = secs / unit.secs();
+        Some(format!("{} {}", value, self.unit_name(unit, value)))
+    }
+
+    /// Returns the name of `unit` for a count of `value`.
+    fn unit_name(&self, unit: TimeUnit, value: u64) -> &str {
+        let plural = self.locale.is_plural(value, false);
+        // German relative phrases take the dative plural: "vor 3 Tagen", "in 3 Tagen".
+        match (self.locale, unit, plural) {
+            (Locale::German, TimeUnit::Years, true) => "Jahren",
+            (Locale::German, TimeUnit::Months, true) => "Monaten",
+            (Locale::German, TimeUnit::Days, true) => "Tagen",
+            _ => self.locale.long(unit, plural),
+        }
+    }
+}
+
+/// Phrases `duration` as time in the past with the default [`RelativeFormatter`], such as
+/// `"5 minutes ago"`, or `"just now"` below 10 seconds.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_ago;
+///
+/// assert_eq!(format_ago(Duration::from_secs(1)), "just now");
+/// assert_eq!(format_ago(Duration::from_secs(90)), "1 minute ago");
+/// assert_eq!(format_ago(Duration::from_secs(3 * 24 * 3600 + 7200)), "3 days ago");
+/// ```
+pub fn format_ago(duration: Duration) -> String {
+    RelativeFormatter::new().ago(duration)
+}
+
+/// Phrases `duration` as time in the future with the default [`RelativeFormatter`], such
+/// as `"in 45 seconds"`, or `"in a moment"` below 10 seconds.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_in;
+///
+/// assert_eq!(format_in(Duration::from_secs(45)), "in 45 seconds");
+/// assert_eq!(format_in(Duration::from_secs(15 * 24 * 3600)), "in 2 weeks");
+/// println!("retrying {}", format_in(Duration::from_secs(2))); // "retrying in a moment"
+/// ```
+pub fn format_in(duration: Duration) -> String {
+    RelativeFormatter::new().from_now(duration)
+}
+
+/// Phrases `duration` approximately with the default [`RelativeFormatter`]: `"about 2
+/// hours"`, `"under a minute"` or `"a few seconds"`.
+///
+/// See [`RelativeFormatter::approximate`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_approximate;
+///
+/// assert_eq!(format_approximate(Duration::from_secs(5)), "a few seconds");
+/// assert_eq!(format_approximate(Duration::from_secs(2 * 3600 + 1000)), "about 2 hours");
+/// ```
+pub fn format_approximate(duration: Duration) -> String {
+    RelativeFormatter::new().approximate(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60;
+    const DAY: u64 = 24 * 60 * MINUTE;
+
+    #[test]
+    fn test_ago() {
+        let cases = [
+            (0, "just now"),
+            (9, "just now"),
+            (10, "10 seconds ago"),
+            (59, "59 seconds ago"),
+            (MINUTE, "1 minute ago"),
+            (150 * MINUTE, "2 hours ago"),
+            (DAY + 1, "1 day ago"),
+            (13 * DAY, "1 week ago"),
+            (45 * DAY, "1 month ago"),
+            (800 * DAY, "2 years ago"),
+        ];
+        for (secs, expected) in cases {
+            assert_eq!(format_ago(Duration::from_secs(secs)), expected, "{}s", secs);
+        }
+    }
+
+    #[test]
+    fn test_from_now() {
+        assert_eq!(format_in(Duration::from_secs(5)), "in a moment");
+        assert_eq!(format_in(Duration::from_secs(45)), "in 45 seconds");
+        assert_eq!(format_in(Duration::from_secs(DAY)), "in 1 day");
+        let hours = RelativeFormatter::new().granularity(TimeUnit::Hours);
+        assert_eq!(
+            hours.from_now(Duration::from_secs(30 * MINUTE)),
+            "in a moment"
+        );
+        assert_eq!(hours.ago(Duration::from_secs(30 * MINUTE)), "just now");
+    }
+
+    #[test]
+    fn test_granularity_and_units() {
+        let days = RelativeFormatter::new()
+            .granularity(TimeUnit::Days)
+            .largest_unit(TimeUnit::Days);
+        assert_eq!(days.ago(Duration::from_secs(20 * 3600)), "just now");
+        assert_eq!(days.ago(Duration::from_secs(90 * DAY)), "90 days ago");
+
+        let strict = RelativeFormatter::new().just_now(Duration::ZERO);
+        assert_eq!(strict.ago(Duration::from_secs(1)), "1 second ago");
+        assert_eq!(strict.ago(Duration::from_millis(500)), "just now");
+
+        let clamped = RelativeFormatter::new()
+            .granularity(TimeUnit::Years)
+            .largest_unit(TimeUnit::Hours);
+        assert_eq!(clamped.ago(Duration::from_secs(2 * DAY)), "48 hours ago");
+    }
+
+    #[test]
+    fn test_locales() {
+        let d = Duration::from_secs(3 * DAY);
+        let ago = |locale| RelativeFormatter::new().locale(locale).ago(d);
+        assert_eq!(ago(Locale::German), "vor 3 Tagen");
+        assert_eq!(ago(Locale::French), "il y a 3 jours");
+        assert_eq!(ago(Locale::Spanish), "hace 3 días");
+        let german = RelativeFormatter::new().locale(Locale::German);
+        assert_eq!(german.ago(Duration::from_secs(3600)), "vor 1 Stunde");
+        assert_eq!(german.ago(Duration::ZERO), "gerade eben");
+        assert_eq!(german.from_now(d), "in 3 Tagen");
+
+        let from_now = |locale| RelativeFormatter::new().locale(locale).from_now(d);
+        assert_eq!(from_now(Locale::French), "dans 3 jours");
+        assert_eq!(from_now(Locale::Spanish), "en 3 días");
+        let french = RelativeFormatter::new().locale(Locale::French);
+        assert_eq!(french.from_now(Duration::ZERO), "dans un instant");
+    }
+
+    #[test]
+    fn test_approximate() {
+        let cases = [
+            (0, "a few seconds"),
+            (10, "under a minute"),
+            (MINUTE, "about 1 minute"),
+            (90, "about 2 minutes"),
+            (44 * MINUTE, "about 44 minutes"),
+            (59 * MINUTE + 30, "about 1 hour"),
+            (100 * MINUTE, "about 2 hours"),
+            (6 * DAY + 13 * 3600, "about 1 week"),
+            (10 * DAY, "about 1 week"),
+            (11 * DAY, "about 2 weeks"),
+            (400 * DAY, "about 1 year"),
+        ];
+        for (secs, expected) in cases {
+            let d = Duration::from_secs(secs);
+            assert_eq!(format_approximate(d), expected, "{}s", secs);
+        }
+        assert_eq!(
+            format_approximate(Duration::MAX),
+            "about 584942417355 years"
+        );
+    }
+
+    #[test]
+    fn test_approximate_thresholds_and_locales() {
+        let hours = RelativeFormatter::new()
+            .granularity(TimeUnit::Hours)
+            .just_now(Duration::from_secs(30));
+        assert_eq!(hours.approximate(Duration::from_secs(20)), "a few seconds");
+        assert_eq!(hours.approximate(Duration::from_secs(40)), "under an hour");
+        let seconds = RelativeFormatter::new().largest_unit(TimeUnit::Seconds);
+        assert_eq!(
+            seconds.approximate(Duration::from_secs(90)),
+            "about 90 seconds"
+        );
+
+        let d = Duration::from_secs(40);
+        let under = |locale| RelativeFormatter::new().locale(locale).approximate(d);
+        assert_eq!(under(Locale::German), "unter einer Minute");
+        assert_eq!(under(Locale::French), "moins d'une minute");
+        assert_eq!(under(Locale::Spanish), "menos de un minuto");
+        let days = RelativeFormatter::new()
+            .granularity(TimeUnit::Days)
+            .locale(Locale::German);
+        assert_eq!(days.approximate(d), "unter einem Tag");
+        assert_eq!(
+            days.approximate(Duration::from_secs(3 * DAY)),
+            "etwa 3 Tage"
+        );
+    }
+}
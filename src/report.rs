@@ -0,0 +1,188 @@
+//! Timing summaries as GitHub-flavored markdown tables, for pasting into pull requests and
+//! issues.
+//!
+//! [`markdown`] renders the [`timers`](crate::timers) registry, and [`markdown_stats`] a set
+//! of labelled benchmark results, both with the columns label, calls, total, mean and p95.
+//! The registry table adds an errors column counting failed runs.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use elapsed_time::{report, timers};
+//!
+//! timers::record("report::db_query", Duration::from_millis(120));
+//! timers::record("report::db_query", Duration::from_millis(80));
+//! println!("{}", report::markdown());
+//! // | label            | calls |  total |   mean |    p95 | errors |
+//! // | ---------------- | ----: | -----: | -----: | -----: | -----: |
+//! // | report::db_query |     2 | 0.200s | 0.100s | 0.120s |      0 |
+//! ```
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::stats::Stats;
+use crate::{format_duration, timers};
+
+const HEADER: [&str; 5] = ["label", "calls", "total", "mean", "p95"];
+const REGISTRY_HEADER: [&str; 6] = ["label", "calls", "total", "mean", "p95", "errors"];
+
+/// Renders every timer in the registry as a markdown table, ordered by total time, longest
+/// first.
+///
+/// The calls, total, mean and p95 columns describe the successful runs, with p95
+/// estimated within 1%; the errors column counts the failed ones. With no timers recorded
+/// only the header is rendered.
+pub fn markdown() -> String {
+    let rows = timers::report_rows(Some(0.95))
+        .into_iter()
+        .map(|row| {
+            let [total, mean, p95] = match (row.ok, row.quantile) {
+                (Some(stats), Some(p95)) => [stats.total, stats.mean(), p95].map(format_duration),
+                _ => ["-"; 3].map(str::to_string),
+            };
+            [
+                row.label,
+                row.ok.map_or(0, |stats| stats.count).to_string(),
+                total,
+                mean,
+                p95,
+                row.errors.map_or(0, |errors| errors.count).to_string(),
+            ]
+        })
+        .collect();
+    render(REGISTRY_HEADER, rows)
+}
+
+/// Renders labelled benchmark results as a markdown table, in the given order.
+///
+/// Each [`Stats`] contributes its sample count as the calls, the sum of its samples as the
+/// total, and its mean and exact 95th percentile.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::report::markdown_stats;
+/// use elapsed_time::stats::measure_n;
+///
+/// let vec = measure_n(20, || (0..1_000u32).collect::<Vec<_>>());
+/// let sum = measure_n(20, || (0..1_000u32).sum::<u32>());
+/// println!("{}", markdown_stats([("collect", &vec), ("sum", &sum)]));
+/// ```
+pub fn markdown_stats<'a, I>(benchmarks: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, &'a Stats)>,
+{
+    let rows = benchmarks
+        .into_iter()
+        .map(|(label, stats)| {
+            let total = stats
+                .samples()
+                .iter()
+                .fold(Duration::ZERO, |total, sample| {
+                    total.saturating_add(*sample)
+                });
+            [
+                label.to_string(),
+                stats.len().to_string(),
+                format_duration(total),
+                format_duration(stats.mean()),
+                format_duration(stats.quantile(0.95)),
+            ]
+        })
+        .collect();
+    render(HEADER, rows)
+}
+
+/// Renders an aligned markdown table: the label column left-aligned, the rest right-aligned.
+fn render<const N: usize>(header: [&str; N], mut rows: Vec<[String; N]>) -> String {
+    for row in &mut rows {
+        row[0] = escape(&row[0]);
+    }
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header = header.map(str::to_string);
+    for (index, row) in std::iter::once(&header).chain(&rows).enumerate() {
+        out.push('|');
+        for (column, (cell, width)) in row.iter().zip(widths).enumerate() {
+            // Writing to a `String` cannot fail.
+            let _ = if column == 0 {
+                write!(out, " {:<width$} |", cell, width = width)
+            } else {
+                write!(out, " {:>width$} |", cell, width = width)
+            };
+        }
+        out.push('\n');
+        if index == 0 {
+            out.push('|');
+            for (column, width) in widths.into_iter().enumerate() {
+                let _ = if column == 0 {
+                    write!(out, " {} |", "-".repeat(width))
+                } else {
+                    write!(out, " {}: |", "-".repeat(width - 1))
+                };
+            }
+            out.push('\n');
+        }
+    }
+    out.pop();
+    out
+}
+
+/// Escapes the characters that would break a table cell.
+fn escape(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_stats() {
+        let fast = Stats::from_samples([100, 200, 300].map(Duration::from_millis)).unwrap();
+        let slow = Stats::from_samples([Duration::from_secs(90)]).unwrap();
+        assert_eq!(
+            markdown_stats([("fast", &fast), ("slow | cold", &slow)]),
+            "| label        | calls |  total |   mean |    p95 |\n\
+             | ------------ | ----: | -----: | -----: | -----: |\n\
+             | fast         |     3 | 0.600s | 0.200s | 0.290s |\n\
+             | slow \\| cold |     1 | 1m 30s | 1m 30s | 1m 30s |"
+        );
+    }
+
+    #[test]
+    fn test_registry_and_empty() {
+        timers::record("report::test_registry", Duration::from_millis(250));
+        timers::record_error("report::test_registry", Duration::from_secs(1));
+        timers::record_error("report::test_failed", Duration::from_secs(1));
+        let table = markdown();
+        // Other tests share the registry, so compare trimmed cells rather than padding.
+        let cells = |label: &str| -> Vec<String> {
+            let line = table
+                .lines()
+                .find(|line| line.starts_with(&format!("| {} ", label)))
+                .unwrap();
+            line.split('|')
+                .map(|cell| cell.trim().to_string())
+                .collect()
+        };
+        assert_eq!(cells("report::test_registry")[2..4], ["1", "0.250s"]);
+        assert_eq!(cells("report::test_registry")[6], "1");
+        assert_eq!(
+            cells("report::test_failed")[2..7],
+            ["0", "-", "-", "-", "1"]
+        );
+        assert_eq!(
+            markdown_stats([]),
+            "| label | calls | total | mean | p95 |\n| ----- | ----: | ----: | ---: | --: |"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use std::io;
+use std::time::Duration;
+
+use crate::format_duration;
+use crate::rng::SplitMix64;
+
+/// A bounded, uniformly random subset of a stream of measurements.
+///
+/// Every measurement added has the same chance of being kept, however long the stream,
+/// while memory stays fixed at `capacity` samples. Each sample keeps its position in the
+/// stream, so the subset can be plotted against time.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::Reservoir;
+///
+/// let mut reservoir = Reservoir::new(100);
+/// for ms in 0..10_000 {
+///     reservoir.add(Duration::from_millis(ms % 250));
+/// }
+///
+/// assert_eq!(reservoir.seen(), 10_000);
+/// assert_eq!(reservoir.samples().len(), 100);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reservoir {
+    capacity: usize,
+    samples: Vec<(u64, Duration)>,
+    seen: u64,
+    rng: SplitMix64,
+}
+
+impl Reservoir {
+    /// Creates a reservoir keeping at most `capacity` samples, randomly seeded.
+    pub fn new(capacity: usize) -> Self {
+        Reservoir::with_rng(capacity, SplitMix64::from_entropy())
+    }
+
+    /// Creates a reservoir whose choice of samples is determined by `seed`, for
+    /// reproducible reports and tests.
+    pub fn with_seed(capacity: usize, seed: u64) -> Self {
+        Reservoir::with_rng(capacity, SplitMix64::new(seed))
+    }
+
+    fn with_rng(capacity: usize, rng: SplitMix64) -> Self {
+        Reservoir {
+            capacity,
+            samples: Vec::with_capacity(capacity),
+            seen: 0,
+            rng,
+        }
+    }
+
+    /// Offers one measurement to the reservoir.
+    pub fn add(&mut self, duration: Duration) {
+        let index = self.seen;
+        self.seen += 1;
+        if self.samples.len() < self.capacity {
+            self.samples.push((index, duration));
+            return;
+        }
+        let slot = self.rng.below(self.seen) as usize;
+        if slot < self.capacity {
+            self.samples[slot] = (index, duration);
+        }
+    }
+
+    /// Returns the number of measurements offered so far.
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// Returns the maximum number of samples kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the kept samples as `(position in stream, duration)`, ordered by position.
+    pub fn samples(&self) -> Vec<(u64, Duration)> {
+        let mut samples = self.samples.clone();
+        samples.sort_unstable_by_key(|(index, _)| *index);
+        samples
+    }
+
+    /// Writes the kept samples as CSV, for a scatter plot in a spreadsheet.
+    ///
+    /// The header is `index,nanos,formatted`, followed by one row per sample ordered by
+    /// position in the stream, e.g. `42,1200000000,1.200s`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing to `out`.
+    pub fn write_csv<W: io::Write>(&self, mut out: W) -> io::Result<()> {
+        crate::csv::write_record(&mut out, &["index", "nanos", "formatted"])?;
+        for (index, duration) in self.samples() {
+            let (index, nanos) = (index.to_string(), duration.as_nanos().to_string());
+            crate::csv::write_record(&mut out, &[&index, &nanos, &format_duration(duration)])?;
+        }
+        Ok(())
+    }
+
+    /// Returns the reservoir as JSON: `seen`, `capacity`, then the `samples` ordered by
+    /// position, each as `{"index":42,"duration":{"nanos":1200000000,"formatted":"1.200s"}}`.
+    ///
+    /// Enabled with the `serde_json` feature.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> String {
+        use crate::json::duration_value;
+
+        let samples: Vec<_> = self
+            .samples()
+            .into_iter()
+            .map(|(index, duration)| {
+                serde_json::json!({ "index": index, "duration": duration_value(duration) })
+            })
+            .collect();
+        serde_json::json!({
+            "seen": self.seen,
+            "capacity": self.capacity,
+            "samples": samples,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_everything_below_capacity() {
+        let mut reservoir = Reservoir::with_seed(5, 1);
+        for ms in [3, 1, 2] {
+            reservoir.add(Duration::from_millis(ms));
+        }
+        let samples: Vec<_> = reservoir
+            .samples()
+            .into_iter()
+            .map(|(i, d)| (i, d.as_millis()))
+            .collect();
+        assert_eq!(samples, [(0, 3), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_bounded_and_reproducible() {
+        let fill = |seed| {
+            let mut reservoir = Reservoir::with_seed(10, seed);
+            for ms in 0..1000 {
+                reservoir.add(Duration::from_millis(ms));
+            }
+            reservoir
+        };
+        let reservoir = fill(42);
+        assert_eq!(reservoir.seen(), 1000);
+        assert_eq!(reservoir.samples().len(), 10);
+        assert_eq!(reservoir.samples(), fill(42).samples());
+        assert!(reservoir.samples().windows(2).all(|w| w[0].0 < w[1].0));
+        // Samples keep their duration alongside their position.
+        assert!(reservoir.samples().iter().all(|(i, d)| d.as_millis() == *i as u128));
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut reservoir = Reservoir::with_seed(5, 1);
+        for ms in [1500, 90_000] {
+            reservoir.add(Duration::from_millis(ms));
+        }
+        let mut csv = Vec::new();
+        reservoir.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "index,nanos,formatted\n0,1500000000,1.500s\n1,90000000000,1m 30s\n"
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_to_json() {
+        let mut reservoir = Reservoir::with_seed(5, 1);
+        for ms in [300, 100] {
+            reservoir.add(Duration::from_millis(ms));
+        }
+        let json: serde_json::Value = serde_json::from_str(&reservoir.to_json()).unwrap();
+        assert_eq!(json["seen"], 2);
+        assert_eq!(json["capacity"], 5);
+        assert_eq!(json["samples"][1]["index"], 1);
+        assert_eq!(json["samples"][1]["duration"]["nanos"], 100_000_000);
+        assert_eq!(json["samples"][0]["duration"]["formatted"], "0.300s");
+    }
+
+    #[test]
+    fn test_roughly_uniform() {
+        // Over many runs, late items are kept as often as early ones.
+        let (mut early, mut late) = (0, 0);
+        for seed in 0..200 {
+            let mut reservoir = Reservoir::with_seed(10, seed);
+            for ms in 0..100 {
+                reservoir.add(Duration::from_millis(ms));
+            }
+            for (index, _) in reservoir.samples() {
+                if index < 50 {
+                    early += 1;
+                } else {
+                    late += 1;
+                }
+            }
+        }
+        assert_eq!(early + late, 2000);
+        assert!((800..1200).contains(&early), "early={} late={}", early, late);
+    }
+}
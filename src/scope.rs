@@ -0,0 +1,835 @@
+//! Nested timed scopes that build a call tree automatically.
+//!
+//! Each thread keeps a stack of open scopes. Opening a scope with [`timed_scope!`] or
+//! [`ScopeGuard::enter`] makes it a child of whatever scope is currently open on the same
+//! thread, so nested calls form a tree without passing parent handles around. When an
+//! outermost scope closes, its finished tree is stored for the thread and can be collected
+//! with [`take_completed`]. At most [`MAX_COMPLETED`] trees are kept; once that many are
+//! waiting, the oldest is discarded for each new one and counted by [`dropped`].
+//!
+//! Async tasks can move between threads at every `.await`, so a thread's stack can't follow
+//! them. Wrap a task's future in [`instrument`] instead: the wrapper carries the task's own
+//! stack and installs it on whichever thread polls the task, so scopes opened inside it
+//! nest correctly. Guards are tied to the thread that opened them and are not `Send`; to
+//! time a step that spans an `.await`, wrap that step in its own [`instrument`] call.
+//!
+//! Work fanned out to other threads can still report into the tree: take a cloneable
+//! [`ScopeHandle`] from the open scope with [`current_handle`], move it to the workers, and
+//! open their scopes with [`ScopeHandle::enter`]. Their records become children of the
+//! originating scope when it closes.
+//!
+//! [`profile`] merges finished trees into a call-tree profile with call counts and self
+//! time, for a quick look at where a batch job spends its time.
+//!
+//! # Example
+//!
+//! ```
+//! use elapsed_time::{scope, timed_scope};
+//!
+//! fn load_config() {
+//!     timed_scope!("load_config");
+//! }
+//!
+//! fn handle_request() {
+//!     timed_scope!("handle_request");
+//!     load_config();
+//! }
+//!
+//! handle_request();
+//! let trees = scope::take_completed();
+//! assert_eq!(trees[0].label, "handle_request");
+//! assert_eq!(trees[0].children[0].label, "load_config");
+//! println!("{}", trees[0]);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// A finished scope: its label, how long it was open, and the scopes opened inside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeRecord {
+    /// The label the scope was opened with.
+    pub label: String,
+    /// How long the scope was open.
+    pub duration: Duration,
+    /// Scopes opened and closed inside this one, in the order they closed. Children
+    /// reported from other threads through a [`ScopeHandle`] follow the local ones.
+    pub children: Vec<ScopeRecord>,
+}
+
+impl ScopeRecord {
+    /// Returns the time spent in this scope outside any of its children.
+    ///
+    /// Children that ran concurrently on other threads can add up to more than the scope
+    /// itself; the self time is then zero.
+    pub fn self_time(&self) -> Duration {
+        let children: Duration = self.children.iter().map(|child| child.duration).sum();
+        self.duration.saturating_sub(children)
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}: {}", "", self.label, format_duration(self.duration), indent = depth * 2)?;
+        for child in &self.children {
+            writeln!(f)?;
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ScopeRecord {
+    /// Renders the tree with two spaces of indentation per level, e.g.
+    /// `"handle_request: 1.200s\n  load_config: 0.100s"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// A node of a call-tree profile: every scope with the same label under the same parent,
+/// merged into one.
+///
+/// Build one with [`profile`]. Its `Display` implementation renders an indented tree:
+///
+/// ```text
+/// batch: 10s total, 1s self, 1 call
+///   load: 6s total, 6s self, 3 calls
+///   save: 3s total, 3s self, 1 call
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileNode {
+    /// The label the merged scopes were opened with.
+    pub label: String,
+    /// How many scopes were merged into this node.
+    pub calls: u64,
+    /// The merged scopes' combined duration.
+    pub total: Duration,
+    /// The part of `total` spent outside any child scope.
+    pub self_time: Duration,
+    /// The merged children, in the order their labels first appeared.
+    pub children: Vec<ProfileNode>,
+}
+
+impl ProfileNode {
+    fn new(label: &str) -> Self {
+        ProfileNode {
+            label: label.to_string(),
+            calls: 0,
+            total: Duration::ZERO,
+            self_time: Duration::ZERO,
+            children: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, record: &ScopeRecord) {
+        self.calls += 1;
+        self.total += record.duration;
+        self.self_time += record.self_time();
+        merge_into(&mut self.children, &record.children);
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{}: {} total, {} self, {} {}",
+            "",
+            self.label,
+            format_duration(self.total),
+            format_duration(self.self_time),
+            self.calls,
+            if self.calls == 1 { "call" } else { "calls" },
+            indent = depth * 2
+        )?;
+        for child in &self.children {
+            writeln!(f)?;
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ProfileNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+fn merge_into(nodes: &mut Vec<ProfileNode>, records: &[ScopeRecord]) {
+    for record in records {
+        let index = match nodes.iter().position(|node| node.label == record.label) {
+            Some(index) => index,
+            None => {
+                nodes.push(ProfileNode::new(&record.label));
+                nodes.len() - 1
+            }
+        };
+        nodes[index].add(record);
+    }
+}
+
+/// Merges scope trees into a call-tree profile with per-node call counts, total time and
+/// self time.
+///
+/// Scopes with the same label under the same parent become one node, so a loop that
+/// opens `"load"` a thousand times shows up as one line with `1000 calls`.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{scope, timed_scope};
+///
+/// fn load() {
+///     timed_scope!("load");
+/// }
+///
+/// for _ in 0..3 {
+///     timed_scope!("batch");
+///     load();
+///     load();
+/// }
+///
+/// let profile = scope::profile(&scope::take_completed());
+/// assert_eq!((profile[0].label.as_str(), profile[0].calls), ("batch", 3));
+/// assert_eq!((profile[0].children[0].label.as_str(), profile[0].children[0].calls), ("load", 6));
+/// println!("{}", profile[0]);
+/// ```
+pub fn profile(records: &[ScopeRecord]) -> Vec<ProfileNode> {
+    let mut nodes = Vec::new();
+    merge_into(&mut nodes, records);
+    nodes
+}
+
+/// Records sent to an open scope from other threads.
+#[derive(Debug)]
+struct Inbox {
+    open: bool,
+    records: Vec<ScopeRecord>,
+}
+
+/// A scope that is still open on the current thread.
+struct Frame {
+    label: String,
+    start: Instant,
+    children: Vec<ScopeRecord>,
+    /// Created on demand when a handle to this scope is requested.
+    inbox: Option<Arc<Mutex<Inbox>>>,
+    /// Set when the scope was opened through a handle; its record goes there when it closes.
+    parent: Option<ScopeHandle>,
+}
+
+impl Frame {
+    fn new(label: String, parent: Option<ScopeHandle>) -> Self {
+        Frame {
+            label,
+            start: Instant::now(),
+            children: Vec::new(),
+            inbox: None,
+            parent,
+        }
+    }
+
+    fn handle(&mut self) -> ScopeHandle {
+        let inbox = self.inbox.get_or_insert_with(|| {
+            Arc::new(Mutex::new(Inbox {
+                open: true,
+                records: Vec::new(),
+            }))
+        });
+        ScopeHandle {
+            inbox: Arc::clone(inbox),
+        }
+    }
+
+    /// Closes the scope. Returns its record unless it was delivered to a remote parent.
+    fn close(mut self, now: Instant) -> Option<ScopeRecord> {
+        if let Some(inbox) = self.inbox.take() {
+            let mut inbox = inbox.lock().unwrap_or_else(|e| e.into_inner());
+            inbox.open = false;
+            self.children.append(&mut inbox.records);
+        }
+        let record = ScopeRecord {
+            label: self.label,
+            duration: now.saturating_duration_since(self.start),
+            children: self.children,
+        };
+        crate::sink::observe(&record.label, record.duration);
+        match self.parent {
+            Some(parent) => parent.deliver(record),
+            None => Some(record),
+        }
+    }
+}
+
+/// A cloneable, thread-safe reference to an open scope.
+///
+/// Scopes opened through a handle, typically on worker threads, are reported as children
+/// of the scope the handle came from. If that scope has already closed, they are kept as
+/// completed trees of the thread they ran on instead.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+/// use elapsed_time::{scope, timed_scope};
+///
+/// {
+///     timed_scope!("fan_out");
+///     let handle = scope::current_handle().unwrap();
+///     let workers: Vec<_> = (0..3)
+///         .map(|i| {
+///             let handle = handle.clone();
+///             thread::spawn(move || {
+///                 let _guard = handle.enter(format!("worker_{}", i));
+///                 // ... work ...
+///             })
+///         })
+///         .collect();
+///     for worker in workers {
+///         worker.join().unwrap();
+///     }
+/// }
+///
+/// let tree = &scope::take_completed()[0];
+/// assert_eq!(tree.children.len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScopeHandle {
+    inbox: Arc<Mutex<Inbox>>,
+}
+
+impl ScopeHandle {
+    /// Opens a scope on the current thread that reports to the handle's scope when it closes.
+    ///
+    /// Scopes opened inside it on this thread nest under it as usual.
+    pub fn enter(&self, label: impl Into<String>) -> ScopeGuard {
+        ScopeGuard::push(Frame::new(label.into(), Some(self.clone())))
+    }
+
+    /// Adds a record to the handle's scope, or hands it back if that scope already closed.
+    fn deliver(&self, record: ScopeRecord) -> Option<ScopeRecord> {
+        let mut inbox = self.inbox.lock().unwrap_or_else(|e| e.into_inner());
+        if !inbox.open {
+            return Some(record);
+        }
+        inbox.records.push(record);
+        None
+    }
+}
+
+/// How many finished trees a thread keeps for [`take_completed`] before discarding the
+/// oldest.
+pub const MAX_COMPLETED: usize = 1_000;
+
+thread_local! {
+    static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+    static COMPLETED: RefCell<VecDeque<ScopeRecord>> = const { RefCell::new(VecDeque::new()) };
+    static DROPPED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Attaches a finished record to the innermost open scope, or stores it as a completed tree,
+/// discarding the oldest stored tree if [`MAX_COMPLETED`] are already waiting.
+fn attach(record: ScopeRecord) {
+    let orphan = STACK.with(|stack| match stack.borrow_mut().last_mut() {
+        Some(parent) => {
+            parent.children.push(record);
+            None
+        }
+        None => Some(record),
+    });
+    if let Some(record) = orphan {
+        COMPLETED.with(|completed| {
+            let mut completed = completed.borrow_mut();
+            if completed.len() >= MAX_COMPLETED {
+                completed.pop_front();
+                DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+            }
+            completed.push_back(record);
+        });
+    }
+}
+
+/// Closes the innermost open scope and attaches its record to the parent, if any.
+fn close_top(now: Instant) {
+    let record = STACK
+        .with(|stack| stack.borrow_mut().pop())
+        .and_then(|frame| frame.close(now));
+    if let Some(record) = record {
+        attach(record);
+    }
+}
+
+/// Closes a detached stack of frames, innermost first, and returns the outermost record.
+fn close_all(mut frames: Vec<Frame>, now: Instant) -> Option<ScopeRecord> {
+    let mut record = None;
+    while let Some(mut frame) = frames.pop() {
+        frame.children.extend(record.take());
+        record = frame.close(now);
+    }
+    record
+}
+
+/// An open scope on the current thread; the scope closes when the guard is dropped.
+///
+/// The guard is not `Send`: it must close on the thread that opened it. In async code,
+/// drop guards before the next `.await` and nest [`instrument`] for steps that span one.
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>(_: T) {}
+/// assert_send(elapsed_time::scope::ScopeGuard::enter("step"));
+/// ```
+#[must_use = "the scope closes as soon as the guard is dropped"]
+pub struct ScopeGuard {
+    depth: usize,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ScopeGuard {
+    /// Opens a scope called `label` as a child of the current thread's innermost open scope.
+    pub fn enter(label: impl Into<String>) -> Self {
+        Self::push(Frame::new(label.into(), None))
+    }
+
+    fn push(frame: Frame) -> Self {
+        let depth = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.push(frame);
+            stack.len() - 1
+        });
+        ScopeGuard {
+            depth,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns a handle other threads can use to report child scopes into this one.
+    pub fn handle(&self) -> ScopeHandle {
+        STACK.with(|stack| {
+            stack
+                .borrow_mut()
+                .get_mut(self.depth)
+                .expect("scope guard outlived its scope")
+                .handle()
+        })
+    }
+
+    /// Returns how long this scope has been open.
+    pub fn elapsed(&self) -> Duration {
+        STACK.with(|stack| {
+            stack
+                .borrow()
+                .get(self.depth)
+                .map_or(Duration::ZERO, |frame| frame.start.elapsed())
+        })
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        // Close any inner scopes whose guards were leaked, then this one.
+        let now = Instant::now();
+        while STACK.with(|stack| stack.borrow().len()) > self.depth {
+            close_top(now);
+        }
+    }
+}
+
+impl fmt::Debug for ScopeGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopeGuard").field("depth", &self.depth).finish()
+    }
+}
+
+/// A future running inside its own timed scope; see [`instrument`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Instrumented<F: Future> {
+    inner: Pin<Box<F>>,
+    label: Option<String>,
+    stack: Vec<Frame>,
+}
+
+/// Swaps a task's stack into the thread-local slot and swaps it back out on drop,
+/// including when the inner poll panics.
+struct Installed<'a> {
+    task: &'a mut Vec<Frame>,
+    saved: Vec<Frame>,
+}
+
+impl<'a> Installed<'a> {
+    fn new(task: &'a mut Vec<Frame>) -> Self {
+        let saved = STACK.with(|stack| mem::replace(&mut *stack.borrow_mut(), mem::take(task)));
+        Installed { task, saved }
+    }
+}
+
+impl Drop for Installed<'_> {
+    fn drop(&mut self) {
+        let saved = mem::take(&mut self.saved);
+        *self.task = STACK.with(|stack| mem::replace(&mut *stack.borrow_mut(), saved));
+    }
+}
+
+impl<F: Future> Future for Instrumented<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let this = self.get_mut();
+        if let Some(label) = this.label.take() {
+            this.stack.push(Frame::new(label, None));
+        }
+
+        let poll = {
+            let _installed = Installed::new(&mut this.stack);
+            this.inner.as_mut().poll(cx)
+        };
+
+        if poll.is_ready() {
+            if let Some(record) = close_all(mem::take(&mut this.stack), Instant::now()) {
+                attach(record);
+            }
+        }
+        poll
+    }
+}
+
+impl<F: Future> fmt::Debug for Instrumented<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Instrumented")
+            .field("depth", &self.stack.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs a future inside a timed scope called `label` that follows the task across threads.
+///
+/// The scope opens on the first poll and closes when the future completes. Scopes opened
+/// while the future is being polled, including nested [`instrument`] futures awaited inside
+/// it, become its children no matter which thread each poll runs on. The finished
+/// tree attaches to the scope that was open where the future completed, or is stored for
+/// [`take_completed`] on that thread.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{scope, timed_scope};
+///
+/// async fn fetch() {
+///     scope::instrument("fetch", async {
+///         // ... .await points ...
+///     })
+///     .await;
+///     timed_scope!("decode");
+/// }
+///
+/// let task = scope::instrument("request", async {
+///     fetch().await;
+/// });
+/// // Spawn `task` on any executor, e.g. `tokio::spawn(task)`.
+/// # drop(task);
+/// ```
+pub fn instrument<F: Future>(label: impl Into<String>, future: F) -> Instrumented<F> {
+    Instrumented {
+        inner: Box::pin(future),
+        label: Some(label.into()),
+        stack: Vec::new(),
+    }
+}
+
+/// Returns a handle to the innermost scope open on this thread, if any.
+pub fn current_handle() -> Option<ScopeHandle> {
+    STACK.with(|stack| stack.borrow_mut().last_mut().map(Frame::handle))
+}
+
+/// Returns the number of scopes currently open on this thread.
+pub fn depth() -> usize {
+    STACK.with(|stack| stack.borrow().len())
+}
+
+/// Removes and returns the finished outermost scopes recorded on this thread, oldest first.
+///
+/// Call this regularly in long-running programs: only the latest [`MAX_COMPLETED`] trees
+/// are kept.
+pub fn take_completed() -> Vec<ScopeRecord> {
+    COMPLETED.with(|completed| mem::take(&mut *completed.borrow_mut()).into())
+}
+
+/// Returns how many finished trees this thread has discarded because [`MAX_COMPLETED`]
+/// were already waiting to be taken.
+pub fn dropped() -> u64 {
+    DROPPED.with(Cell::get)
+}
+
+/// Opens a timed scope that lasts until the end of the enclosing block.
+///
+/// Nested uses on the same thread build a tree automatically; see the [`scope`](crate::scope)
+/// module for collecting the results.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::timed_scope;
+///
+/// fn compile() {
+///     timed_scope!("compile");
+///     // ... the scope closes when `compile` returns ...
+/// }
+/// # compile();
+/// ```
+#[macro_export]
+macro_rules! timed_scope {
+    ($label:expr) => {
+        let _timed_scope_guard = $crate::scope::ScopeGuard::enter($label);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inner() {
+        timed_scope!("inner");
+    }
+
+    fn outer() {
+        timed_scope!("outer");
+        inner();
+        inner();
+        assert_eq!(depth(), 1);
+    }
+
+    #[test]
+    fn test_nested_scopes_build_tree() {
+        take_completed();
+        outer();
+        assert_eq!(depth(), 0);
+
+        let trees = take_completed();
+        assert_eq!(trees.len(), 1);
+        let outer = &trees[0];
+        assert_eq!(outer.label, "outer");
+        assert_eq!(outer.children.len(), 2);
+        assert!(outer.children.iter().all(|c| c.label == "inner" && c.children.is_empty()));
+        assert!(outer.children.iter().map(|c| c.duration).sum::<Duration>() <= outer.duration);
+        assert!(take_completed().is_empty());
+    }
+
+    #[test]
+    fn test_leaked_inner_guard_is_closed_by_parent() {
+        take_completed();
+        {
+            timed_scope!("parent");
+            std::mem::forget(ScopeGuard::enter("leaked"));
+        }
+        assert_eq!(depth(), 0);
+        let trees = take_completed();
+        assert_eq!(trees[0].children[0].label, "leaked");
+    }
+
+    #[test]
+    fn test_completed_trees_are_capped() {
+        take_completed();
+        let before = dropped();
+        for _ in 0..MAX_COMPLETED + 2 {
+            timed_scope!("batch");
+        }
+        {
+            timed_scope!("last");
+        }
+        let trees = take_completed();
+        assert_eq!(trees.len(), MAX_COMPLETED);
+        assert_eq!(trees.last().unwrap().label, "last");
+        assert_eq!(dropped() - before, 3);
+    }
+
+    #[test]
+    fn test_display_tree() {
+        let record = ScopeRecord {
+            label: "request".into(),
+            duration: Duration::from_millis(1200),
+            children: vec![
+                ScopeRecord {
+                    label: "parse".into(),
+                    duration: Duration::from_millis(100),
+                    children: vec![ScopeRecord {
+                        label: "lex".into(),
+                        duration: Duration::from_millis(40),
+                        children: Vec::new(),
+                    }],
+                },
+                ScopeRecord {
+                    label: "db".into(),
+                    duration: Duration::from_secs(1),
+                    children: Vec::new(),
+                },
+            ],
+        };
+        assert_eq!(
+            record.to_string(),
+            "request: 1.200s\n  parse: 0.100s\n    lex: 0.040s\n  db: 1s"
+        );
+    }
+
+    #[test]
+    fn test_profile_merges_repeated_scopes() {
+        let leaf = |label: &str, ms| ScopeRecord {
+            label: label.into(),
+            duration: Duration::from_millis(ms),
+            children: Vec::new(),
+        };
+        let batch = |loads: Vec<ScopeRecord>| ScopeRecord {
+            label: "batch".into(),
+            duration: Duration::from_secs(5),
+            children: loads,
+        };
+        let records = [
+            batch(vec![leaf("load", 1000), leaf("load", 2000), leaf("save", 500)]),
+            batch(vec![leaf("load", 3000)]),
+        ];
+        assert_eq!(records[0].self_time(), Duration::from_millis(1500));
+
+        let profile = profile(&records);
+        assert_eq!(profile.len(), 1);
+        assert_eq!(
+            profile[0].to_string(),
+            "batch: 10s total, 3.500s self, 2 calls\n  \
+             load: 6s total, 6s self, 3 calls\n  \
+             save: 0.500s total, 0.500s self, 1 call"
+        );
+    }
+
+    /// Polls futures by hand so tests control exactly when each one makes progress.
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        let waker = std::task::Waker::noop();
+        Pin::new(future).poll(&mut Context::from_waker(waker))
+    }
+
+    /// A future that is pending on its first poll, like a real `.await` on I/O.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                Poll::Pending
+            }
+        }
+    }
+
+    async fn task(name: &'static str) {
+        instrument(name, async {
+            YieldOnce(false).await;
+            timed_scope!("after_await");
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_instrumented_tasks_keep_separate_stacks() {
+        take_completed();
+        let mut a = instrument("a", task("step_a"));
+        let mut b = instrument("b", task("step_b"));
+
+        // Interleave the two tasks the way an executor would.
+        assert!(poll_once(&mut a).is_pending());
+        assert!(poll_once(&mut b).is_pending());
+        assert_eq!(depth(), 0);
+        assert!(poll_once(&mut b).is_ready());
+        assert!(poll_once(&mut a).is_ready());
+
+        let trees = take_completed();
+        let labels: Vec<_> = trees.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(labels, ["b", "a"]);
+        for (tree, step) in trees.iter().zip(["step_b", "step_a"]) {
+            assert_eq!(tree.children.len(), 1);
+            assert_eq!(tree.children[0].label, step);
+            assert_eq!(tree.children[0].children[0].label, "after_await");
+        }
+    }
+
+    #[test]
+    fn test_instrumented_task_attaches_to_open_scope() {
+        take_completed();
+        {
+            timed_scope!("caller");
+            let mut future = instrument("child_task", async { 7 });
+            assert_eq!(poll_once(&mut future), Poll::Ready(7));
+        }
+        let trees = take_completed();
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees[0].children[0].label, "child_task");
+    }
+
+    #[test]
+    fn test_instrumented_future_moves_between_threads() {
+        take_completed();
+        let mut future = instrument("moving", task("step"));
+        assert!(poll_once(&mut future).is_pending());
+        let trees = std::thread::spawn(move || {
+            assert!(poll_once(&mut future).is_ready());
+            take_completed()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(trees[0].children[0].children[0].label, "after_await");
+        assert!(take_completed().is_empty());
+    }
+
+    #[test]
+    fn test_worker_threads_report_to_origin() {
+        take_completed();
+        {
+            let origin = ScopeGuard::enter("origin");
+            let handle = origin.handle();
+            timed_scope!("local");
+            std::thread::scope(|threads| {
+                for name in ["w1", "w2"] {
+                    let handle = handle.clone();
+                    threads.spawn(move || {
+                        let _worker = handle.enter(name);
+                        timed_scope!("nested");
+                    });
+                }
+            });
+            assert!(take_completed().is_empty());
+        }
+
+        let trees = take_completed();
+        assert_eq!(trees.len(), 1);
+        let labels: Vec<_> = trees[0].children.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels.len(), 3);
+        assert_eq!(labels[0], "local");
+        assert!(labels.contains(&"w1") && labels.contains(&"w2"));
+        assert!(trees[0].children[1..].iter().all(|c| c.children[0].label == "nested"));
+    }
+
+    #[test]
+    fn test_handle_after_origin_closed() {
+        take_completed();
+        let handle = {
+            timed_scope!("short_lived");
+            current_handle().unwrap()
+        };
+        assert_eq!(take_completed().len(), 1);
+
+        let late = std::thread::spawn(move || {
+            drop(handle.enter("late"));
+            take_completed()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(late[0].label, "late");
+        assert!(current_handle().is_none());
+    }
+}
@@ -0,0 +1,113 @@
+//! Serde support for durations in this crate's human-readable format.
+//!
+//! Enabled with the `serde` feature. Use the module with `#[serde(with = ...)]` on plain
+//! `Duration` fields; [`HumanDuration`](crate::HumanDuration) fields need nothing extra.
+//! In human-readable formats such as JSON and TOML a duration is written as a string like
+//! `"2m 30s"` and read with [`parse_duration`](crate::parse_duration); in binary formats it
+//! is a whole number of nanoseconds.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use serde::{Deserialize, Serialize};
+//! use elapsed_time::HumanDuration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "elapsed_time::serde_human")]
+//!     timeout: Duration,
+//!     retry_after: HumanDuration,
+//! }
+//!
+//! let config: Config =
+//!     serde_json::from_str(r#"{"timeout": "2m 30s", "retry_after": "1.5s"}"#).unwrap();
+//! assert_eq!(config.timeout, Duration::from_secs(150));
+//! assert_eq!(
+//!     serde_json::to_string(&config).unwrap(),
+//!     r#"{"timeout":"2m 30s","retry_after":"1.500s"}"#
+//! );
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+
+use crate::{format_duration, parse_duration, HumanDuration};
+
+/// Serializes `duration` as a human-readable string or, in binary formats, as nanoseconds.
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format_duration(*duration))
+    } else {
+        serializer.serialize_u128(duration.as_nanos())
+    }
+}
+
+/// Deserializes a duration written by [`serialize`].
+///
+/// Human-readable formats may also give a plain number, read as nanoseconds.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(DurationVisitor)
+    } else {
+        deserializer.deserialize_u128(DurationVisitor)
+    }
+}
+
+struct DurationVisitor;
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a duration such as \"2m 30s\" or a number of nanoseconds")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Duration, E> {
+        parse_duration(value).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, nanos: u64) -> Result<Duration, E> {
+        Ok(Duration::from_nanos(nanos))
+    }
+
+    fn visit_u128<E: de::Error>(self, nanos: u128) -> Result<Duration, E> {
+        let secs =
+            u64::try_from(nanos / 1_000_000_000).map_err(|_| E::custom("duration is too large"))?;
+        Ok(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+    }
+}
+
+impl serde::Serialize for HumanDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for HumanDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(HumanDuration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        let d = HumanDuration(Duration::from_millis(90_500));
+        let json = serde_json::to_string(&d).unwrap();
+        assert_eq!(json, "\"1m 30.500s\"");
+        assert_eq!(serde_json::from_str::<HumanDuration>(&json).unwrap(), d);
+        assert_eq!(
+            serde_json::from_str::<HumanDuration>("1500000000").unwrap(),
+            HumanDuration(Duration::from_millis(1500))
+        );
+        let err = serde_json::from_str::<HumanDuration>("\"soon\"").unwrap_err();
+        assert!(err.to_string().contains("invalid number"), "{}", err);
+    }
+}
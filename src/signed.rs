@@ -0,0 +1,225 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+use crate::fmt_duration;
+
+/// A span of time that may be negative, such as the difference between two runs.
+///
+/// `std::time::Duration` cannot be negative, so this pairs a magnitude with a sign. A zero
+/// span is never negative. `Display` writes [`format_duration`](crate::format_duration)
+/// output prefixed with `"-"` for negative spans; use `{:+}` to also prefix positive spans
+/// with `"+"`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::SignedDuration;
+///
+/// let baseline = Duration::from_millis(4_500);
+/// let run = Duration::from_millis(2_200);
+/// let delta = SignedDuration::between(baseline, run);
+/// assert!(delta.is_negative());
+/// assert_eq!(format!("this run was {} vs baseline", delta), "this run was -2.300s vs baseline");
+/// assert_eq!(format!("{:+}", SignedDuration::from_nanos(1_500_000_000)), "+1.500s");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SignedDuration {
+    negative: bool,
+    magnitude: Duration,
+}
+
+impl SignedDuration {
+    /// Creates a signed span from a sign and a magnitude.
+    pub const fn new(negative: bool, magnitude: Duration) -> Self {
+        SignedDuration {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    /// Creates a signed span from a nanosecond count.
+    ///
+    /// Magnitudes beyond `Duration::MAX` saturate.
+    pub fn from_nanos(nanos: i128) -> Self {
+        let magnitude = nanos.unsigned_abs();
+        let magnitude = match u64::try_from(magnitude / 1_000_000_000) {
+            Ok(secs) => Duration::new(secs, (magnitude % 1_000_000_000) as u32),
+            Err(_) => Duration::MAX,
+        };
+        SignedDuration::new(nanos < 0, magnitude)
+    }
+
+    /// Returns `to - from`, which is negative when `to` is the shorter of the two.
+    pub fn between(from: Duration, to: Duration) -> Self {
+        match to.checked_sub(from) {
+            Some(magnitude) => SignedDuration::new(false, magnitude),
+            None => SignedDuration::new(true, from - to),
+        }
+    }
+
+    /// Returns the wall-clock time from `from` to `to`, which is negative when `to` is
+    /// earlier, as when the system clock was set back between the two readings.
+    #[cfg(feature = "std")]
+    pub fn between_system_times(from: SystemTime, to: SystemTime) -> Self {
+        match to.duration_since(from) {
+            Ok(magnitude) => SignedDuration::new(false, magnitude),
+            Err(err) => SignedDuration::new(true, err.duration()),
+        }
+    }
+
+    /// Returns `true` if the span is negative.
+    pub const fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the span without its sign.
+    pub const fn magnitude(&self) -> Duration {
+        self.magnitude
+    }
+
+    /// Returns the span as a signed nanosecond count.
+    pub const fn as_nanos(&self) -> i128 {
+        let nanos = self.magnitude.as_nanos() as i128;
+        if self.negative {
+            -nanos
+        } else {
+            nanos
+        }
+    }
+}
+
+impl fmt::Display for SignedDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        } else if f.sign_plus() {
+            f.write_str("+")?;
+        }
+        fmt_duration(f, self.magnitude)
+    }
+}
+
+impl From<Duration> for SignedDuration {
+    fn from(duration: Duration) -> Self {
+        SignedDuration::new(false, duration)
+    }
+}
+
+impl From<(bool, Duration)> for SignedDuration {
+    fn from((negative, magnitude): (bool, Duration)) -> Self {
+        SignedDuration::new(negative, magnitude)
+    }
+}
+
+impl From<i128> for SignedDuration {
+    fn from(nanos: i128) -> Self {
+        SignedDuration::from_nanos(nanos)
+    }
+}
+
+/// Formats a span that may be negative, prefixing `"-"` when it is.
+///
+/// # Arguments
+///
+/// * `span` - A [`SignedDuration`], a `(negative, Duration)` pair, a plain `Duration` or an
+///   `i128` nanosecond count.
+///
+/// # Returns
+///
+/// A `String` in the [`format_duration`](crate::format_duration) format, with a leading
+/// `"-"` for negative spans.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::format_signed_duration;
+///
+/// assert_eq!(format_signed_duration((true, Duration::from_millis(2_300))), "-2.300s");
+/// assert_eq!(format_signed_duration(-90_000_000_000i128), "-1m 30s");
+/// assert_eq!(format_signed_duration(Duration::from_secs(5)), "5s");
+/// ```
+pub fn format_signed_duration(span: impl Into<SignedDuration>) -> String {
+    span.into().to_string()
+}
+
+/// Formats the wall-clock time from `from` to `to`, such as between two file
+/// modification times.
+///
+/// Unlike `Instant`s, `SystemTime`s can go backwards, so the span is signed: it gets a
+/// leading `"-"` when `to` is earlier than `from`.
+///
+/// # Arguments
+///
+/// * `from` - The earlier point in time.
+/// * `to` - The later point in time.
+///
+/// # Returns
+///
+/// A `String` in the [`format_duration`](crate::format_duration) format, with a leading
+/// `"-"` if `to` comes before `from`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use elapsed_time::format_between_system_times;
+///
+/// let built = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let edited = built + Duration::from_secs(150);
+/// assert_eq!(format_between_system_times(built, edited), "2m 30s");
+/// assert_eq!(format_between_system_times(edited, built), "-2m 30s");
+/// ```
+#[cfg(feature = "std")]
+pub fn format_between_system_times(from: SystemTime, to: SystemTime) -> String {
+    SignedDuration::between_system_times(from, to).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_nanos() {
+        let span = SignedDuration::from_nanos(-2_300_000_000);
+        assert!(span.is_negative());
+        assert_eq!(span.magnitude(), Duration::from_millis(2_300));
+        assert_eq!(span.as_nanos(), -2_300_000_000);
+        assert_eq!(
+            SignedDuration::from_nanos(i128::MIN).magnitude(),
+            Duration::MAX
+        );
+    }
+
+    #[test]
+    fn test_zero_is_never_negative() {
+        assert!(!SignedDuration::new(true, Duration::ZERO).is_negative());
+        assert_eq!(format_signed_duration((true, Duration::ZERO)), "0s");
+        assert_eq!(format!("{:+}", SignedDuration::default()), "+0s");
+    }
+
+    #[test]
+    fn test_between() {
+        let (a, b) = (Duration::from_secs(3), Duration::from_secs(5));
+        assert_eq!(SignedDuration::between(a, b).to_string(), "2s");
+        assert_eq!(SignedDuration::between(b, a).to_string(), "-2s");
+        assert_eq!(format!("{:+}", SignedDuration::between(a, b)), "+2s");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_between_system_times() {
+        let from = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let to = from + Duration::from_millis(1_500);
+        assert_eq!(
+            SignedDuration::between_system_times(from, to),
+            SignedDuration::from_nanos(1_500_000_000)
+        );
+        assert_eq!(format_between_system_times(to, from), "-1.500s");
+        assert_eq!(format_between_system_times(from, from), "0s");
+    }
+}
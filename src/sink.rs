@@ -0,0 +1,192 @@
+//! A single, replaceable destination for the measurements this crate reports.
+//!
+//! `#[timed]`, [`time!`](crate::time) and [`ScopedTimer`](crate::ScopedTimer) report
+//! through the global [`MeasurementSink`], which prints `"<label>: <elapsed>"` to stderr
+//! until another one is installed with [`set_sink`]. An installed sink also sees every
+//! closed [scope](crate::scope) and every duration added to the [`timers`](crate::timers)
+//! registry, so one sink can route all of the crate's timings to a channel, a metrics
+//! client or a file.
+//!
+//! Scopes and the registry keep their figures for later reports, so the default stderr
+//! sink does not print them.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::mpsc;
+//! use std::time::Duration;
+//! use elapsed_time::{sink, timers};
+//!
+//! let (tx, rx) = mpsc::channel();
+//! sink::set_sink(move |label: &str, elapsed: Duration| {
+//!     let _ = tx.send((label.to_string(), elapsed));
+//! });
+//!
+//! timers::record("db_query", Duration::from_millis(120));
+//! assert_eq!(rx.recv().unwrap(), ("db_query".to_string(), Duration::from_millis(120)));
+//! sink::reset_sink();
+//! ```
+
+use std::io::Write;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::Duration;
+
+use crate::format_duration;
+
+/// A destination for labelled measurements.
+///
+/// Any `Fn(&str, Duration)` closure that is `Send + Sync` is already a sink.
+pub trait MeasurementSink: Send + Sync {
+    /// Receives one measurement called `label` that took `duration`.
+    fn record(&self, label: &str, duration: Duration);
+}
+
+impl<F> MeasurementSink for F
+where
+    F: Fn(&str, Duration) + Send + Sync,
+{
+    fn record(&self, label: &str, duration: Duration) {
+        self(label, duration)
+    }
+}
+
+/// The default sink: prints `"<label>: <elapsed>"` to stderr.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StderrSink;
+
+impl MeasurementSink for StderrSink {
+    fn record(&self, label: &str, duration: Duration) {
+        eprintln!("{}", line(label, duration));
+    }
+}
+
+/// A sink that writes `"<label>: <elapsed>"` lines to any writer, such as a log file.
+///
+/// Write errors are ignored; reporting never affects the measured work.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::fs::File;
+/// use elapsed_time::sink::{self, WriterSink};
+///
+/// sink::set_sink(WriterSink::new(File::create("timings.log").unwrap()));
+/// ```
+#[derive(Debug)]
+pub struct WriterSink<W> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> WriterSink<W> {
+    /// Creates a sink writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        WriterSink {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Returns the writer, e.g. to inspect a `Vec<u8>` in tests.
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<W: Write + Send> MeasurementSink for WriterSink<W> {
+    fn record(&self, label: &str, duration: Duration) {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = writeln!(writer, "{}", line(label, duration));
+    }
+}
+
+/// Formats `"<label>: <elapsed>"`.
+fn line(label: &str, duration: Duration) -> String {
+    format!("{}: {}", label, format_duration(duration))
+}
+
+static SINK: RwLock<Option<Arc<dyn MeasurementSink>>> = RwLock::new(None);
+
+/// Returns the installed sink, if any. The lock is released before the sink is called, so
+/// a sink may itself install another one.
+fn installed() -> Option<Arc<dyn MeasurementSink>> {
+    SINK.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// Replaces the global sink for every thread.
+pub fn set_sink(sink: impl MeasurementSink + 'static) {
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(sink));
+}
+
+/// Restores the default [`StderrSink`].
+pub fn reset_sink() {
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Sends a measurement to the global sink, [`StderrSink`] unless another one is installed.
+pub fn record(label: &str, duration: Duration) {
+    match installed() {
+        Some(sink) => sink.record(label, duration),
+        None => StderrSink.record(label, duration),
+    }
+}
+
+/// Sends a measurement kept for later reporting, from a scope or the registry, to the
+/// installed sink. Without one it is dropped rather than printed.
+pub(crate) fn observe(label: &str, duration: Duration) {
+    if let Some(sink) = installed() {
+        sink.record(label, duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer_sink() {
+        let sink = WriterSink::new(Vec::new());
+        sink.record("load config (src/main.rs:42)", Duration::from_millis(1300));
+        sink.record("build", Duration::from_secs(90));
+        assert_eq!(
+            String::from_utf8(sink.into_inner()).unwrap(),
+            "load config (src/main.rs:42): 1.300s\nbuild: 1m 30s\n"
+        );
+    }
+
+    // The only test that installs a global sink; other tests' measurements may reach it
+    // while it is installed, so it only looks at its own labels.
+    #[test]
+    fn test_installed_sink_sees_everything() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&seen);
+        set_sink(move |label: &str, _: Duration| {
+            if label.starts_with("sink::test") {
+                recorder.lock().unwrap().push(label.to_string());
+            }
+        });
+
+        record("sink::test record", Duration::ZERO);
+        crate::timers::record("sink::test timer", Duration::from_millis(5));
+        {
+            crate::timed_scope!("sink::test scope");
+        }
+        drop(crate::ScopedTimer::new("sink::test guard"));
+        crate::time!("sink::test block", {});
+        reset_sink();
+        observe("sink::test after reset", Duration::ZERO);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            seen[..4],
+            [
+                "sink::test record",
+                "sink::test timer",
+                "sink::test scope",
+                "sink::test guard"
+            ]
+        );
+        assert!(seen[4].starts_with("sink::test block (src/sink.rs:"));
+        assert_eq!(seen.len(), 5);
+    }
+}
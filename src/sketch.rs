@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use crate::format_duration;
+
+/// Relative accuracy of [`DurationSketch::new`].
+const DEFAULT_ACCURACY: f64 = 0.01;
+
+/// Bucket limit of [`DurationSketch::new`], enough to cover nanoseconds to years at 1%.
+const DEFAULT_MAX_BUCKETS: usize = 2048;
+
+/// Quantiles shown by [`DurationSketch`]'s `Display` implementation.
+const DISPLAY_QUANTILES: [(f64, &str); 4] =
+    [(0.5, "p50"), (0.9, "p90"), (0.99, "p99"), (0.999, "p99.9")];
+
+/// A fixed-memory summary of a stream of durations that answers quantile queries.
+///
+/// This is a DDSketch: measurements are counted in logarithmically sized buckets, so any
+/// quantile is reported within the configured relative accuracy (1% by default) of the
+/// true value, however many measurements were added. When the bucket limit is reached the
+/// smallest buckets are merged, which only affects the accuracy of the lowest quantiles.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::DurationSketch;
+///
+/// let mut sketch = DurationSketch::new();
+/// for ms in 1..=1000 {
+///     sketch.add(Duration::from_millis(ms));
+/// }
+///
+/// let p99 = sketch.quantile(0.99).unwrap();
+/// assert!(p99 > Duration::from_millis(980) && p99 < Duration::from_millis(1000));
+/// println!("{}", sketch); // "n=1000  p50 0.500s  p90 0.893s  p99 0.987s  p99.9 1s"
+/// ```
+#[derive(Debug, Clone)]
+pub struct DurationSketch {
+    gamma_ln: f64,
+    max_buckets: usize,
+    buckets: BTreeMap<i32, u64>,
+    zero_count: u64,
+    count: u64,
+    min: Option<Duration>,
+    max: Option<Duration>,
+}
+
+impl Default for DurationSketch {
+    fn default() -> Self {
+        DurationSketch::new()
+    }
+}
+
+impl DurationSketch {
+    /// Creates a sketch with 1% relative accuracy.
+    pub fn new() -> Self {
+        DurationSketch::with_accuracy(DEFAULT_ACCURACY, DEFAULT_MAX_BUCKETS)
+    }
+
+    /// Creates a sketch reporting quantiles within `accuracy` (e.g. `0.01` for 1%) of the
+    /// true value, using at most `max_buckets` buckets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `accuracy` is not strictly between 0 and 1, or `max_buckets` is zero.
+    pub fn with_accuracy(accuracy: f64, max_buckets: usize) -> Self {
+        assert!(accuracy > 0.0 && accuracy < 1.0, "accuracy must be between 0 and 1");
+        assert!(max_buckets > 0, "max_buckets must be at least 1");
+        let gamma = (1.0 + accuracy) / (1.0 - accuracy);
+        DurationSketch {
+            gamma_ln: gamma.ln(),
+            max_buckets,
+            buckets: BTreeMap::new(),
+            zero_count: 0,
+            count: 0,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Records one measurement.
+    pub fn add(&mut self, duration: Duration) {
+        self.count += 1;
+        self.min = Some(self.min.map_or(duration, |min| min.min(duration)));
+        self.max = Some(self.max.map_or(duration, |max| max.max(duration)));
+
+        let nanos = duration.as_nanos() as f64;
+        if nanos < 1.0 {
+            self.zero_count += 1;
+            return;
+        }
+        let key = (nanos.ln() / self.gamma_ln).ceil() as i32;
+        *self.buckets.entry(key).or_insert(0) += 1;
+        self.collapse();
+    }
+
+    /// Adds every measurement recorded by `other`, which should use the same accuracy.
+    pub fn merge(&mut self, other: &DurationSketch) {
+        for (key, count) in &other.buckets {
+            *self.buckets.entry(*key).or_insert(0) += count;
+        }
+        self.zero_count += other.zero_count;
+        self.count += other.count;
+        self.min = self.min.into_iter().chain(other.min).min();
+        self.max = self.max.into_iter().chain(other.max).max();
+        self.collapse();
+    }
+
+    /// Merges the smallest buckets until the bucket limit is respected.
+    fn collapse(&mut self) {
+        while self.buckets.len() > self.max_buckets {
+            let (_, lowest) = self.buckets.pop_first().expect("more buckets than the limit");
+            *self.buckets.first_entry().expect("limit is at least 1").get_mut() += lowest;
+        }
+    }
+
+    /// Returns the number of measurements recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the smallest measurement recorded, exactly.
+    pub fn min(&self) -> Option<Duration> {
+        self.min
+    }
+
+    /// Returns the largest measurement recorded, exactly.
+    pub fn max(&self) -> Option<Duration> {
+        self.max
+    }
+
+    /// Returns the estimated `q`-quantile, e.g. `0.99` for p99, or `None` if empty.
+    ///
+    /// `q` is clamped to `0.0..=1.0`; the extremes return the exact minimum and maximum.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        let rank = (q.clamp(0.0, 1.0) * self.count.checked_sub(1)? as f64) as u64;
+        if rank == 0 {
+            return self.min;
+        }
+        if rank == self.count - 1 {
+            return self.max;
+        }
+        let mut seen = self.zero_count;
+        if rank < seen {
+            return Some(Duration::ZERO);
+        }
+        for (key, count) in &self.buckets {
+            seen += count;
+            if rank < seen {
+                // The midpoint of the bucket in relative terms, clamped to what was seen.
+                let gamma = self.gamma_ln.exp();
+                let nanos = 2.0 * (*key as f64 * self.gamma_ln).exp() / (gamma + 1.0);
+                let estimate = Duration::from_nanos(nanos.round() as u64);
+                return Some(estimate.clamp(self.min?, self.max?));
+            }
+        }
+        self.max
+    }
+
+    /// Returns the estimated `q`-quantile formatted with [`format_duration`].
+    pub fn format_quantile(&self, q: f64) -> Option<String> {
+        self.quantile(q).map(format_duration)
+    }
+}
+
+impl fmt::Display for DurationSketch {
+    /// Renders `"n=1000  p50 0.500s  p90 0.893s  p99 0.987s  p99.9 1s"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "n={}", self.count)?;
+        for (q, name) in DISPLAY_QUANTILES {
+            if let Some(value) = self.format_quantile(q) {
+                write!(f, "  {} {}", name, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn within(estimate: Duration, exact: Duration, accuracy: f64) -> bool {
+        (estimate.as_secs_f64() - exact.as_secs_f64()).abs() <= exact.as_secs_f64() * accuracy
+    }
+
+    #[test]
+    fn test_quantiles_within_accuracy() {
+        let mut sketch = DurationSketch::new();
+        for us in 1..=100_000 {
+            sketch.add(Duration::from_micros(us));
+        }
+        assert_eq!(sketch.count(), 100_000);
+        for q in [0.0, 0.25, 0.5, 0.9, 0.99, 0.999, 1.0] {
+            let exact = Duration::from_micros((q * 99_999.0) as u64 + 1);
+            let estimate = sketch.quantile(q).unwrap();
+            assert!(within(estimate, exact, 0.01), "q={} {:?} vs {:?}", q, estimate, exact);
+        }
+        assert_eq!(sketch.min(), Some(Duration::from_micros(1)));
+        assert_eq!(sketch.quantile(1.0), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_bounded_buckets_and_zero() {
+        let mut sketch = DurationSketch::with_accuracy(0.01, 16);
+        sketch.add(Duration::ZERO);
+        for ms in 1..=1000 {
+            sketch.add(Duration::from_millis(ms));
+        }
+        assert_eq!(sketch.buckets.len(), 16);
+        assert_eq!(sketch.quantile(0.0), Some(Duration::ZERO));
+        // The top buckets are untouched by collapsing.
+        assert!(within(sketch.quantile(0.99).unwrap(), Duration::from_millis(990), 0.01));
+    }
+
+    #[test]
+    fn test_merge_and_display() {
+        let mut a = DurationSketch::new();
+        let mut b = DurationSketch::new();
+        assert_eq!(a.quantile(0.5), None);
+        assert_eq!(a.to_string(), "n=0");
+        a.add(Duration::from_secs(1));
+        b.add(Duration::from_secs(3));
+        a.merge(&b);
+        assert_eq!(a.count(), 2);
+        assert_eq!(a.max(), Some(Duration::from_secs(3)));
+        assert_eq!(a.format_quantile(1.0).as_deref(), Some("3s"));
+        assert!(a.to_string().starts_with("n=2  p50 "));
+    }
+}
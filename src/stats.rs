@@ -0,0 +1,382 @@
+//! Repeated measurements and their summary statistics.
+//!
+//! A single timing is noisy: caches, frequency scaling and the scheduler all move it around.
+//! [`measure_n`] runs a closure several times and summarizes the samples in a [`Stats`];
+//! [`Repeat`] does the same after a number of untimed warmup runs.
+//!
+//! # Example
+//!
+//! ```
+//! use elapsed_time::stats::measure_n;
+//!
+//! let stats = measure_n(20, || (1..=10_000u64).sum::<u64>());
+//! assert_eq!(stats.len(), 20);
+//! assert!(stats.min() <= stats.median() && stats.median() <= stats.max());
+//! println!("{}", stats); // "n 20  min 0s  max 0.001s  mean 0s  median 0s  stddev 0s"
+//! ```
+
+use std::fmt;
+use std::hint::black_box;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// Summary statistics over a set of timings.
+///
+/// Each statistic is returned as a `Duration`; pass it to [`format_duration`] for display,
+/// or print the whole summary with `Display`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// The samples in ascending order; never empty.
+    sorted: Vec<Duration>,
+}
+
+impl Stats {
+    /// Builds statistics from a set of timings, or `None` if there are none.
+    pub fn from_samples(samples: impl IntoIterator<Item = Duration>) -> Option<Self> {
+        let mut sorted: Vec<Duration> = samples.into_iter().collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_unstable();
+        Some(Stats { sorted })
+    }
+
+    /// Returns the number of samples.
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Always returns `false`; a `Stats` holds at least one sample.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the samples in ascending order.
+    pub fn samples(&self) -> &[Duration] {
+        &self.sorted
+    }
+
+    /// Returns the fastest sample.
+    pub fn min(&self) -> Duration {
+        self.sorted[0]
+    }
+
+    /// Returns the slowest sample.
+    pub fn max(&self) -> Duration {
+        self.sorted[self.sorted.len() - 1]
+    }
+
+    /// Returns the arithmetic mean of the samples.
+    pub fn mean(&self) -> Duration {
+        let total: u128 = self.sorted.iter().map(Duration::as_nanos).sum();
+        nanos_to_duration(total / self.sorted.len() as u128)
+    }
+
+    /// Returns the middle sample, or the mean of the two middle samples for an even count.
+    pub fn median(&self) -> Duration {
+        let n = self.sorted.len();
+        if n % 2 == 1 {
+            self.sorted[n / 2]
+        } else {
+            let (a, b) = (self.sorted[n / 2 - 1], self.sorted[n / 2]);
+            nanos_to_duration((a.as_nanos() + b.as_nanos()) / 2)
+        }
+    }
+
+    /// Returns the `q`-quantile, e.g. `0.99` for p99.
+    ///
+    /// `q` is clamped to `0.0..=1.0`. Between two samples the value is interpolated
+    /// linearly, so `quantile(0.5)` is the [`median`](Stats::median).
+    pub fn quantile(&self, q: f64) -> Duration {
+        let rank = q.clamp(0.0, 1.0) * (self.sorted.len() - 1) as f64;
+        let (below, above) = (
+            self.sorted[rank.floor() as usize],
+            self.sorted[rank.ceil() as usize],
+        );
+        let fraction = rank - rank.floor();
+        let gap = (above - below).as_nanos() as f64 * fraction;
+        below + Duration::from_nanos(gap.round() as u64)
+    }
+
+    /// Returns the 50th, 90th, 95th and 99th percentiles as
+    /// `"p50=1.200s p90=1.700s p95=1.900s p99=2.400s"`.
+    pub fn summary(&self) -> String {
+        [("p50", 0.5), ("p90", 0.9), ("p95", 0.95), ("p99", 0.99)]
+            .iter()
+            .map(|(name, q)| format!("{}={}", name, format_duration(self.quantile(*q))))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the sample standard deviation, or zero for a single sample.
+    pub fn stddev(&self) -> Duration {
+        let n = self.sorted.len();
+        if n < 2 {
+            return Duration::ZERO;
+        }
+        let mean = self.mean().as_secs_f64();
+        let variance = self
+            .sorted
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1) as f64;
+        Duration::try_from_secs_f64(variance.sqrt()).unwrap_or(Duration::MAX)
+    }
+
+    /// Writes the raw samples as CSV, for analysis in a spreadsheet.
+    ///
+    /// The header is `nanos,formatted`, followed by one row per sample in ascending order,
+    /// e.g. `1200000000,1.200s`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing to `out`.
+    pub fn write_csv<W: io::Write>(&self, mut out: W) -> io::Result<()> {
+        crate::csv::write_record(&mut out, &["nanos", "formatted"])?;
+        for sample in &self.sorted {
+            let nanos = sample.as_nanos().to_string();
+            crate::csv::write_record(&mut out, &[&nanos, &format_duration(*sample)])?;
+        }
+        Ok(())
+    }
+
+    /// Returns the statistics as JSON: the sample count, then `min`, `max`, `mean`,
+    /// `median`, `stddev`, `p50`, `p90`, `p95` and `p99`, each as
+    /// `{"nanos":1200000000,"formatted":"1.200s"}`.
+    ///
+    /// Enabled with the `serde_json` feature.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> String {
+        use crate::json::duration_value;
+
+        let mut object = serde_json::Map::new();
+        object.insert("count".to_string(), self.len().into());
+        for (name, value) in [
+            ("min", self.min()),
+            ("max", self.max()),
+            ("mean", self.mean()),
+            ("median", self.median()),
+            ("stddev", self.stddev()),
+            ("p50", self.quantile(0.5)),
+            ("p90", self.quantile(0.9)),
+            ("p95", self.quantile(0.95)),
+            ("p99", self.quantile(0.99)),
+        ] {
+            object.insert(name.to_string(), duration_value(value));
+        }
+        serde_json::Value::Object(object).to_string()
+    }
+}
+
+impl fmt::Display for Stats {
+    /// Renders `"n 20  min 0.080s  max 0.430s  mean 0.181s  median 0.095s  stddev 0.090s"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "n {}  min {}  max {}  mean {}  median {}  stddev {}",
+            self.len(),
+            format_duration(self.min()),
+            format_duration(self.max()),
+            format_duration(self.mean()),
+            format_duration(self.median()),
+            format_duration(self.stddev())
+        )
+    }
+}
+
+fn nanos_to_duration(nanos: u128) -> Duration {
+    Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    )
+}
+
+/// A repeated measurement: how many runs to time, and how many untimed warmup runs to do
+/// first.
+///
+/// Warmup runs execute the closure but are left out of the statistics, so cold caches,
+/// lazy initialization and the first page faults don't skew the first samples.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::stats::Repeat;
+///
+/// let stats = Repeat::new(50).warmup(5).measure(|| (1..=10_000u64).sum::<u64>());
+/// assert_eq!(stats.len(), 50);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    runs: usize,
+    warmup: usize,
+}
+
+impl Repeat {
+    /// Creates a measurement of `runs` timed runs, without warmup.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `runs` is zero.
+    pub fn new(runs: usize) -> Self {
+        assert!(runs > 0, "a repeated measurement needs at least one run");
+        Repeat { runs, warmup: 0 }
+    }
+
+    /// Sets how many times the closure runs untimed before the timed runs start.
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Runs the warmup iterations, then times each of the runs and summarizes them.
+    ///
+    /// The closure's result is passed through [`std::hint::black_box`] so the compiler
+    /// cannot optimize the work away.
+    pub fn measure<F, T>(&self, mut f: F) -> Stats
+    where
+        F: FnMut() -> T,
+    {
+        for _ in 0..self.warmup {
+            black_box(f());
+        }
+        let samples = (0..self.runs).map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        });
+        Stats::from_samples(samples).expect("runs is at least one")
+    }
+}
+
+/// Runs a closure `n` times and summarizes how long each run took.
+///
+/// This is `Repeat::new(n).measure(f)`; use [`Repeat`] to add warmup runs.
+///
+/// # Arguments
+///
+/// * `n` - How many times to run the closure.
+/// * `f` - The closure to run and measure.
+///
+/// # Returns
+///
+/// The [`Stats`] of the `n` timings.
+///
+/// # Panics
+///
+/// Panics if `n` is zero.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::stats::measure_n;
+///
+/// let stats = measure_n(10, || std::thread::sleep(std::time::Duration::from_millis(1)));
+/// println!("sleep(1ms): median {}", elapsed_time::format_duration(stats.median()));
+/// ```
+pub fn measure_n<F, T>(n: usize, f: F) -> Stats
+where
+    F: FnMut() -> T,
+{
+    Repeat::new(n).measure(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Stats {
+        Stats::from_samples(values.iter().map(|&ms| Duration::from_millis(ms))).unwrap()
+    }
+
+    #[test]
+    fn test_statistics() {
+        let stats = ms(&[400, 100, 300, 200]);
+        assert_eq!(stats.samples()[0], Duration::from_millis(100));
+        assert_eq!(stats.min(), Duration::from_millis(100));
+        assert_eq!(stats.max(), Duration::from_millis(400));
+        assert_eq!(stats.mean(), Duration::from_millis(250));
+        assert_eq!(stats.median(), Duration::from_millis(250));
+        assert_eq!(stats.stddev().as_micros(), 129_099);
+        assert_eq!(
+            stats.to_string(),
+            "n 4  min 0.100s  max 0.400s  mean 0.250s  median 0.250s  stddev 0.129s"
+        );
+    }
+
+    #[test]
+    fn test_quantiles() {
+        let stats = ms(&(1..=101).collect::<Vec<_>>());
+        assert_eq!(stats.quantile(0.0), Duration::from_millis(1));
+        assert_eq!(stats.quantile(0.9), Duration::from_millis(91));
+        assert_eq!(stats.quantile(2.0), Duration::from_millis(101));
+        assert_eq!(
+            stats.summary(),
+            "p50=0.051s p90=0.091s p95=0.096s p99=0.100s"
+        );
+
+        let stats = ms(&[100, 200]);
+        assert_eq!(stats.quantile(0.5), stats.median());
+        assert_eq!(stats.quantile(0.25), Duration::from_millis(125));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_to_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&ms(&[400, 100, 300, 200]).to_json()).unwrap();
+        assert_eq!(json["count"], 4);
+        assert_eq!(json["min"]["nanos"], 100_000_000);
+        assert_eq!(json["median"]["formatted"], "0.250s");
+        assert_eq!(json["p99"]["nanos"], 397_000_000);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut csv = Vec::new();
+        ms(&[1500, 90_000]).write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "nanos,formatted\n1500000000,1.500s\n90000000000,1m 30s\n"
+        );
+    }
+
+    #[test]
+    fn test_single_and_empty() {
+        let stats = ms(&[5]);
+        assert_eq!(
+            (stats.median(), stats.stddev()),
+            (Duration::from_millis(5), Duration::ZERO)
+        );
+        assert!(Stats::from_samples(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_measure_n_runs_n_times() {
+        let mut calls = 0;
+        let stats = measure_n(7, || calls += 1);
+        assert_eq!((calls, stats.len()), (7, 7));
+    }
+
+    #[test]
+    fn test_warmup_runs_are_not_sampled() {
+        let mut calls = 0u64;
+        let stats = Repeat::new(3).warmup(4).measure(|| {
+            calls += 1;
+            // Only the warmup runs are slow.
+            if calls <= 4 {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        });
+        assert_eq!((calls, stats.len()), (7, 3));
+        assert!(stats.max() < Duration::from_millis(20));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one run")]
+    fn test_measure_n_zero() {
+        measure_n(0, || ());
+    }
+}
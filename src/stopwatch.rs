@@ -0,0 +1,374 @@
+use std::fmt;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// A stopwatch that accumulates time across pauses.
+///
+/// Its `Display` implementation reads the clock when formatted, so a running stopwatch can
+/// be dropped straight into a status line and always shows the current total.
+///
+/// With the `serde` feature, a stopwatch can be serialized, stored, and deserialized later
+/// with its total intact. A stopwatch saved while running keeps counting: the wall-clock
+/// time between saving and loading is added to it.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::Stopwatch;
+///
+/// let mut stopwatch = Stopwatch::start();
+/// // ... first phase ...
+/// stopwatch.pause();
+/// // ... time that should not count ...
+/// stopwatch.resume();
+/// // ... second phase ...
+/// println!("total so far: {}", stopwatch.elapsed_formatted());
+/// let total = stopwatch.stop();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "StopwatchState", from = "StopwatchState")
+)]
+pub struct Stopwatch {
+    accumulated: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Stopwatch {
+    /// Creates a paused stopwatch reading zero.
+    pub fn new() -> Self {
+        Stopwatch::default()
+    }
+
+    /// Creates a stopwatch and starts it.
+    pub fn start() -> Self {
+        Stopwatch {
+            accumulated: Duration::ZERO,
+            running_since: Some(Instant::now()),
+        }
+    }
+
+    /// Stops counting, keeping the time accumulated so far. Does nothing if already paused.
+    pub fn pause(&mut self) {
+        self.pause_at(Instant::now());
+    }
+
+    fn pause_at(&mut self, now: Instant) {
+        if let Some(started) = self.running_since.take() {
+            self.accumulated += now.saturating_duration_since(started);
+        }
+    }
+
+    /// Continues counting after a pause. Does nothing if already running.
+    pub fn resume(&mut self) {
+        self.resume_at(Instant::now());
+    }
+
+    fn resume_at(&mut self, now: Instant) {
+        self.running_since.get_or_insert(now);
+    }
+
+    /// Stops counting and returns the total time the stopwatch ran.
+    ///
+    /// This is [`pause`](Stopwatch::pause) followed by [`elapsed`](Stopwatch::elapsed); a
+    /// stopped stopwatch can still be resumed.
+    pub fn stop(&mut self) -> Duration {
+        self.stop_at(Instant::now())
+    }
+
+    fn stop_at(&mut self, now: Instant) -> Duration {
+        self.pause_at(now);
+        self.accumulated
+    }
+
+    /// Returns `true` if the stopwatch is counting.
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Returns the total time the stopwatch has been running.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_at(Instant::now())
+    }
+
+    fn elapsed_at(&self, now: Instant) -> Duration {
+        match self.running_since {
+            Some(started) => self.accumulated + now.saturating_duration_since(started),
+            None => self.accumulated,
+        }
+    }
+
+    /// Returns the total time the stopwatch has been running, formatted with
+    /// [`format_duration`].
+    pub fn elapsed_formatted(&self) -> String {
+        format_duration(self.elapsed())
+    }
+}
+
+impl fmt::Display for Stopwatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.elapsed_formatted())
+    }
+}
+
+/// A [`Stopwatch`] that records labelled splits, for timing the stages of a pipeline.
+///
+/// Each [`lap`](LapTimer::lap) closes a split covering the running time since the previous
+/// one; paused time is not counted. Its `Display` implementation renders a per-stage
+/// breakdown:
+///
+/// ```text
+/// fetch  1.200s
+/// parse  0.300s
+/// total  1.500s
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::LapTimer;
+///
+/// let mut timer = LapTimer::start();
+/// // ... fetch ...
+/// timer.lap("fetch");
+/// // ... parse ...
+/// timer.lap("parse");
+///
+/// for (label, duration, formatted) in timer.laps() {
+///     println!("{}: {} ({:?})", label, formatted, duration);
+/// }
+/// println!("total: {}", elapsed_time::format_duration(timer.total()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LapTimer {
+    stopwatch: Stopwatch,
+    laps: Vec<(String, Duration)>,
+    split: Duration,
+}
+
+impl LapTimer {
+    /// Creates a lap timer and starts it.
+    pub fn start() -> Self {
+        LapTimer {
+            stopwatch: Stopwatch::start(),
+            ..LapTimer::default()
+        }
+    }
+
+    /// Stops counting. Does nothing if already paused.
+    pub fn pause(&mut self) {
+        self.stopwatch.pause();
+    }
+
+    /// Continues counting after a pause. Does nothing if already running.
+    pub fn resume(&mut self) {
+        self.stopwatch.resume();
+    }
+
+    /// Returns `true` if the timer is counting.
+    pub fn is_running(&self) -> bool {
+        self.stopwatch.is_running()
+    }
+
+    /// Records a split called `label` ending now and returns its duration.
+    pub fn lap(&mut self, label: &str) -> Duration {
+        self.lap_at(label, Instant::now())
+    }
+
+    fn lap_at(&mut self, label: &str, now: Instant) -> Duration {
+        let elapsed = self.stopwatch.elapsed_at(now);
+        let duration = elapsed.saturating_sub(self.split);
+        self.split = elapsed;
+        self.laps.push((label.to_string(), duration));
+        duration
+    }
+
+    /// Returns every recorded lap as `(label, duration, formatted duration)`, in order.
+    pub fn laps(&self) -> Vec<(&str, Duration, String)> {
+        self.laps
+            .iter()
+            .map(|(label, duration)| (label.as_str(), *duration, format_duration(*duration)))
+            .collect()
+    }
+
+    /// Returns the total running time, including any time since the last lap.
+    pub fn total(&self) -> Duration {
+        self.stopwatch.elapsed()
+    }
+
+    /// Writes every recorded lap as CSV, for analysis in a spreadsheet.
+    ///
+    /// The header is `label,nanos,formatted`, followed by one row per lap in order, e.g.
+    /// `fetch,1200000000,1.200s`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing to `out`.
+    pub fn write_csv<W: io::Write>(&self, mut out: W) -> io::Result<()> {
+        crate::csv::write_record(&mut out, &["label", "nanos", "formatted"])?;
+        for (label, duration) in &self.laps {
+            let nanos = duration.as_nanos().to_string();
+            crate::csv::write_record(&mut out, &[label, &nanos, &format_duration(*duration)])?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for LapTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const TOTAL: &str = "total";
+        let width = self
+            .laps
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .chain([TOTAL.len()])
+            .max()
+            .unwrap_or(0);
+
+        for (label, duration) in &self.laps {
+            writeln!(f, "{:<width$}  {}", label, format_duration(*duration), width = width)?;
+        }
+        write!(f, "{:<width$}  {}", TOTAL, format_duration(self.total()), width = width)
+    }
+}
+
+/// The serialized form of a [`Stopwatch`].
+///
+/// An `Instant` is only meaningful inside the process that created it, so a running
+/// stopwatch is stored as its total so far plus the wall-clock time that total was taken at.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StopwatchState {
+    accumulated: Duration,
+    running: bool,
+    anchor: std::time::SystemTime,
+}
+
+#[cfg(feature = "serde")]
+impl From<Stopwatch> for StopwatchState {
+    fn from(stopwatch: Stopwatch) -> Self {
+        StopwatchState {
+            accumulated: stopwatch.elapsed(),
+            running: stopwatch.is_running(),
+            anchor: std::time::SystemTime::now(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<StopwatchState> for Stopwatch {
+    fn from(state: StopwatchState) -> Self {
+        let mut accumulated = state.accumulated;
+        if state.running {
+            // A clock that moved backwards adds nothing rather than failing the load.
+            accumulated += state.anchor.elapsed().unwrap_or_default();
+        }
+        Stopwatch {
+            accumulated,
+            running_since: state.running.then(Instant::now),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_and_resume() {
+        let t0 = Instant::now();
+        let mut stopwatch = Stopwatch::new();
+        assert!(!stopwatch.is_running());
+        stopwatch.resume_at(t0);
+        stopwatch.pause_at(t0 + Duration::from_secs(2));
+        stopwatch.pause_at(t0 + Duration::from_secs(5));
+        assert_eq!(stopwatch.elapsed_at(t0 + Duration::from_secs(9)), Duration::from_secs(2));
+
+        stopwatch.resume_at(t0 + Duration::from_secs(10));
+        stopwatch.resume_at(t0 + Duration::from_secs(11));
+        assert!(stopwatch.is_running());
+        assert_eq!(stopwatch.elapsed_at(t0 + Duration::from_secs(13)), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_stop() {
+        let t0 = Instant::now();
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.resume_at(t0);
+        let total = stopwatch.stop_at(t0 + Duration::from_millis(1250));
+        assert_eq!(total, Duration::from_millis(1250));
+        assert!(!stopwatch.is_running());
+        assert_eq!(stopwatch.stop_at(t0 + Duration::from_secs(4)), Duration::from_millis(1250));
+        assert_eq!(stopwatch.elapsed_formatted(), "1.250s");
+    }
+
+    #[test]
+    fn test_display_reads_clock() {
+        let paused = Stopwatch {
+            accumulated: Duration::from_millis(1500),
+            running_since: None,
+        };
+        assert_eq!(paused.to_string(), "1.500s");
+
+        let mut running = Stopwatch {
+            accumulated: Duration::from_secs(60),
+            running_since: Some(Instant::now() - Duration::from_secs(2)),
+        };
+        assert!(running.to_string().starts_with("1m 2"));
+        running.pause();
+        let frozen = running.to_string();
+        assert_eq!(running.to_string(), frozen);
+    }
+
+    #[test]
+    fn test_laps_skip_paused_time() {
+        let t0 = Instant::now();
+        let mut timer = LapTimer::default();
+        timer.stopwatch.resume_at(t0);
+        let fetch = timer.lap_at("fetch", t0 + Duration::from_millis(1200));
+        assert_eq!(fetch, Duration::from_millis(1200));
+        timer.stopwatch.pause_at(t0 + Duration::from_secs(2));
+        timer.stopwatch.resume_at(t0 + Duration::from_secs(5));
+        let parse = timer.lap_at("parse", t0 + Duration::from_secs(6));
+        assert_eq!(parse, Duration::from_millis(1800));
+        timer.stopwatch.pause_at(t0 + Duration::from_secs(7));
+
+        let laps = timer.laps();
+        assert_eq!(laps[0], ("fetch", Duration::from_millis(1200), "1.200s".to_string()));
+        assert_eq!(laps[1], ("parse", Duration::from_millis(1800), "1.800s".to_string()));
+        assert_eq!(timer.total(), Duration::from_secs(4));
+        assert_eq!(timer.to_string(), "fetch  1.200s\nparse  1.800s\ntotal  4s");
+
+        let mut csv = Vec::new();
+        timer.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "label,nanos,formatted\nfetch,1200000000,1.200s\nparse,1800000000,1.800s\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let paused = Stopwatch {
+            accumulated: Duration::from_millis(1500),
+            running_since: None,
+        };
+        let json = serde_json::to_string(&paused).unwrap();
+        assert!(json.contains("\"running\":false"));
+        let restored: Stopwatch = serde_json::from_str(&json).unwrap();
+        assert!(!restored.is_running());
+        assert_eq!(restored.elapsed(), Duration::from_millis(1500));
+
+        let stored = r#"{"accumulated":{"secs":60,"nanos":0},"running":true,
+                         "anchor":{"secs_since_epoch":0,"nanos_since_epoch":0}}"#;
+        let restored: Stopwatch = serde_json::from_str(stored).unwrap();
+        assert!(restored.is_running());
+        // Saved running at the epoch, so every second since then counts.
+        assert!(restored.elapsed() > Duration::from_secs(60 * 60 * 24 * 365));
+    }
+}
@@ -0,0 +1,126 @@
+//! Formatting and parsing the `time` crate's signed `Duration`.
+//!
+//! Enabled with the `time` feature.
+
+use time::error::ConversionRange;
+
+use crate::{parse_duration, HumanDuration, ParseError, SignedDuration};
+
+impl From<time::Duration> for SignedDuration {
+    fn from(duration: time::Duration) -> Self {
+        SignedDuration::new(duration.is_negative(), duration.unsigned_abs())
+    }
+}
+
+impl TryFrom<time::Duration> for HumanDuration {
+    type Error = ConversionRange;
+
+    /// Fails if `duration` is negative.
+    fn try_from(duration: time::Duration) -> Result<Self, ConversionRange> {
+        Ok(HumanDuration(duration.try_into()?))
+    }
+}
+
+impl TryFrom<HumanDuration> for time::Duration {
+    type Error = ConversionRange;
+
+    /// Fails if the duration is longer than `time::Duration::MAX`.
+    fn try_from(duration: HumanDuration) -> Result<Self, ConversionRange> {
+        duration.0.try_into()
+    }
+}
+
+/// Formats a `time::Duration`, prefixing `"-"` when it is negative.
+///
+/// # Arguments
+///
+/// * `duration` - The duration to format.
+///
+/// # Returns
+///
+/// A `String` in the [`format_duration`](crate::format_duration) format, with a leading `"-"`
+/// for negative durations.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::format_time_duration;
+///
+/// assert_eq!(format_time_duration(time::Duration::milliseconds(90_500)), "1m 30.500s");
+/// assert_eq!(format_time_duration(time::Duration::seconds(-5)), "-5s");
+/// ```
+pub fn format_time_duration(duration: time::Duration) -> String {
+    SignedDuration::from(duration).to_string()
+}
+
+/// Parses a human-readable duration into a `time::Duration`.
+///
+/// Accepts everything [`parse_duration`] does, optionally preceded by `"-"` for a negative
+/// duration.
+///
+/// # Arguments
+///
+/// * `input` - A string such as `"1h 30m"` or `"-2.5s"`.
+///
+/// # Returns
+///
+/// The parsed `time::Duration`, or a `ParseError` describing the first offending component.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] if [`parse_duration`] rejects the input, or
+/// [`ParseError::Overflow`] if the result doesn't fit in a `time::Duration`.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::parse_time_duration;
+///
+/// assert_eq!(parse_time_duration("1m 30s").unwrap(), time::Duration::seconds(90));
+/// assert_eq!(parse_time_duration("-2.5s").unwrap(), time::Duration::milliseconds(-2_500));
+/// ```
+pub fn parse_time_duration(input: &str) -> Result<time::Duration, ParseError> {
+    let input = input.trim_start();
+    let (negative, rest) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let magnitude: time::Duration = parse_duration(rest)?
+        .try_into()
+        .map_err(|_| ParseError::Overflow)?;
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_signed_duration;
+    use std::time::Duration;
+
+    #[test]
+    fn test_negative_duration_keeps_its_sign() {
+        let duration = time::Duration::milliseconds(-2_300);
+        assert_eq!(format_signed_duration(duration), "-2.300s");
+        assert_eq!(format_time_duration(time::Duration::ZERO), "0s");
+        assert_eq!(
+            SignedDuration::from(time::Duration::MIN).magnitude(),
+            time::Duration::MIN.unsigned_abs()
+        );
+    }
+
+    #[test]
+    fn test_human_duration_conversions() {
+        let human = HumanDuration(Duration::from_secs(150));
+        let duration = time::Duration::try_from(human).unwrap();
+        assert_eq!(duration, time::Duration::seconds(150));
+        assert_eq!(HumanDuration::try_from(duration).unwrap(), human);
+        assert!(HumanDuration::try_from(-duration).is_err());
+        assert!(time::Duration::try_from(HumanDuration(Duration::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_time_duration("-"), Err(ParseError::Empty));
+        assert!(parse_time_duration("--5s").is_err());
+    }
+}
@@ -0,0 +1,499 @@
+//! A process-wide registry of named timers, for lightweight whole-application profiling.
+//!
+//! Any thread can [`record`] a duration under a label, or [`time`] a closure under one.
+//! The registry keeps the count, total, minimum and maximum per label for the life of the
+//! process, and [`report`] renders them as an aligned table ordered by total time, so the
+//! most expensive operations come first.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use elapsed_time::timers;
+//!
+//! timers::record("db_query", Duration::from_millis(120));
+//! timers::record("db_query", Duration::from_millis(80));
+//! let rows = timers::time("parse_rows", || vec![1, 2, 3]);
+//! assert_eq!(rows.len(), 3);
+//!
+//! let db = timers::get("db_query").unwrap();
+//! assert_eq!((db.count, db.total), (2, Duration::from_millis(200)));
+//! println!("{}", timers::report());
+//! // label       count  total   mean    min     max     errors
+//! // db_query    2      0.200s  0.100s  0.080s  0.120s  0
+//! // parse_rows  1      0s      0s      0s      0s      0
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::{format_duration, DurationSketch};
+
+/// The aggregated timings recorded under one label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerStats {
+    /// How many durations were recorded.
+    pub count: u64,
+    /// The sum of all recorded durations.
+    pub total: Duration,
+    /// The shortest recorded duration.
+    pub min: Duration,
+    /// The longest recorded duration.
+    pub max: Duration,
+}
+
+impl TimerStats {
+    fn new(duration: Duration) -> Self {
+        TimerStats {
+            count: 1,
+            total: duration,
+            min: duration,
+            max: duration,
+        }
+    }
+
+    fn add(&mut self, duration: Duration) {
+        self.count += 1;
+        self.total = self.total.saturating_add(duration);
+        self.min = self.min.min(duration);
+        self.max = self.max.max(duration);
+    }
+
+    /// Returns the mean recorded duration.
+    pub fn mean(&self) -> Duration {
+        let nanos = self.total.as_nanos() / u128::from(self.count);
+        Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+}
+
+/// One outcome's exact aggregates, plus a sketch of its distribution for quantiles.
+struct Series {
+    stats: TimerStats,
+    sketch: DurationSketch,
+}
+
+impl Series {
+    fn new(duration: Duration) -> Self {
+        let mut sketch = DurationSketch::new();
+        sketch.add(duration);
+        Series {
+            stats: TimerStats::new(duration),
+            sketch,
+        }
+    }
+
+    fn add(&mut self, duration: Duration) {
+        self.stats.add(duration);
+        self.sketch.add(duration);
+    }
+}
+
+/// One label's timings, with successes and failures kept apart so slow or fast error paths
+/// don't skew the normal figures.
+#[derive(Default)]
+struct Timer {
+    ok: Option<Series>,
+    errors: Option<Series>,
+}
+
+impl Timer {
+    fn series(&mut self, ok: bool) -> &mut Option<Series> {
+        if ok {
+            &mut self.ok
+        } else {
+            &mut self.errors
+        }
+    }
+}
+
+static REGISTRY: Mutex<BTreeMap<String, Timer>> = Mutex::new(BTreeMap::new());
+
+/// Locks the registry. A panic while it was held cannot leave it inconsistent, so a
+/// poisoned lock is recovered rather than propagated.
+fn registry() -> MutexGuard<'static, BTreeMap<String, Timer>> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn add(label: &str, duration: Duration, ok: bool) {
+    let mut registry = registry();
+    if !registry.contains_key(label) {
+        registry.insert(label.to_string(), Timer::default());
+    }
+    let timer = registry
+        .get_mut(label)
+        .expect("the timer was just inserted");
+    match timer.series(ok) {
+        Some(series) => series.add(duration),
+        series => *series = Some(Series::new(duration)),
+    }
+    drop(registry);
+    crate::sink::observe(label, duration);
+}
+
+/// Adds `duration` to the timer named `label`, creating it on first use.
+///
+/// The duration is also passed to an installed [`sink`](crate::sink).
+pub fn record(label: &str, duration: Duration) {
+    add(label, duration, true);
+}
+
+/// Adds the `duration` of a failed operation to the timer named `label`.
+///
+/// Failures are aggregated apart from the durations passed to [`record`]; read them with
+/// [`get_errors`]. Reports count them in their own `errors` column. An installed
+/// [`sink`](crate::sink) receives the duration under `label`, like any other.
+pub fn record_error(label: &str, duration: Duration) {
+    add(label, duration, false);
+}
+
+/// Runs a closure, records how long it took under `label` and returns its result.
+pub fn time<F, T>(label: &str, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+    let result = f();
+    record(label, start.elapsed());
+    result
+}
+
+/// Runs a fallible closure and records how long it took under `label`, as a success or,
+/// if it returned `Err`, as an error; see [`record_error`].
+pub fn time_result<F, T, E>(label: &str, f: F) -> Result<T, E>
+where
+    F: FnOnce() -> Result<T, E>,
+{
+    let start = Instant::now();
+    let result = f();
+    add(label, start.elapsed(), result.is_ok());
+    result
+}
+
+/// Returns the aggregated timings for `label`, or `None` if nothing was recorded under it.
+///
+/// Failures recorded with [`record_error`] are not included.
+pub fn get(label: &str) -> Option<TimerStats> {
+    Some(registry().get(label)?.ok.as_ref()?.stats)
+}
+
+/// Returns the aggregated timings of the failures recorded under `label`, or `None` if
+/// there were none.
+pub fn get_errors(label: &str) -> Option<TimerStats> {
+    Some(registry().get(label)?.errors.as_ref()?.stats)
+}
+
+/// Returns the estimated `q`-quantile of the durations recorded under `label`, e.g. `0.95`
+/// for p95, or `None` if nothing was recorded under it.
+///
+/// The estimate is within 1% of the true value; see [`DurationSketch`].
+pub fn quantile(label: &str, q: f64) -> Option<Duration> {
+    registry().get(label)?.ok.as_ref()?.sketch.quantile(q)
+}
+
+/// Returns every timer, ordered by total time, longest first.
+///
+/// Labels that only recorded failures are left out; [`report`] lists failures too.
+pub fn snapshot() -> Vec<(String, TimerStats)> {
+    let mut timers: Vec<_> = registry()
+        .iter()
+        .filter_map(|(label, timer)| Some((label.clone(), timer.ok.as_ref()?.stats)))
+        .collect();
+    timers.sort_by_key(|(_, stats)| Reverse(stats.total));
+    timers
+}
+
+/// One label's line in the reports: its successful timings, if any, with the failures in
+/// a field of their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ReportRow {
+    pub(crate) label: String,
+    pub(crate) ok: Option<TimerStats>,
+    /// The estimated quantile of the successful durations, if one was asked for.
+    pub(crate) quantile: Option<Duration>,
+    pub(crate) errors: Option<TimerStats>,
+}
+
+/// Returns a row per label, failures included, ordered by the total time of the successful
+/// runs, longest first.
+///
+/// With `q`, each row also holds the estimated `q`-quantile of its successful durations,
+/// read under the same lock so the two always agree.
+pub(crate) fn report_rows(q: Option<f64>) -> Vec<ReportRow> {
+    let mut rows: Vec<_> = registry()
+        .iter()
+        .map(|(label, timer)| ReportRow {
+            label: label.clone(),
+            ok: timer.ok.as_ref().map(|series| series.stats),
+            quantile: q.and_then(|q| {
+                let series = timer.ok.as_ref()?;
+                Some(series.sketch.quantile(q).unwrap_or(series.stats.max))
+            }),
+            errors: timer.errors.as_ref().map(|series| series.stats),
+        })
+        .collect();
+    rows.sort_by_key(|row| Reverse(row.ok.map_or(Duration::ZERO, |stats| stats.total)));
+    rows
+}
+
+/// Removes every timer from the registry.
+pub fn reset() {
+    registry().clear();
+}
+
+/// Renders every timer as an aligned table, ordered by total time, longest first.
+///
+/// The `errors` column counts the failures recorded with [`record_error`]; the other
+/// columns describe the successful runs only. Returns `"no timers recorded"` if the
+/// registry is empty.
+pub fn report() -> String {
+    render(&report_rows(None))
+}
+
+/// Returns every timer as a JSON array, ordered by total time, longest first.
+///
+/// Each element holds the `label` and `count`, then `total`, `mean`, `min` and `max` as
+/// `{"nanos":200000000,"formatted":"0.200s"}`, of the successful runs; labels that only
+/// failed have a `count` of 0 and no durations. `errors` holds the same fields for the
+/// failures, or `null` if there were none. Enabled with the `serde_json` feature.
+#[cfg(feature = "serde_json")]
+pub fn report_json() -> String {
+    render_json(&report_rows(None))
+}
+
+#[cfg(feature = "serde_json")]
+fn render_json(rows: &[ReportRow]) -> String {
+    use serde_json::{Map, Value};
+
+    use crate::json::duration_value;
+
+    fn stats_json(object: &mut Map<String, Value>, stats: Option<&TimerStats>) {
+        object.insert(
+            "count".to_string(),
+            stats.map_or(0, |stats| stats.count).into(),
+        );
+        if let Some(stats) = stats {
+            object.insert("total".to_string(), duration_value(stats.total));
+            object.insert("mean".to_string(), duration_value(stats.mean()));
+            object.insert("min".to_string(), duration_value(stats.min));
+            object.insert("max".to_string(), duration_value(stats.max));
+        }
+    }
+
+    let rows: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            let mut object = Map::new();
+            object.insert("label".to_string(), row.label.clone().into());
+            stats_json(&mut object, row.ok.as_ref());
+            let errors = row.errors.as_ref().map_or(Value::Null, |errors| {
+                let mut object = Map::new();
+                stats_json(&mut object, Some(errors));
+                Value::Object(object)
+            });
+            object.insert("errors".to_string(), errors);
+            Value::Object(object)
+        })
+        .collect();
+    Value::Array(rows).to_string()
+}
+
+fn render(rows: &[ReportRow]) -> String {
+    if rows.is_empty() {
+        return "no timers recorded".to_string();
+    }
+    let header = ["label", "count", "total", "mean", "min", "max", "errors"];
+    let rows: Vec<[String; 7]> = rows
+        .iter()
+        .map(|row| {
+            let [total, mean, min, max] = match row.ok {
+                Some(stats) => {
+                    [stats.total, stats.mean(), stats.min, stats.max].map(format_duration)
+                }
+                None => ["-"; 4].map(str::to_string),
+            };
+            [
+                row.label.clone(),
+                row.ok.map_or(0, |stats| stats.count).to_string(),
+                total,
+                mean,
+                min,
+                max,
+                row.errors.map_or(0, |errors| errors.count).to_string(),
+            ]
+        })
+        .collect();
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header = header.map(str::to_string);
+    for row in std::iter::once(&header).chain(&rows) {
+        let mut line = String::new();
+        for (cell, width) in row.iter().zip(widths) {
+            // Writing to a `String` cannot fail.
+            let _ = write!(line, "{:<width$}  ", cell, width = width);
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out.pop();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The registry is shared by every test in the process, so each test uses its own labels.
+
+    #[test]
+    fn test_record_aggregates() {
+        for ms in [300, 100, 200] {
+            record("timers::test_record", Duration::from_millis(ms));
+        }
+        let stats = get("timers::test_record").unwrap();
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.total, Duration::from_millis(600));
+        assert_eq!(
+            (stats.min, stats.max),
+            (Duration::from_millis(100), Duration::from_millis(300))
+        );
+        assert_eq!(stats.mean(), Duration::from_millis(200));
+        assert_eq!(quantile("timers::test_record", 0.0), Some(stats.min));
+        assert_eq!(quantile("timers::test_record", 1.0), Some(stats.max));
+        assert!(get("timers::never_recorded").is_none());
+        assert!(quantile("timers::never_recorded", 0.5).is_none());
+    }
+
+    #[test]
+    fn test_time_and_threads() {
+        let threads: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(|| time("timers::test_threads", || 42)))
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), 42);
+        }
+        assert_eq!(get("timers::test_threads").unwrap().count, 4);
+        assert!(snapshot()
+            .iter()
+            .any(|(label, _)| label == "timers::test_threads"));
+    }
+
+    #[test]
+    fn test_errors_are_kept_apart() {
+        let label = "timers::test_errors";
+        record(label, Duration::from_millis(10));
+        record_error(label, Duration::from_secs(30));
+        let parsed: Result<u32, _> = time_result(label, || "x".parse::<u32>());
+        assert!(parsed.is_err());
+        assert_eq!(time_result(label, || Ok::<_, ()>(7)), Ok(7));
+
+        assert_eq!(get(label).unwrap().count, 2);
+        let errors = get_errors(label).unwrap();
+        assert_eq!(errors.count, 2);
+        assert_eq!(errors.max, Duration::from_secs(30));
+        assert!(get_errors("timers::test_record_never_failed").is_none());
+
+        record_error("timers::test_errors_only", Duration::from_millis(5));
+        assert!(get("timers::test_errors_only").is_none());
+        assert!(!snapshot()
+            .iter()
+            .any(|(label, _)| label == "timers::test_errors_only"));
+        let rows = report_rows(None);
+        let only = rows
+            .iter()
+            .find(|row| row.label == "timers::test_errors_only")
+            .unwrap();
+        assert_eq!(
+            (only.ok, only.errors.map(|errors| errors.count)),
+            (None, Some(1))
+        );
+    }
+
+    #[test]
+    fn test_errors_do_not_clash_with_labels() {
+        // A timer whose name looks like an error row stays its own timer.
+        record("timers::test_clash (errors)", Duration::from_millis(1));
+        record_error("timers::test_clash", Duration::from_millis(2));
+        assert_eq!(get("timers::test_clash (errors)").unwrap().count, 1);
+        assert!(get_errors("timers::test_clash (errors)").is_none());
+        let rows = report_rows(None);
+        let clash = rows
+            .iter()
+            .find(|row| row.label == "timers::test_clash")
+            .unwrap();
+        assert_eq!(clash.errors.unwrap().max, Duration::from_millis(2));
+    }
+
+    fn row(label: &str, ok: Option<TimerStats>, errors: Option<TimerStats>) -> ReportRow {
+        ReportRow {
+            label: label.to_string(),
+            ok,
+            quantile: None,
+            errors,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut db = TimerStats::new(Duration::from_millis(120));
+        db.add(Duration::from_millis(80));
+        let cache = TimerStats::new(Duration::from_micros(500));
+        let failed = TimerStats::new(Duration::from_secs(30));
+        let rows = [
+            row("db_query", Some(db), Some(failed)),
+            row("cache", Some(cache), None),
+            row("upload", None, Some(failed)),
+        ];
+        assert_eq!(
+            render(&rows),
+            "label     count  total   mean    min     max     errors\n\
+             db_query  2      0.200s  0.100s  0.080s  0.120s  1\n\
+             cache     1      0s      0s      0s      0s      0\n\
+             upload    0      -       -       -       -       1"
+        );
+        assert_eq!(render(&[]), "no timers recorded");
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_render_json() {
+        let mut db = TimerStats::new(Duration::from_millis(120));
+        db.add(Duration::from_millis(80));
+        let json = render_json(&[row("db_query", Some(db), None)]);
+        assert_eq!(
+            json,
+            concat!(
+                r#"[{"label":"db_query","count":2,"#,
+                r#""total":{"nanos":200000000,"formatted":"0.200s"},"#,
+                r#""mean":{"nanos":100000000,"formatted":"0.100s"},"#,
+                r#""min":{"nanos":80000000,"formatted":"0.080s"},"#,
+                r#""max":{"nanos":120000000,"formatted":"0.120s"},"#,
+                r#""errors":null}]"#
+            )
+        );
+        let failed = TimerStats::new(Duration::from_secs(2));
+        let json = render_json(&[row("upload", None, Some(failed))]);
+        assert_eq!(
+            json,
+            concat!(
+                r#"[{"label":"upload","count":0,"errors":{"count":1,"#,
+                r#""total":{"nanos":2000000000,"formatted":"2s"},"#,
+                r#""mean":{"nanos":2000000000,"formatted":"2s"},"#,
+                r#""min":{"nanos":2000000000,"formatted":"2s"},"#,
+                r#""max":{"nanos":2000000000,"formatted":"2s"}}}]"#
+            )
+        );
+        assert_eq!(render_json(&[]), "[]");
+    }
+}
@@ -0,0 +1,424 @@
+use std::fmt;
+
+use crate::format_signed_duration;
+
+/// The timestamp layouts understood by [`elapsed_between`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampFormat {
+    /// Try every format below, in order.
+    #[default]
+    Auto,
+    /// RFC 3339, e.g. `2024-03-01T12:00:00.250Z` or `2024-03-01T14:00:00+02:00`.
+    Rfc3339,
+    /// A date and time without an offset, as most loggers write them, e.g.
+    /// `2024-03-01 12:00:00,250`.
+    DateTime,
+    /// The Common Log Format used by Apache and nginx, e.g. `[01/Mar/2024:12:00:00 +0000]`.
+    Clf,
+    /// A syslog timestamp without a year, e.g. `Mar  1 12:00:00`.
+    Syslog,
+    /// A time of day only, e.g. `12:00:00.250`.
+    Clock,
+}
+
+/// An error returned when [`elapsed_between`] cannot read its timestamps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampError {
+    /// The input does not match the requested format.
+    Unrecognized(String),
+    /// One timestamp carries a date and the other is a time of day only.
+    Mismatched,
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampError::Unrecognized(input) => write!(f, "unrecognized timestamp \"{}\"", input),
+            TimestampError::Mismatched => {
+                write!(f, "cannot compare a time of day with a full timestamp")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimestampError {}
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// A parsed timestamp: nanoseconds since the Unix epoch, or since midnight for `Clock`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Timestamp {
+    nanos: i128,
+    has_date: bool,
+}
+
+/// Returns the number of days from 1970-01-01 to the given civil date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// A cursor over the bytes of a timestamp.
+struct Scanner<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    /// Reads exactly `len` ASCII digits.
+    fn digits(&mut self, len: usize) -> Option<u32> {
+        let digits = self.rest.get(..len)?;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        self.rest = &self.rest[len..];
+        digits.parse().ok()
+    }
+
+    /// Reads one or two digits, as in syslog's space-padded day.
+    fn short_number(&mut self) -> Option<u32> {
+        let len = self.rest.bytes().take(2).take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None;
+        }
+        self.digits(len)
+    }
+
+    /// Consumes `c` if it comes next.
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consumes one of `choices`, returning `None` if none comes next.
+    fn one_of(&mut self, choices: &[char]) -> Option<()> {
+        choices.iter().any(|c| self.eat(*c)).then_some(())
+    }
+
+    /// Reads a three-letter English month abbreviation, returning 1 to 12.
+    fn month_name(&mut self) -> Option<u32> {
+        let name = self.rest.get(..3)?;
+        let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(name))?;
+        self.rest = &self.rest[3..];
+        Some(month as u32 + 1)
+    }
+
+    /// Reads `HH:MM:SS` with an optional `.` or `,` fraction, returning nanoseconds.
+    fn clock(&mut self) -> Option<i128> {
+        let hours = self.digits(2)?;
+        self.one_of(&[':'])?;
+        let minutes = self.digits(2)?;
+        self.one_of(&[':'])?;
+        let seconds = self.digits(2)?;
+        if hours > 23 || minutes > 59 || seconds > 60 {
+            return None;
+        }
+        let mut nanos = 0;
+        if self.eat('.') || self.eat(',') {
+            let len = self.rest.bytes().take_while(u8::is_ascii_digit).count();
+            if len == 0 {
+                return None;
+            }
+            // Digits past nanosecond precision are dropped.
+            let kept = len.min(9);
+            nanos = self.digits(kept)? as i128 * 10i128.pow(9 - kept as u32);
+            self.rest = &self.rest[len - kept..];
+        }
+        let secs = (hours * 3600 + minutes * 60 + seconds) as i128;
+        Some(secs * NANOS_PER_SEC + nanos)
+    }
+
+    /// Reads a `±HH:MM` or `±HHMM` offset, returning seconds east of UTC.
+    fn offset(&mut self) -> Option<i64> {
+        let sign = if self.eat('+') {
+            1
+        } else if self.eat('-') {
+            -1
+        } else {
+            return None;
+        };
+        let hours = self.digits(2)?;
+        self.eat(':');
+        let minutes = self.digits(2)?;
+        Some(sign * (hours as i64 * 3600 + minutes as i64 * 60))
+    }
+
+    /// Reads a `YYYY-MM-DD` date, returning days since the epoch.
+    fn date(&mut self) -> Option<i64> {
+        let year = self.digits(4)?;
+        self.one_of(&['-'])?;
+        let month = self.digits(2)?;
+        self.one_of(&['-'])?;
+        let day = self.digits(2)?;
+        civil_days(year as i64, month, day)
+    }
+
+    /// Returns `true` if nothing follows, or the next character ends a word, as the space
+    /// after a timestamp at the start of a log line does.
+    #[cfg(feature = "regex")]
+    fn at_boundary(&self) -> bool {
+        !self.rest.starts_with(|c: char| c.is_alphanumeric())
+    }
+}
+
+/// Returns the number of days in `month` of `year`, or 0 for an invalid month.
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// Validates a date and converts it to days since the epoch.
+fn civil_days(year: i64, month: u32, day: u32) -> Option<i64> {
+    if day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+fn at(days: i64, clock: i128, offset: i64) -> Timestamp {
+    Timestamp {
+        nanos: (days * SECS_PER_DAY - offset) as i128 * NANOS_PER_SEC + clock,
+        has_date: true,
+    }
+}
+
+fn parse_rfc3339(s: &mut Scanner<'_>) -> Option<Timestamp> {
+    let days = s.date()?;
+    s.one_of(&['T', 't', ' '])?;
+    let clock = s.clock()?;
+    let offset = if s.eat('Z') || s.eat('z') { 0 } else { s.offset()? };
+    Some(at(days, clock, offset))
+}
+
+fn parse_date_time(s: &mut Scanner<'_>) -> Option<Timestamp> {
+    let days = s.date()?;
+    s.one_of(&['T', ' '])?;
+    let clock = s.clock()?;
+    Some(at(days, clock, 0))
+}
+
+fn parse_clf(s: &mut Scanner<'_>) -> Option<Timestamp> {
+    let day = s.digits(2)?;
+    s.one_of(&['/'])?;
+    let month = s.month_name()?;
+    s.one_of(&['/'])?;
+    let year = s.digits(4)?;
+    s.one_of(&[':'])?;
+    let clock = s.clock()?;
+    s.one_of(&[' '])?;
+    let offset = s.offset()?;
+    Some(at(civil_days(year as i64, month, day)?, clock, offset))
+}
+
+fn parse_syslog(s: &mut Scanner<'_>) -> Option<Timestamp> {
+    let month = s.month_name()?;
+    s.one_of(&[' '])?;
+    s.eat(' ');
+    let day = s.short_number()?;
+    s.one_of(&[' '])?;
+    let clock = s.clock()?;
+    // Syslog omits the year; a leap year keeps "Feb 29" valid and only matters within a year.
+    Some(at(civil_days(2000, month, day)?, clock, 0))
+}
+
+fn parse_clock(s: &mut Scanner<'_>) -> Option<Timestamp> {
+    let nanos = s.clock()?;
+    Some(Timestamp {
+        nanos,
+        has_date: false,
+    })
+}
+
+/// A parser for one timestamp layout, reading from the start of the scanner.
+type Parser = fn(&mut Scanner<'_>) -> Option<Timestamp>;
+
+/// Returns the parsers to try for `format`, in order.
+fn parsers(format: TimestampFormat) -> &'static [Parser] {
+    match format {
+        TimestampFormat::Auto => &[
+            parse_rfc3339,
+            parse_date_time,
+            parse_clf,
+            parse_syslog,
+            parse_clock,
+        ],
+        TimestampFormat::Rfc3339 => &[parse_rfc3339],
+        TimestampFormat::DateTime => &[parse_date_time],
+        TimestampFormat::Clf => &[parse_clf],
+        TimestampFormat::Syslog => &[parse_syslog],
+        TimestampFormat::Clock => &[parse_clock],
+    }
+}
+
+/// Parses `input` as `format`, ignoring surrounding whitespace and square brackets.
+fn parse_timestamp(input: &str, format: TimestampFormat) -> Result<Timestamp, TimestampError> {
+    let trimmed = input.trim();
+    let trimmed = trimmed
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or(trimmed);
+    parsers(format)
+        .iter()
+        .find_map(|parse| {
+            let mut scanner = Scanner { rest: trimmed };
+            parse(&mut scanner).filter(|_| scanner.rest.is_empty())
+        })
+        .ok_or_else(|| TimestampError::Unrecognized(input.to_string()))
+}
+
+/// Reads the timestamp that starts a log line, as in `"2024-03-01 12:00:00,250 INFO
+/// ..."` or `"[01/Mar/2024:12:00:00 +0000] GET ..."`, if there is one.
+#[cfg(feature = "regex")]
+pub(crate) fn leading_timestamp(line: &str, format: TimestampFormat) -> Option<Timestamp> {
+    let line = line.trim_start();
+    let line = line.strip_prefix('[').unwrap_or(line);
+    parsers(format).iter().find_map(|parse| {
+        let mut scanner = Scanner { rest: line };
+        parse(&mut scanner).filter(|_| scanner.at_boundary())
+    })
+}
+
+/// Returns the time from `a` to `b` in nanoseconds.
+///
+/// Times of day only are assumed to cross midnight when `b` is earlier than `a`.
+pub(crate) fn nanos_between(a: Timestamp, b: Timestamp) -> Result<i128, TimestampError> {
+    if a.has_date != b.has_date {
+        return Err(TimestampError::Mismatched);
+    }
+    let mut nanos = b.nanos - a.nanos;
+    if !a.has_date && nanos < 0 {
+        nanos += SECS_PER_DAY as i128 * NANOS_PER_SEC;
+    }
+    Ok(nanos)
+}
+
+/// Computes the formatted duration between two timestamps.
+///
+/// Timestamps carrying an offset are compared in UTC; those without one are assumed to
+/// share a time zone. Surrounding square brackets, as in web server logs, are ignored.
+///
+/// # Arguments
+///
+/// * `a` - The earlier timestamp.
+/// * `b` - The later timestamp.
+/// * `format` - The layout of both timestamps, or `TimestampFormat::Auto` to detect it.
+///
+/// # Returns
+///
+/// The formatted time from `a` to `b`, prefixed with `-` if `b` is earlier. When both are
+/// times of day only, `b` is taken to be on the following day if it is earlier than `a`.
+///
+/// # Errors
+///
+/// Returns `TimestampError::Unrecognized` if either timestamp does not match `format`,
+/// and `TimestampError::Mismatched` if only one of them carries a date.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{elapsed_between, TimestampFormat};
+///
+/// let elapsed = elapsed_between(
+///     "2024-03-01T12:00:00Z",
+///     "2024-03-01T14:30:05+02:00",
+///     TimestampFormat::Auto,
+/// );
+/// assert_eq!(elapsed.unwrap(), "30m 5s");
+///
+/// let elapsed = elapsed_between("23:59:58,500", "00:00:01", TimestampFormat::Clock);
+/// assert_eq!(elapsed.unwrap(), "2.500s");
+/// ```
+pub fn elapsed_between(a: &str, b: &str, format: TimestampFormat) -> Result<String, TimestampError> {
+    let nanos = nanos_between(parse_timestamp(a, format)?, parse_timestamp(b, format)?)?;
+    Ok(format_signed_duration(nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn between(a: &str, b: &str) -> String {
+        elapsed_between(a, b, TimestampFormat::Auto).unwrap()
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+
+    #[test]
+    fn test_formats() {
+        assert_eq!(between("2024-02-28T23:00:00Z", "2024-03-01T00:00:00.250z"), "1d 1h 0m 0.250s");
+        assert_eq!(between("2024-03-01 12:00:00,100", "2024-03-01 12:00:01.350"), "1.250s");
+        assert_eq!(
+            between("[01/Mar/2024:12:00:00 +0000]", "[01/Mar/2024:13:00:00 +0100]"),
+            "0s"
+        );
+        assert_eq!(between("Feb 29 23:59:00", "Mar  1 00:01:00"), "2m");
+        assert_eq!(between("12:00:00.123456789123", "12:00:01"), "0.876s");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_leading_timestamp() {
+        let read = |line| leading_timestamp(line, TimestampFormat::Auto);
+        let nanos = |a, b| nanos_between(read(a).unwrap(), read(b).unwrap()).unwrap();
+        assert_eq!(nanos("12:00:00 start job=1", "12:05:00 end job=1"), 300 * NANOS_PER_SEC);
+        assert_eq!(
+            nanos("2024-03-01 12:00:00,250 INFO up", "2024-03-01T12:00:01Z done"),
+            750_000_000
+        );
+        assert_eq!(
+            nanos("[01/Mar/2024:12:00:00 +0000] GET /", "[01/Mar/2024:12:00:02 +0000] GET /"),
+            2 * NANOS_PER_SEC
+        );
+        assert!(read("Mar  1 12:00:00 host cron[1]: job").is_some());
+        assert_eq!(read("job 7 started"), None);
+        assert_eq!(read("12:00:00x"), None);
+    }
+
+    #[test]
+    fn test_negative_and_wrapping() {
+        assert_eq!(between("2024-03-01T12:00:05Z", "2024-03-01T12:00:00Z"), "-5s");
+        assert_eq!(between("23:00:00", "01:00:00"), "2h 0m 0s");
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = elapsed_between("2024-03-01 12:00:00", "soon", TimestampFormat::Auto);
+        assert_eq!(err, Err(TimestampError::Unrecognized("soon".to_string())));
+        let err = elapsed_between("2024-03-01 12:00:00", "12:00:00", TimestampFormat::Auto);
+        assert_eq!(err, Err(TimestampError::Mismatched));
+        // No offset, so not RFC 3339.
+        assert!(elapsed_between("2024-03-01T12:00:00", "2024-03-01T12:00:00Z", TimestampFormat::Rfc3339).is_err());
+        assert!(elapsed_between("2024-13-01 00:00:00", "2024-01-01 00:00:00", TimestampFormat::Auto).is_err());
+        let err = elapsed_between("2024-02-31 00:00:00", "2024-03-01 00:00:00", TimestampFormat::DateTime);
+        assert_eq!(err, Err(TimestampError::Unrecognized("2024-02-31 00:00:00".to_string())));
+        assert!(elapsed_between("2023-02-29T00:00:00Z", "2023-03-01T00:00:00Z", TimestampFormat::Auto).is_err());
+        assert!(elapsed_between("31/Apr/2024:00:00:00 +0000", "01/May/2024:00:00:00 +0000", TimestampFormat::Clf).is_err());
+        assert_eq!(between("2024-02-29 00:00:00", "2024-03-01 00:00:00"), "1d 0h 0m 0s");
+        assert_eq!(between("2000-02-29 00:00:00", "2000-03-01 00:00:00"), "1d 0h 0m 0s");
+        assert!(elapsed_between("1900-02-29 00:00:00", "1900-03-01 00:00:00", TimestampFormat::Auto).is_err());
+    }
+}
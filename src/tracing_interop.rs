@@ -0,0 +1,167 @@
+//! Measurements as `tracing` events and span fields.
+//!
+//! Enabled with the `tracing` feature.
+
+use std::time::Duration;
+
+use tracing::field;
+
+use crate::clock::measure_call;
+use crate::format_duration;
+
+/// Emits an `INFO` event recording that `label` took `elapsed`.
+///
+/// The event's target is `elapsed_time` and its message `"label took 1.300s"`. It carries
+/// the fields `label`, `elapsed` (the formatted duration) and `elapsed_ns`, so subscribers
+/// can filter and aggregate without parsing the message.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use elapsed_time::trace_elapsed;
+///
+/// trace_elapsed("db_query", Duration::from_millis(1_300));
+/// ```
+pub fn trace_elapsed(label: &str, elapsed: Duration) {
+    let formatted = format_duration(elapsed);
+    tracing::info!(
+        target: "elapsed_time",
+        label,
+        elapsed = formatted.as_str(),
+        elapsed_ns = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX),
+        "{} took {}",
+        label,
+        formatted
+    );
+}
+
+/// Runs a closure, emits its elapsed time with [`trace_elapsed`] and returns its result.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_traced;
+///
+/// let rows = measure_traced("load_rows", || vec![1, 2, 3]);
+/// assert_eq!(rows.len(), 3);
+/// ```
+pub fn measure_traced<F, T>(label: &str, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let (result, elapsed) = measure_call(f);
+    trace_elapsed(label, elapsed);
+    result
+}
+
+/// Runs a closure inside an `INFO` span and records its formatted elapsed time on the span.
+///
+/// The span is named `measure`, with target `elapsed_time` and a `label` field. Events the
+/// closure emits are nested under it, and once it returns the span's `elapsed` field is
+/// set to e.g. `"1.300s"`, so the timing appears wherever the span is reported.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::measure_in_span;
+///
+/// let total = measure_in_span("checksum", || {
+///     tracing::debug!("hashing");
+///     (1..=100u64).sum::<u64>()
+/// });
+/// assert_eq!(total, 5050);
+/// ```
+pub fn measure_in_span<F, T>(label: &str, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let span = tracing::info_span!(
+        target: "elapsed_time",
+        "measure",
+        label,
+        elapsed = field::Empty
+    );
+    let (result, elapsed) = span.in_scope(|| measure_call(f));
+    span.record("elapsed", format_duration(elapsed).as_str());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Collects every field recorded on events and spans as `"name=value"`.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        fields: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Visit for Recorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            let entry = format!("{}={:?}", field.name(), value);
+            self.fields.lock().unwrap().push(entry);
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            span.record(&mut self.clone());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut self.clone());
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    fn capture(f: impl FnOnce()) -> Vec<String> {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), f);
+        let fields = recorder.fields.lock().unwrap().clone();
+        fields
+    }
+
+    #[test]
+    fn test_trace_elapsed_fields() {
+        let fields = capture(|| trace_elapsed("db_query", Duration::from_millis(1_300)));
+        assert_eq!(
+            fields,
+            [
+                "message=db_query took 1.300s",
+                "label=\"db_query\"",
+                "elapsed=\"1.300s\"",
+                "elapsed_ns=1300000000",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_measure_in_span_records_elapsed() {
+        let fields = capture(|| {
+            assert_eq!(measure_in_span("work", || 42), 42);
+        });
+        assert_eq!(fields[0], "label=\"work\"");
+        assert!(fields[1].starts_with("elapsed=\"") && fields[1].ends_with("s\""));
+        assert_eq!(fields.len(), 2);
+    }
+}
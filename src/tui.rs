@@ -0,0 +1,219 @@
+//! [ratatui](https://ratatui.rs) widgets for rendering timings inside terminal UIs.
+//!
+//! Enabled with the `ratatui` feature.
+
+use std::time::{Duration, Instant};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, Widget};
+
+use crate::format_duration;
+
+/// A widget showing a single elapsed time, such as a running stopwatch.
+///
+/// Build a fresh widget on every frame; [`running_since`](StopwatchWidget::running_since)
+/// reads the clock when it is called, so redrawing keeps the value live.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Instant;
+/// use ratatui::buffer::Buffer;
+/// use ratatui::layout::Rect;
+/// use ratatui::widgets::{Block, Widget};
+/// use elapsed_time::tui::StopwatchWidget;
+///
+/// let start = Instant::now();
+/// let mut buf = Buffer::empty(Rect::new(0, 0, 20, 3));
+/// StopwatchWidget::running_since(start)
+///     .block(Block::bordered().title("build"))
+///     .render(buf.area, &mut buf);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StopwatchWidget<'a> {
+    elapsed: Duration,
+    block: Option<Block<'a>>,
+    style: Style,
+}
+
+impl<'a> StopwatchWidget<'a> {
+    /// Creates a widget displaying `elapsed`.
+    pub fn new(elapsed: Duration) -> Self {
+        StopwatchWidget {
+            elapsed,
+            block: None,
+            style: Style::default(),
+        }
+    }
+
+    /// Creates a widget displaying the time since `start`, measured now.
+    pub fn running_since(start: Instant) -> Self {
+        Self::new(start.elapsed())
+    }
+
+    /// Wraps the widget in a block, e.g. for a border and title.
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Sets the style of the elapsed-time text.
+    pub fn style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.style = style.into();
+        self
+    }
+}
+
+impl Widget for StopwatchWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut paragraph = Paragraph::new(format_duration(self.elapsed)).style(self.style);
+        if let Some(block) = self.block {
+            paragraph = paragraph.block(block);
+        }
+        paragraph.render(area, buf);
+    }
+}
+
+/// One line of a [`TimingTable`]: a label with its call count and total time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimingRow {
+    pub label: String,
+    pub count: u64,
+    pub total: Duration,
+}
+
+impl TimingRow {
+    /// Creates a row for `label` that was measured `count` times for `total` time.
+    pub fn new(label: impl Into<String>, count: u64, total: Duration) -> Self {
+        TimingRow {
+            label: label.into(),
+            count,
+            total,
+        }
+    }
+
+    /// Returns the mean time per call, or zero when there were no calls.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64)
+    }
+}
+
+/// A table widget listing labelled timings with their call count, total and mean.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use ratatui::buffer::Buffer;
+/// use ratatui::layout::Rect;
+/// use ratatui::widgets::Widget;
+/// use elapsed_time::tui::{TimingRow, TimingTable};
+///
+/// let rows = vec![
+///     TimingRow::new("db_query", 12, Duration::from_millis(4_800)),
+///     TimingRow::new("render", 3, Duration::from_millis(900)),
+/// ];
+/// let mut buf = Buffer::empty(Rect::new(0, 0, 60, 5));
+/// TimingTable::new(rows).render(buf.area, &mut buf);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TimingTable<'a> {
+    rows: Vec<TimingRow>,
+    block: Option<Block<'a>>,
+    header_style: Style,
+}
+
+impl<'a> TimingTable<'a> {
+    /// Creates a table showing `rows` in the given order.
+    pub fn new(rows: impl IntoIterator<Item = TimingRow>) -> Self {
+        TimingTable {
+            rows: rows.into_iter().collect(),
+            block: None,
+            header_style: Style::default().add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Wraps the table in a block, e.g. for a border and title.
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Sets the style of the header row. Defaults to bold.
+    pub fn header_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.header_style = style.into();
+        self
+    }
+}
+
+impl Widget for TimingTable<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let header = Row::new(["Label", "Calls", "Total", "Mean"]).style(self.header_style);
+        let rows = self.rows.iter().map(|row| {
+            Row::new([
+                Cell::from(row.label.clone()),
+                Cell::from(row.count.to_string()),
+                Cell::from(format_duration(row.total)),
+                Cell::from(format_duration(row.mean())),
+            ])
+        });
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(8),
+            Constraint::Length(16),
+            Constraint::Length(16),
+        ];
+        let mut table = Table::new(rows, widths).header(header);
+        if let Some(block) = self.block {
+            table = table.block(block);
+        }
+        Widget::render(table, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(buf: &Buffer) -> Vec<String> {
+        let area = buf.area;
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stopwatch_widget() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 1));
+        StopwatchWidget::new(Duration::from_secs(125)).render(buf.area, &mut buf);
+        assert_eq!(lines(&buf), ["2m 5s"]);
+    }
+
+    #[test]
+    fn test_timing_table() {
+        let rows = [
+            TimingRow::new("db_query", 4, Duration::from_secs(2)),
+            TimingRow::new("idle", 0, Duration::ZERO),
+        ];
+        let mut buf = Buffer::empty(Rect::new(0, 0, 52, 3));
+        TimingTable::new(rows).render(buf.area, &mut buf);
+        let lines = lines(&buf);
+        assert!(lines[0].starts_with("Label"));
+        assert!(lines[1].starts_with("db_query"));
+        assert!(lines[1].contains(" 4 "));
+        assert!(lines[1].contains("2s"));
+        assert!(lines[1].ends_with("0.500s"));
+        assert!(lines[2].ends_with("0s"));
+    }
+}
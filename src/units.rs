@@ -0,0 +1,203 @@
+use core::time::Duration;
+
+const SECS_PER_MINUTE: u64 = 60;
+const SECS_PER_HOUR: u64 = 60 * SECS_PER_MINUTE;
+const SECS_PER_DAY: u64 = 24 * SECS_PER_HOUR;
+const SECS_PER_WEEK: u64 = 7 * SECS_PER_DAY;
+
+/// Creates a `Duration` of the given number of milliseconds.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{format_duration, millis};
+///
+/// assert_eq!(format_duration(millis(1500)), "1.500s");
+/// ```
+pub const fn millis(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+/// Creates a `Duration` of the given number of seconds.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{format_duration, secs};
+///
+/// const RETRY_DELAY: std::time::Duration = secs(90);
+/// assert_eq!(format_duration(RETRY_DELAY), "1m 30s");
+/// ```
+pub const fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+/// Creates a `Duration` of the given number of minutes.
+///
+/// Saturates at `u64::MAX` seconds instead of overflowing.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{format_duration, mins};
+///
+/// assert_eq!(format_duration(mins(5)), "5m");
+/// ```
+pub const fn mins(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(SECS_PER_MINUTE))
+}
+
+/// Creates a `Duration` of the given number of hours.
+///
+/// Saturates at `u64::MAX` seconds instead of overflowing.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{format_duration, hours};
+///
+/// assert_eq!(format_duration(hours(2)), "2h 0m 0s");
+/// ```
+pub const fn hours(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(SECS_PER_HOUR))
+}
+
+/// Creates a `Duration` of the given number of days.
+///
+/// Saturates at `u64::MAX` seconds instead of overflowing.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{days, format_duration};
+///
+/// assert_eq!(format_duration(days(3)), "3d 0h 0m 0s");
+/// ```
+pub const fn days(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(SECS_PER_DAY))
+}
+
+/// Creates a `Duration` of the given number of weeks.
+///
+/// Saturates at `u64::MAX` seconds instead of overflowing.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{format_duration, weeks};
+///
+/// assert_eq!(format_duration(weeks(1)), "1w 0d 0h 0m 0s");
+/// ```
+pub const fn weeks(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(SECS_PER_WEEK))
+}
+
+macro_rules! unit_newtype {
+    ($(#[$meta:meta])* $name:ident, $constructor:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub u64);
+
+        impl From<$name> for Duration {
+            fn from(value: $name) -> Duration {
+                $constructor(value.0)
+            }
+        }
+
+        impl core::fmt::Display for $name {
+            /// Formats the quantity with [`format_duration`](crate::format_duration).
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(&crate::format_duration(Duration::from(*self)))
+            }
+        }
+    };
+}
+
+unit_newtype!(
+    /// A whole number of seconds.
+    ///
+    /// Use this in signatures that must be given seconds, so a caller can't pass
+    /// milliseconds by accident.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::Seconds;
+    ///
+    /// fn set_timeout(timeout: impl Into<Duration>) -> Duration {
+    ///     timeout.into()
+    /// }
+    ///
+    /// assert_eq!(set_timeout(Seconds(90)), Duration::from_secs(90));
+    /// assert_eq!(Seconds(90).to_string(), "1m 30s");
+    /// ```
+    Seconds,
+    secs
+);
+
+unit_newtype!(
+    /// A whole number of minutes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::Minutes;
+    ///
+    /// assert_eq!(Duration::from(Minutes(5)), Duration::from_secs(300));
+    /// assert_eq!(Minutes(5).to_string(), "5m");
+    /// ```
+    Minutes,
+    mins
+);
+
+unit_newtype!(
+    /// A whole number of hours.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use elapsed_time::Hours;
+    ///
+    /// assert_eq!(Duration::from(Hours(2)), Duration::from_secs(7200));
+    /// assert_eq!(Hours(26).to_string(), "1d 2h 0m 0s");
+    /// ```
+    Hours,
+    hours
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constructors() {
+        assert_eq!(millis(250), Duration::from_millis(250));
+        assert_eq!(secs(90), Duration::from_secs(90));
+        assert_eq!(mins(5), Duration::from_secs(300));
+        assert_eq!(hours(2), Duration::from_secs(7200));
+        assert_eq!(days(1), Duration::from_secs(86400));
+        assert_eq!(weeks(1), Duration::from_secs(604800));
+    }
+
+    #[test]
+    fn test_constructors_saturate() {
+        assert_eq!(weeks(u64::MAX), Duration::from_secs(u64::MAX));
+        assert_eq!(mins(u64::MAX / 2), Duration::from_secs(u64::MAX));
+    }
+
+    #[test]
+    fn test_unit_newtypes() {
+        assert_eq!(Duration::from(Seconds(45)), Duration::from_secs(45));
+        assert_eq!(Duration::from(Minutes(2)), Duration::from_secs(120));
+        assert_eq!(Duration::from(Hours(1)), Duration::from_secs(3600));
+
+        assert_eq!(Seconds(45).to_string(), "45s");
+        assert_eq!(Minutes(2).to_string(), "2m");
+        assert_eq!(Hours(1).to_string(), "1h 0m 0s");
+
+        assert!(Seconds(59) < Seconds(60));
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::format_duration;
+
+/// When the program started, as far as this crate knows.
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Anchors the program's start time now, unless it has already been anchored, and returns
+/// it.
+///
+/// The anchor is otherwise set by the first call to [`uptime`] or [`uptime_formatted`].
+/// Call this first thing in `main` so uptime counts from program start rather than from the
+/// first log line that asks for it.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{init_uptime, uptime};
+///
+/// let started = init_uptime();
+/// assert_eq!(init_uptime(), started);
+/// assert!(uptime() <= started.elapsed());
+/// ```
+pub fn init_uptime() -> Instant {
+    *START.get_or_init(Instant::now)
+}
+
+/// Returns how long the program has been running, counted from [`init_uptime`].
+pub fn uptime() -> Duration {
+    init_uptime().elapsed()
+}
+
+/// Returns how long the program has been running, formatted with [`format_duration`], for
+/// prefixing log lines.
+///
+/// # Example
+///
+/// ```
+/// use elapsed_time::{init_uptime, uptime_formatted};
+///
+/// init_uptime();
+/// // ... much later ...
+/// eprintln!("[+{}] cache warmed", uptime_formatted()); // e.g. "[+2m 13s] cache warmed"
+/// ```
+pub fn uptime_formatted() -> String {
+    format_duration(uptime())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uptime_is_anchored_once() {
+        let started = init_uptime();
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(init_uptime(), started);
+        let up = uptime();
+        assert!(up >= Duration::from_millis(10));
+        assert!(up <= started.elapsed());
+        assert!(!uptime_formatted().is_empty());
+    }
+}
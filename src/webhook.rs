@@ -0,0 +1,215 @@
+//! A reporter that POSTs measurements as JSON to a webhook URL.
+//!
+//! Enabled with the `webhook` feature.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::format_duration;
+use crate::json::push_string;
+
+/// How long a webhook request may take before it is abandoned.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Decides which measurements get reported, given their label and duration.
+type Filter = Arc<dyn Fn(&str, Duration) -> bool + Send + Sync>;
+
+/// An error returned when a webhook request fails.
+#[derive(Debug)]
+pub struct WebhookError(Box<ureq::Error>);
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "webhook request failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for WebhookError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+impl From<ureq::Error> for WebhookError {
+    fn from(error: ureq::Error) -> Self {
+        WebhookError(Box::new(error))
+    }
+}
+
+/// Sends measurements matching a filter to a URL as a JSON payload.
+///
+/// Each report is a `POST` with a body like:
+///
+/// ```json
+/// {"label":"nightly-etl","duration_ns":4320000000000,"formatted":"1h 12m 0s","metadata":{"host":"db-1"}}
+/// ```
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use elapsed_time::webhook::WebhookReporter;
+///
+/// let reporter = WebhookReporter::new("https://automation.example.com/hooks/jobs")
+///     .filter(|_, duration| duration > Duration::from_secs(60))
+///     .metadata("host", "db-1");
+///
+/// reporter.report("nightly-etl", Duration::from_secs(4320))?;
+/// # Ok::<(), elapsed_time::webhook::WebhookError>(())
+/// ```
+#[derive(Clone)]
+pub struct WebhookReporter {
+    url: String,
+    filter: Filter,
+    metadata: Vec<(String, String)>,
+    agent: ureq::Agent,
+}
+
+impl WebhookReporter {
+    /// Creates a reporter posting every measurement to `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        WebhookReporter {
+            url: url.into(),
+            filter: Arc::new(|_, _| true),
+            metadata: Vec::new(),
+            agent: ureq::AgentBuilder::new().timeout(DEFAULT_TIMEOUT).build(),
+        }
+    }
+
+    /// Only reports measurements for which `filter(label, duration)` returns `true`.
+    pub fn filter<P>(mut self, filter: P) -> Self
+    where
+        P: Fn(&str, Duration) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Arc::new(filter);
+        self
+    }
+
+    /// Adds a key/value pair sent in every payload's `metadata` object.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets how long each request may take. Defaults to 10 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.agent = ureq::AgentBuilder::new().timeout(timeout).build();
+        self
+    }
+
+    /// Returns `true` if a measurement passes the filter.
+    pub fn matches(&self, label: &str, duration: Duration) -> bool {
+        (self.filter)(label, duration)
+    }
+
+    /// Builds the JSON payload that would be sent for a measurement.
+    pub fn payload(&self, label: &str, duration: Duration) -> String {
+        let mut out = String::from("{\"label\":");
+        push_string(&mut out, label);
+        out.push_str(&format!(",\"duration_ns\":{},\"formatted\":", duration.as_nanos()));
+        push_string(&mut out, &format_duration(duration));
+        out.push_str(",\"metadata\":{");
+        for (i, (key, value)) in self.metadata.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            push_string(&mut out, key);
+            out.push(':');
+            push_string(&mut out, value);
+        }
+        out.push_str("}}");
+        out
+    }
+
+    /// Posts a measurement to the webhook if it passes the filter.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the measurement was sent, `Ok(false)` if the filter skipped it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WebhookError` if the request could not be sent or the server answered
+    /// with an error status.
+    pub fn report(&self, label: &str, duration: Duration) -> Result<bool, WebhookError> {
+        if !self.matches(label, duration) {
+            return Ok(false);
+        }
+        self.agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&self.payload(label, duration))?;
+        Ok(true)
+    }
+}
+
+impl fmt::Debug for WebhookReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookReporter")
+            .field("url", &self.url)
+            .field("metadata", &self.metadata)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Accepts one HTTP request, answers 200 and returns the request body.
+    fn serve_one(listener: TcpListener) -> thread::JoinHandle<String> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8(body).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_payload() {
+        let reporter = WebhookReporter::new("http://localhost/")
+            .metadata("host", "db-1")
+            .metadata("run", "42");
+        assert_eq!(
+            reporter.payload("nightly \"etl\"", Duration::from_millis(1500)),
+            r#"{"label":"nightly \"etl\"","duration_ns":1500000000,"formatted":"1.500s","metadata":{"host":"db-1","run":"42"}}"#
+        );
+    }
+
+    #[test]
+    fn test_report_posts_matching_measurements() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = serve_one(listener);
+
+        let reporter = WebhookReporter::new(url).filter(|label, _| label.starts_with("job"));
+        assert!(!reporter.report("other", Duration::from_secs(1)).unwrap());
+        assert!(reporter.report("job-a", Duration::from_secs(2)).unwrap());
+
+        assert_eq!(
+            server.join().unwrap(),
+            r#"{"label":"job-a","duration_ns":2000000000,"formatted":"2s","metadata":{}}"#
+        );
+    }
+}